@@ -0,0 +1,475 @@
+//! Minimal client for the Docker Engine API, which Podman also serves, over
+//! the engine's local Unix socket. Only the few GET endpoints the container
+//! tools need are spoken, using HTTP/1.0 so every response simply ends when
+//! the engine closes the connection.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Upper bound for one request; stats with `stream=false` take about 1-2s.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sockets to try, in order: `DOCKER_HOST` / `CONTAINER_HOST` when they name
+/// a `unix://` socket, the rootful Docker socket, then rootless and rootful
+/// Podman.
+pub fn socket_candidates() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = ["DOCKER_HOST", "CONTAINER_HOST"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .filter_map(|host| host.strip_prefix("unix://").map(PathBuf::from))
+        .collect();
+    candidates.push(PathBuf::from("/var/run/docker.sock"));
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        candidates.push(PathBuf::from(runtime_dir).join("podman/podman.sock"));
+    }
+    candidates.push(PathBuf::from("/run/podman/podman.sock"));
+    candidates
+}
+
+#[derive(Debug, Clone)]
+pub struct Engine {
+    socket: PathBuf,
+}
+
+impl Engine {
+    /// An engine listening on `socket`, which is not checked until the first
+    /// request.
+    pub fn new(socket: PathBuf) -> Self {
+        Self { socket }
+    }
+
+    /// Uses the first candidate socket that exists.
+    pub fn locate() -> Result<Self, String> {
+        socket_candidates()
+            .into_iter()
+            .find(|path| path.exists())
+            .map(Self::new)
+            .ok_or_else(|| {
+                "No Docker or Podman socket found (tried DOCKER_HOST, /var/run/docker.sock, \
+                 $XDG_RUNTIME_DIR/podman/podman.sock, /run/podman/podman.sock)"
+                    .to_string()
+            })
+    }
+
+    pub fn socket(&self) -> &std::path::Path {
+        &self.socket
+    }
+
+    /// Issues `GET path` and returns the body of a 2xx response. Engine
+    /// errors are returned as their `message`.
+    pub async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        tokio::time::timeout(REQUEST_TIMEOUT, self.request(path))
+            .await
+            .map_err(|_| format!("Engine did not answer {} within {}s", path, REQUEST_TIMEOUT.as_secs()))?
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        let body = self.get(path).await?;
+        serde_json::from_slice(&body).map_err(|e| format!("Unexpected engine response for {}: {}", path, e))
+    }
+
+    #[cfg(unix)]
+    async fn request(&self, path: &str) -> Result<Vec<u8>, String> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::UnixStream::connect(&self.socket).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                format!(
+                    "Permission denied on {} (join the docker group or use the rootless socket)",
+                    self.socket.display()
+                )
+            } else {
+                format!("Cannot connect to {}: {}", self.socket.display(), e)
+            }
+        })?;
+        let request = format!("GET {} HTTP/1.0\r\nHost: localhost\r\nUser-Agent: rmcp-containers\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+        parse_response(&response)
+    }
+
+    #[cfg(not(unix))]
+    async fn request(&self, _path: &str) -> Result<Vec<u8>, String> {
+        Err("Container engine sockets are only supported on Unix".to_string())
+    }
+}
+
+fn parse_response(response: &[u8]) -> Result<Vec<u8>, String> {
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("Malformed HTTP response from engine")?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let mut body = response[split + 4..].to_vec();
+
+    let mut lines = head.lines();
+    let status: u16 = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or("Malformed HTTP status line from engine")?;
+    let chunked = lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+    if chunked {
+        body = dechunk(&body)?;
+    }
+
+    if (200..300).contains(&status) {
+        Ok(body)
+    } else {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            message: String,
+        }
+        let message = serde_json::from_slice::<ErrorBody>(&body)
+            .map(|e| e.message)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&body).trim().to_string());
+        Err(format!("Engine returned {}: {}", status, message))
+    }
+}
+
+/// Joins the chunks of a chunked body up to the terminating empty chunk. A
+/// cut-off or garbled body is an error rather than silently shortened JSON.
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    const TRUNCATED: &str = "Truncated chunked response from engine";
+    let mut out = Vec::new();
+    loop {
+        let end = body.windows(2).position(|w| w == b"\r\n").ok_or(TRUNCATED)?;
+        let size_field = String::from_utf8_lossy(&body[..end]);
+        let size_field = size_field.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_field, 16)
+            .map_err(|_| format!("Malformed chunk size '{}' in engine response", size_field))?;
+        if size == 0 {
+            return Ok(out);
+        }
+        let start = end + 2;
+        out.extend_from_slice(body.get(start..start.saturating_add(size)).ok_or(TRUNCATED)?);
+        body = &body[(start + size + 2).min(body.len())..];
+    }
+}
+
+/// Log output of containers without a TTY is multiplexed into frames with
+/// an 8-byte header (stream, 0, 0, 0, big-endian length). TTY output is raw.
+/// A frame cut short by the engine keeps what arrived.
+pub fn demux_logs(body: &[u8]) -> String {
+    let framed = body.len() >= 8 && body[0] <= 2 && body[1..4] == [0, 0, 0];
+    if !framed {
+        return String::from_utf8_lossy(body).to_string();
+    }
+    let mut out = Vec::new();
+    let mut rest = body;
+    while rest.len() >= 8 {
+        let size = u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let end = (8 + size).min(rest.len());
+        out.extend_from_slice(&rest[8..end]);
+        rest = &rest[end..];
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Container names and IDs are passed straight into request paths, so only
+/// what the engine allows in them is accepted: `[a-zA-Z0-9][a-zA-Z0-9_.-]*`.
+pub fn is_valid_reference(reference: &str) -> bool {
+    reference.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && reference
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerSummary {
+    pub id: String,
+    #[serde(default)]
+    pub names: Vec<String>,
+    pub image: String,
+    pub state: String,
+    /// Human status such as "Up 3 hours (healthy)" or "Exited (0) 2 days ago".
+    pub status: String,
+    #[serde(default)]
+    pub ports: Vec<Port>,
+    #[serde(default)]
+    pub created: i64,
+}
+
+impl ContainerSummary {
+    pub fn name(&self) -> &str {
+        self.names.first().map(|n| n.trim_start_matches('/')).unwrap_or(&self.id)
+    }
+
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+
+    /// Health check state parsed from the status text, if the container
+    /// has a health check.
+    pub fn health(&self) -> Option<&'static str> {
+        if self.status.contains("(unhealthy)") {
+            Some("unhealthy")
+        } else if self.status.contains("(healthy)") {
+            Some("healthy")
+        } else if self.status.contains("(health: starting)") {
+            Some("starting")
+        } else {
+            None
+        }
+    }
+
+    /// Published ports as `host:port->port/proto`, unpublished ones as
+    /// `port/proto`, without the duplicate IPv6 bindings.
+    pub fn port_list(&self) -> Vec<String> {
+        let mut ports: Vec<String> = self
+            .ports
+            .iter()
+            .map(|p| match p.public_port {
+                Some(public) => format!(
+                    "{}:{}->{}/{}",
+                    p.ip.as_deref().filter(|ip| !ip.is_empty()).unwrap_or("0.0.0.0"),
+                    public,
+                    p.private_port,
+                    p.kind
+                ),
+                None => format!("{}/{}", p.private_port, p.kind),
+            })
+            .filter(|p| !p.starts_with("::"))
+            .collect();
+        ports.sort();
+        ports.dedup();
+        ports
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Port {
+    #[serde(rename = "IP")]
+    pub ip: Option<String>,
+    #[serde(rename = "PrivatePort")]
+    pub private_port: u16,
+    #[serde(rename = "PublicPort")]
+    pub public_port: Option<u16>,
+    #[serde(rename = "Type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Stats {
+    #[serde(default)]
+    pub cpu_stats: CpuStats,
+    #[serde(default)]
+    pub precpu_stats: CpuStats,
+    #[serde(default)]
+    pub memory_stats: MemoryStats,
+    #[serde(default)]
+    pub networks: Option<HashMap<String, NetworkStats>>,
+    #[serde(default)]
+    pub blkio_stats: BlkioStats,
+    #[serde(default)]
+    pub pids_stats: PidsStats,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CpuStats {
+    #[serde(default)]
+    pub cpu_usage: CpuUsage,
+    pub system_cpu_usage: Option<u64>,
+    pub online_cpus: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CpuUsage {
+    #[serde(default)]
+    pub total_usage: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MemoryStats {
+    pub usage: Option<u64>,
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub stats: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NetworkStats {
+    #[serde(default)]
+    pub rx_bytes: u64,
+    #[serde(default)]
+    pub tx_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BlkioStats {
+    #[serde(default)]
+    pub io_service_bytes_recursive: Option<Vec<BlkioEntry>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BlkioEntry {
+    #[serde(default)]
+    pub op: String,
+    #[serde(default)]
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PidsStats {
+    pub current: Option<u64>,
+}
+
+impl Stats {
+    /// CPU usage in percent of one core, the way `docker stats` shows it.
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let cpu = self
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .checked_sub(self.precpu_stats.cpu_usage.total_usage)?;
+        let system = self.cpu_stats.system_cpu_usage?.checked_sub(self.precpu_stats.system_cpu_usage?)?;
+        if system == 0 {
+            return None;
+        }
+        let cpus = self.cpu_stats.online_cpus.unwrap_or(1) as f64;
+        Some(cpu as f64 / system as f64 * cpus * 100.0)
+    }
+
+    /// Memory in use without the reclaimable page cache (`inactive_file` on
+    /// cgroup v2, `cache` on v1), matching `docker stats`.
+    pub fn memory_used(&self) -> Option<u64> {
+        let usage = self.memory_stats.usage?;
+        let cache = ["inactive_file", "total_inactive_file", "cache"]
+            .iter()
+            .find_map(|key| self.memory_stats.stats.get(*key))
+            .copied()
+            .unwrap_or(0);
+        Some(usage.saturating_sub(cache))
+    }
+
+    pub fn network_totals(&self) -> (u64, u64) {
+        self.networks
+            .iter()
+            .flatten()
+            .fold((0, 0), |(rx, tx), (_, n)| (rx + n.rx_bytes, tx + n.tx_bytes))
+    }
+
+    pub fn block_totals(&self) -> (u64, u64) {
+        self.blkio_stats
+            .io_service_bytes_recursive
+            .iter()
+            .flatten()
+            .fold((0, 0), |(read, written), entry| match entry.op.to_lowercase().as_str() {
+                "read" => (read + entry.value, written),
+                "write" => (read, written + entry.value),
+                _ => (read, written),
+            })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers one request on a Unix socket with `response` and hands back
+    /// the request head it received.
+    fn serve(response: Vec<u8>) -> (tempfile::TempDir, Engine, tokio::task::JoinHandle<String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("engine.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                let mut byte = [0];
+                if stream.read(&mut byte).await.unwrap() == 0 {
+                    break;
+                }
+                request.push(byte[0]);
+            }
+            stream.write_all(&response).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (dir, Engine::new(socket), server)
+    }
+
+    fn frame(stream: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![stream, 0, 0, 0];
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[tokio::test]
+    async fn lists_containers() {
+        let body = r#"[{"Id":"4f2a9c0d1e3b5a7c9e1f","Names":["/web"],"Image":"nginx:1.27","State":"running",
+            "Status":"Up 3 hours (healthy)","Created":1700000000,"Ports":[
+            {"IP":"0.0.0.0","PrivatePort":80,"PublicPort":8080,"Type":"tcp"},
+            {"IP":"::","PrivatePort":80,"PublicPort":8080,"Type":"tcp"},
+            {"PrivatePort":443,"Type":"tcp"}]}]"#;
+        let response = format!("HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{}", body);
+        let (_dir, engine, server) = serve(response.into_bytes());
+
+        let containers: Vec<ContainerSummary> = engine.get_json("/containers/json?all=true").await.unwrap();
+        assert!(server.await.unwrap().starts_with("GET /containers/json?all=true HTTP/1.0\r\n"));
+        let web = &containers[0];
+        assert_eq!((web.name(), web.short_id(), web.health()), ("web", "4f2a9c0d1e3b", Some("healthy")));
+        assert_eq!(web.port_list(), ["0.0.0.0:8080->80/tcp", "443/tcp"]);
+    }
+
+    #[tokio::test]
+    async fn joins_chunked_bodies() {
+        let response = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+            e\r\n{\"pids_stats\":\r\n5;ext=1\r\n{\"cur\r\na\r\nrent\":7}}\n\r\n0\r\n\r\n";
+        let (_dir, engine, _server) = serve(response.as_bytes().to_vec());
+
+        let stats: Stats = engine.get_json("/containers/web/stats?stream=false").await.unwrap();
+        assert_eq!(stats.pids_stats.current, Some(7));
+    }
+
+    #[tokio::test]
+    async fn demultiplexes_logs() {
+        let mut response = b"HTTP/1.0 200 OK\r\nContent-Type: application/vnd.docker.raw-stream\r\n\r\n".to_vec();
+        response.extend(frame(1, b"listening on :80\n"));
+        response.extend(frame(2, b"warning: no TLS\n"));
+        response.extend(frame(1, b"GET / 200\n"));
+        let (_dir, engine, _server) = serve(response);
+
+        let body = engine.get("/containers/web/logs?stdout=true&stderr=true").await.unwrap();
+        assert_eq!(demux_logs(&body), "listening on :80\nwarning: no TLS\nGET / 200\n");
+    }
+
+    #[tokio::test]
+    async fn reports_engine_errors() {
+        let response = "HTTP/1.0 404 Not Found\r\n\r\n{\"message\":\"No such container: db\"}";
+        let (_dir, engine, _server) = serve(response.as_bytes().to_vec());
+        assert_eq!(engine.get("/containers/db/json").await.unwrap_err(), "Engine returned 404: No such container: db");
+
+        let (_dir, engine, _server) = serve(b"garbage".to_vec());
+        assert_eq!(engine.get("/_ping").await.unwrap_err(), "Malformed HTTP response from engine");
+
+        let missing = Engine::new(PathBuf::from("/nonexistent/engine.sock"));
+        assert!(missing.get("/_ping").await.unwrap_err().starts_with("Cannot connect to /nonexistent/engine.sock"));
+    }
+
+    #[test]
+    fn rejects_malformed_chunks() {
+        assert_eq!(dechunk(b"3;name=value\r\nabc\r\n0\r\n\r\n").unwrap(), b"abc");
+        assert_eq!(dechunk(b"zz\r\nabc\r\n0\r\n\r\n").unwrap_err(), "Malformed chunk size 'zz' in engine response");
+        assert_eq!(dechunk(b"10\r\nabc").unwrap_err(), "Truncated chunked response from engine");
+        assert_eq!(dechunk(b"3\r\nabc\r\n").unwrap_err(), "Truncated chunked response from engine");
+        assert!(dechunk(b"ffffffffffffffff\r\nabc\r\n").is_err());
+    }
+
+    #[test]
+    fn keeps_truncated_frames() {
+        let mut body = frame(1, b"complete\n");
+        body.extend(&frame(2, b"cut off here")[..12]);
+        assert_eq!(demux_logs(&body), "complete\ncut ");
+        assert_eq!(demux_logs(b"plain tty output\n"), "plain tty output\n");
+    }
+}
@@ -0,0 +1,277 @@
+pub mod engine;
+
+use engine::{ContainerSummary, Engine, Stats};
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
+    model::*,
+    ErrorData as McpError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub struct ContainersServer {
+    pub tool_router: ToolRouter<Self>,
+}
+
+impl Default for ContainersServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ListContainersParams {
+    #[schemars(description = "Include stopped containers (default false)")]
+    #[serde(default)]
+    pub all: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerStatsParams {
+    #[schemars(description = "Container name or ID (default: all running containers)")]
+    #[serde(default)]
+    pub container: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerLogsParams {
+    #[schemars(description = "Container name or ID")]
+    pub container: String,
+    #[schemars(description = "Number of most recent log lines (default 100, max 2000)")]
+    #[serde(default)]
+    pub tail: Option<usize>,
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else if seconds < 86400 {
+        format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
+    } else {
+        format!("{}d {}h", seconds / 86400, (seconds % 86400) / 3600)
+    }
+}
+
+fn engine() -> Result<Engine, McpError> {
+    Engine::locate().map_err(|e| McpError::internal_error(e, None))
+}
+
+fn check_reference(container: &str) -> Result<(), McpError> {
+    if engine::is_valid_reference(container) {
+        Ok(())
+    } else {
+        Err(McpError::invalid_params(
+            format!("Invalid container name or ID '{}'", container),
+            None,
+        ))
+    }
+}
+
+/// One line of `docker stats`-like numbers.
+fn format_stats(stats: &Stats) -> String {
+    let mut parts = Vec::new();
+    if let Some(cpu) = stats.cpu_percent() {
+        parts.push(format!("CPU {:.1}%", cpu));
+    }
+    if let Some(used) = stats.memory_used() {
+        match stats.memory_stats.limit.filter(|limit| *limit > 0) {
+            Some(limit) => parts.push(format!(
+                "memory {} / {} ({:.1}%)",
+                format_bytes(used),
+                format_bytes(limit),
+                used as f64 / limit as f64 * 100.0
+            )),
+            None => parts.push(format!("memory {}", format_bytes(used))),
+        }
+    }
+    let (rx, tx) = stats.network_totals();
+    if stats.networks.is_some() {
+        parts.push(format!("net {} in / {} out", format_bytes(rx), format_bytes(tx)));
+    }
+    let (read, written) = stats.block_totals();
+    if read > 0 || written > 0 {
+        parts.push(format!("block {} read / {} written", format_bytes(read), format_bytes(written)));
+    }
+    if let Some(pids) = stats.pids_stats.current {
+        parts.push(format!("{} pids", pids));
+    }
+    parts.join(", ")
+}
+
+#[rmcp::tool_router]
+impl ContainersServer {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[rmcp::tool(description = "List Docker/Podman containers with image, state, health, published ports and uptime")]
+    pub async fn list_containers(
+        &self,
+        Parameters(params): Parameters<ListContainersParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let engine = engine()?;
+        let all = params.all.unwrap_or(false);
+        let containers: Vec<ContainerSummary> = engine
+            .get_json(&format!("/containers/json?all={}", all as u8))
+            .await
+            .map_err(|e| McpError::internal_error(e, None))?;
+
+        let mut output = format!("Containers ({}):\n\n", engine.socket().display());
+
+        if containers.is_empty() {
+            output.push_str(if all { "No containers.\n" } else { "No running containers.\n" });
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        for c in &containers {
+            output.push_str(&format!("{} ({})\n", c.name(), c.image));
+            output.push_str(&format!("  ID: {}\n", c.short_id()));
+            output.push_str(&format!("  State: {} - {}\n", c.state, c.status));
+            if c.created > 0 {
+                output.push_str(&format!("  Created: {} ago\n", format_duration(now.saturating_sub(c.created) as u64)));
+            }
+            if let Some(health) = c.health() {
+                output.push_str(&format!("  Health: {}\n", health));
+            }
+            let ports = c.port_list();
+            if !ports.is_empty() {
+                output.push_str(&format!("  Ports: {}\n", ports.join(", ")));
+            }
+            output.push('\n');
+        }
+
+        let unhealthy = containers.iter().filter(|c| c.health() == Some("unhealthy")).count();
+        output.push_str(&format!("Total: {} container(s)", containers.len()));
+        if unhealthy > 0 {
+            output.push_str(&format!(", {} unhealthy", unhealthy));
+        }
+        output.push('\n');
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get resource usage of running containers (CPU, memory against limit, network, \
+        block I/O, pids), like 'docker stats'")]
+    pub async fn get_container_stats(
+        &self,
+        Parameters(params): Parameters<ContainerStatsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let engine = engine()?;
+        let targets: Vec<String> = match &params.container {
+            Some(container) => {
+                check_reference(container)?;
+                vec![container.clone()]
+            }
+            None => engine
+                .get_json::<Vec<ContainerSummary>>("/containers/json")
+                .await
+                .map_err(|e| McpError::internal_error(e, None))?
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+        };
+
+        // The engine takes a second or two per container to compute CPU
+        // deltas, so ask for all of them at once.
+        let mut requests = tokio::task::JoinSet::new();
+        for (i, name) in targets.iter().enumerate() {
+            let engine = engine.clone();
+            let path = format!("/containers/{}/stats?stream=false", name);
+            requests.spawn(async move { (i, engine.get_json::<Stats>(&path).await) });
+        }
+        let mut results = Vec::new();
+        while let Some(result) = requests.join_next().await {
+            if let Ok(result) = result {
+                results.push(result);
+            }
+        }
+        results.sort_by_key(|(i, _)| *i);
+
+        if params.container.is_some() {
+            if let Some((_, Err(e))) = results.first() {
+                return Err(McpError::internal_error(e.clone(), None));
+            }
+        }
+
+        let mut output = String::from("Container Resource Usage:\n\n");
+        if targets.is_empty() {
+            output.push_str("No running containers.\n");
+        }
+        for (i, result) in results {
+            match result {
+                Ok(stats) => output.push_str(&format!("{}: {}\n", targets[i], format_stats(&stats))),
+                Err(e) => output.push_str(&format!("{}: {}\n", targets[i], e)),
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get the most recent log lines (stdout and stderr) of a container")]
+    pub async fn get_container_logs(
+        &self,
+        Parameters(params): Parameters<ContainerLogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        check_reference(&params.container)?;
+        let engine = engine()?;
+        let tail = params.tail.unwrap_or(100).clamp(1, 2000);
+        let body = engine
+            .get(&format!(
+                "/containers/{}/logs?stdout=1&stderr=1&tail={}",
+                params.container, tail
+            ))
+            .await
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let logs = engine::demux_logs(&body);
+
+        let mut output = format!("Logs of {} (last {} lines):\n\n", params.container, tail);
+        if logs.trim().is_empty() {
+            output.push_str("(no output)\n");
+        } else {
+            output.push_str(&logs);
+            if !logs.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+}
+
+#[rmcp::tool_handler]
+impl ServerHandler for ContainersServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some("Docker and Podman container server - containers, resource usage, logs".into()),
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! Recent file changes below the directories listed in
+//! `RMCP_SENSORS_WATCH_DIRS`, recorded by a `notify` watcher (inotify on
+//! Linux) into a bounded in-memory log. Paths that git ignores and anything
+//! inside `.git` are dropped as they arrive. On Linux every subdirectory
+//! costs an inotify watch, including ignored ones like `target/`; raise
+//! `fs.inotify.max_user_watches` for very large trees.
+
+use chrono::{DateTime, Local};
+use git2::Repository;
+use notify::event::{CreateKind, EventKind, ModifyKind, RemoveKind, RenameMode};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const WATCH_DIRS_ENV: &str = "RMCP_SENSORS_WATCH_DIRS";
+
+/// The oldest events are dropped beyond this many.
+pub const MAX_EVENTS: usize = 20_000;
+
+/// Files recorded from one directory appearing at once (a moved-in tree or
+/// an unpacked archive).
+const MAX_NEW_FILES: usize = 1_000;
+
+/// Longest look-back window a query may ask for.
+pub const MAX_WINDOW_SECS: u64 = 7 * 86_400;
+
+/// Parses a look-back window like `90s`, `10m`, `2h` or `1d`, capped at
+/// [`MAX_WINDOW_SECS`].
+pub fn parse_window(input: &str) -> Result<chrono::Duration, String> {
+    let s = input.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let invalid = || format!("Could not parse window '{}' (try '90s', '10m', '2h' or '1d')", input);
+    let mult: u64 = match unit.trim() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+        "d" | "day" | "days" => 86_400,
+        _ => return Err(invalid()),
+    };
+    if num.is_empty() {
+        return Err(invalid());
+    }
+    // All digits, so a parse failure means a number past u64 and the cap.
+    let n = num.parse::<u64>().unwrap_or(u64::MAX);
+    let secs = n.saturating_mul(mult).min(MAX_WINDOW_SECS);
+    Ok(chrono::Duration::seconds(secs as i64))
+}
+
+/// Directories from `RMCP_SENSORS_WATCH_DIRS` (a `PATH`-style list).
+pub fn configured_dirs() -> Vec<PathBuf> {
+    std::env::var_os(WATCH_DIRS_ENV)
+        .map(|list| std::env::split_paths(&list).filter(|p| !p.as_os_str().is_empty()).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl Change {
+    pub fn name(self) -> &'static str {
+        match self {
+            Change::Created => "created",
+            Change::Modified => "modified",
+            Change::Deleted => "deleted",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FileEvent {
+    path: PathBuf,
+    change: Change,
+    at: DateTime<Local>,
+}
+
+/// What happened to one path over a time window.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    pub path: PathBuf,
+    /// Net effect: a file created and then edited is "created", one deleted
+    /// and written again is "modified".
+    pub change: Change,
+    pub first: DateTime<Local>,
+    pub last: DateTime<Local>,
+    pub events: usize,
+}
+
+/// Git ignore rules for the repositories events land in, looked up once
+/// per directory.
+#[derive(Default)]
+struct IgnoreRules {
+    /// Directory -> working tree of the repository containing it.
+    workdirs: HashMap<PathBuf, Option<PathBuf>>,
+    repos: HashMap<PathBuf, Repository>,
+}
+
+impl IgnoreRules {
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        if path.components().any(|c| c.as_os_str() == ".git") {
+            return true;
+        }
+        let Some(dir) = path.parent() else { return false };
+        if self.workdirs.len() > 10_000 {
+            self.workdirs.clear();
+        }
+        let workdir = self
+            .workdirs
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let repo = Repository::discover(dir).ok()?;
+                let workdir = repo.workdir()?.to_path_buf();
+                self.repos.entry(workdir.clone()).or_insert(repo);
+                Some(workdir)
+            })
+            .clone();
+        let Some(workdir) = workdir else { return false };
+        let (Some(repo), Ok(relative)) = (self.repos.get(&workdir), path.strip_prefix(&workdir)) else {
+            return false;
+        };
+        repo.is_path_ignored(relative).unwrap_or(false)
+    }
+}
+
+fn classify(kind: &EventKind) -> Option<Change> {
+    match kind {
+        EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder) => None,
+        // inotify also reports both halves of a rename on their own.
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => None,
+        EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => Some(Change::Created),
+        EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => Some(Change::Deleted),
+        // Permission and timestamp changes are noise here.
+        EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => Some(Change::Modified),
+        _ => None,
+    }
+}
+
+/// Files in a newly created directory tree, up to `MAX_NEW_FILES`.
+fn files_below(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => pending.push(entry.path()),
+                Ok(_) => files.push(entry.path()),
+                Err(_) => {}
+            }
+            if files.len() >= MAX_NEW_FILES {
+                return files;
+            }
+        }
+    }
+    files
+}
+
+pub struct FileWatcher {
+    dirs: Vec<PathBuf>,
+    failed: Vec<(PathBuf, String)>,
+    started: DateTime<Local>,
+    log: Arc<Mutex<VecDeque<FileEvent>>>,
+    // Dropping the watcher stops it.
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl std::fmt::Debug for FileWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileWatcher")
+            .field("dirs", &self.dirs)
+            .field("failed", &self.failed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FileWatcher {
+    /// Starts watching the configured directories, or returns `None` when
+    /// none are configured.
+    pub fn from_env() -> Option<Self> {
+        let dirs = configured_dirs();
+        (!dirs.is_empty()).then(|| Self::start(dirs))
+    }
+
+    /// Watches `dirs` recursively. Directories that cannot be watched are
+    /// kept in `failed` with the reason.
+    pub fn start(dirs: Vec<PathBuf>) -> Self {
+        let log: Arc<Mutex<VecDeque<FileEvent>>> = Arc::default();
+        let sink = log.clone();
+        let rules = Mutex::new(IgnoreRules::default());
+        let handler = move |result: notify::Result<notify::Event>| {
+            let Ok(event) = result else { return };
+            let mut rules = rules.lock().unwrap_or_else(|e| e.into_inner());
+            let mut changes: Vec<(PathBuf, Change)> = match event.kind {
+                // Files written into a new directory before the watcher
+                // reached it raise no events of their own.
+                EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To))
+                    if event.paths.iter().any(|p| p.is_dir()) =>
+                {
+                    event
+                        .paths
+                        .iter()
+                        .filter(|p| !rules.is_ignored(p))
+                        .flat_map(|p| files_below(p))
+                        .map(|p| (p, Change::Created))
+                        .collect()
+                }
+                ref kind => match classify(kind) {
+                    Some(change) => event.paths.iter().map(|p| (p.clone(), change)).collect(),
+                    None => return,
+                },
+            };
+            // New directories show up as creations when the event kind is vague.
+            changes.retain(|(path, change)| (*change == Change::Deleted || !path.is_dir()) && !rules.is_ignored(path));
+            drop(rules);
+            let now = Local::now();
+            let mut log = sink.lock().unwrap_or_else(|e| e.into_inner());
+            for (path, change) in changes {
+                if log.len() >= MAX_EVENTS {
+                    log.pop_front();
+                }
+                log.push_back(FileEvent { path, change, at: now });
+            }
+        };
+
+        let mut watched = Vec::new();
+        let mut failed = Vec::new();
+        let watcher = match notify::recommended_watcher(handler) {
+            Ok(mut watcher) => {
+                for dir in dirs {
+                    // Events carry paths under the name the directory was watched by.
+                    let dir = dir.canonicalize().unwrap_or(dir);
+                    match watcher.watch(&dir, RecursiveMode::Recursive) {
+                        Ok(()) => watched.push(dir),
+                        Err(e) => failed.push((dir, e.to_string())),
+                    }
+                }
+                Some(watcher)
+            }
+            Err(e) => {
+                failed.extend(dirs.into_iter().map(|dir| (dir, e.to_string())));
+                None
+            }
+        };
+
+        Self {
+            dirs: watched,
+            failed,
+            started: Local::now(),
+            log,
+            _watcher: watcher,
+        }
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    pub fn failed(&self) -> &[(PathBuf, String)] {
+        &self.failed
+    }
+
+    /// When recording began; nothing earlier is known.
+    pub fn started(&self) -> DateTime<Local> {
+        self.started
+    }
+
+    /// The watched directory `path` lies in, if any.
+    pub fn root_of(&self, path: &Path) -> Option<&Path> {
+        self.dirs.iter().map(PathBuf::as_path).find(|dir| path.starts_with(dir))
+    }
+
+    /// Paths (below `under`, if given) that changed since `since`, most
+    /// recent first. Files created and deleted again within the window are
+    /// left out.
+    pub fn activity(&self, under: Option<&Path>, since: DateTime<Local>) -> Vec<Activity> {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        let mut by_path: HashMap<&Path, Activity> = HashMap::new();
+        for event in log.iter().filter(|e| e.at >= since && under.is_none_or(|u| e.path.starts_with(u))) {
+            let Some(activity) = by_path.get_mut(event.path.as_path()) else {
+                by_path.insert(
+                    &event.path,
+                    Activity {
+                        path: event.path.clone(),
+                        change: event.change,
+                        first: event.at,
+                        last: event.at,
+                        events: 1,
+                    },
+                );
+                continue;
+            };
+            let change = match (activity.change, event.change) {
+                (Change::Created, Change::Deleted) => {
+                    by_path.remove(event.path.as_path());
+                    continue;
+                }
+                (Change::Created, Change::Modified) => Change::Created,
+                (Change::Deleted, Change::Created) => Change::Modified,
+                (_, change) => change,
+            };
+            activity.change = change;
+            activity.last = event.at;
+            activity.events += 1;
+        }
+        let mut activity: Vec<Activity> = by_path.into_values().collect();
+        activity.sort_by(|a, b| b.last.cmp(&a.last).then_with(|| a.path.cmp(&b.path)));
+        activity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_window_units() {
+        assert_eq!(parse_window("90s").unwrap().num_seconds(), 90);
+        assert_eq!(parse_window("10m").unwrap().num_seconds(), 600);
+        assert_eq!(parse_window(" 2 hours ").unwrap().num_seconds(), 7200);
+        assert!(parse_window("10").is_err());
+        assert!(parse_window("m").is_err());
+        assert!(parse_window("-5m").is_err());
+    }
+
+    #[test]
+    fn parse_window_is_capped() {
+        assert_eq!(parse_window("30d").unwrap().num_seconds() as u64, MAX_WINDOW_SECS);
+        assert_eq!(parse_window("99999999999999999999999d").unwrap().num_seconds() as u64, MAX_WINDOW_SECS);
+    }
+}
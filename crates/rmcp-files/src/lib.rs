@@ -0,0 +1,191 @@
+pub mod file_activity;
+
+use chrono::{DateTime, Local};
+use file_activity::{Change, FileWatcher};
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
+    model::*,
+    ErrorData as McpError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct FilesServer {
+    pub tool_router: ToolRouter<Self>,
+    watcher: Option<FileWatcher>,
+}
+
+impl Default for FilesServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FileActivityParams {
+    #[schemars(description = "Only files below this path, which must be inside a watched directory \
+        (default: all watched directories)")]
+    #[serde(default)]
+    pub path: Option<String>,
+    #[schemars(description = "How far back to look: '10m' (default), '2h' or '1d'; at most 7 days")]
+    #[serde(default)]
+    pub within: Option<String>,
+    #[schemars(description = "Maximum number of files to list, newest first (default 50, max 500)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else if seconds < 86400 {
+        format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
+    } else {
+        format!("{}d {}h", seconds / 86400, (seconds % 86400) / 3600)
+    }
+}
+
+fn format_event_time(time: DateTime<Local>) -> String {
+    if time.date_naive() == Local::now().date_naive() {
+        time.format("%H:%M:%S").to_string()
+    } else {
+        time.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+#[rmcp::tool_router]
+impl FilesServer {
+    /// Starts watching the directories in `RMCP_SENSORS_WATCH_DIRS`.
+    pub fn new() -> Self {
+        let watcher = FileWatcher::from_env();
+        for (dir, reason) in watcher.iter().flat_map(|w| w.failed()) {
+            tracing::warn!("Not watching {}: {}", dir.display(), reason);
+        }
+        Self {
+            tool_router: Self::tool_router(),
+            watcher,
+        }
+    }
+
+    #[rmcp::tool(description = "List files created, modified or deleted recently in the watched project \
+        directories (RMCP_SENSORS_WATCH_DIRS), newest first, skipping git-ignored paths. Shows what the user \
+        touched lately rather than what differs from HEAD")]
+    pub async fn get_recent_file_activity(
+        &self,
+        Parameters(params): Parameters<FileActivityParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let watcher = self.watcher.as_ref().ok_or_else(|| {
+            McpError::internal_error(
+                "File activity watching is disabled. Set RMCP_SENSORS_WATCH_DIRS to the directories to watch.",
+                None,
+            )
+        })?;
+        let watched: Vec<_> = watcher.dirs().iter().map(|d| d.display().to_string()).collect();
+
+        let under = match params.path.as_deref() {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let path = path.canonicalize().unwrap_or(path);
+                if watcher.root_of(&path).is_none() {
+                    return Err(McpError::invalid_params(
+                        format!(
+                            "'{}' is not inside a watched directory (watching: {})",
+                            path.display(),
+                            if watched.is_empty() { "none".to_string() } else { watched.join(", ") }
+                        ),
+                        None,
+                    ));
+                }
+                Some(path)
+            }
+            None => None,
+        };
+
+        let within = file_activity::parse_window(params.within.as_deref().unwrap_or("10m"))
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let since = Local::now() - within;
+        let limit = params.limit.unwrap_or(50).clamp(1, 500);
+        let activity = watcher.activity(under.as_deref(), since);
+        let count = |change: Change| activity.iter().filter(|a| a.change == change).count();
+
+        let mut output = format!(
+            "File activity in the last {} ({}):\n",
+            format_duration(within.num_seconds() as u64),
+            under.as_ref().map(|u| u.display().to_string()).unwrap_or_else(|| watched.join(", "))
+        );
+        if since < watcher.started() {
+            output.push_str(&format!(
+                "Recording started at {}; earlier changes are unknown.\n",
+                format_event_time(watcher.started())
+            ));
+        }
+        output.push('\n');
+
+        if activity.is_empty() {
+            output.push_str("No changes.\n");
+        }
+        let mut current_root = None;
+        for entry in activity.iter().take(limit) {
+            // Paths are shown relative to their watched directory.
+            let root = watcher.root_of(&entry.path);
+            if watched.len() > 1 && root != current_root {
+                if let Some(root) = root {
+                    output.push_str(&format!("{}:\n", root.display()));
+                }
+                current_root = root;
+            }
+            let shown = match root {
+                Some(root) => entry.path.strip_prefix(root).unwrap_or(&entry.path),
+                None => &entry.path,
+            };
+            output.push_str(&format!(
+                "  {}  {:<8}  {}",
+                format_event_time(entry.last),
+                entry.change.name(),
+                shown.display()
+            ));
+            if entry.change == Change::Modified && entry.events > 1 {
+                output.push_str(&format!(
+                    " ({} writes since {})",
+                    entry.events,
+                    format_event_time(entry.first)
+                ));
+            }
+            output.push('\n');
+        }
+        if activity.len() > limit {
+            output.push_str(&format!("  ... and {} more\n", activity.len() - limit));
+        }
+
+        output.push_str(&format!(
+            "\n{} file(s): {} created, {} modified, {} deleted\n",
+            activity.len(),
+            count(Change::Created),
+            count(Change::Modified),
+            count(Change::Deleted)
+        ));
+        for (dir, reason) in watcher.failed() {
+            output.push_str(&format!("Not watched: {} ({})\n", dir.display(), reason));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+}
+
+#[rmcp::tool_handler]
+impl ServerHandler for FilesServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some("File activity server - recent changes in watched project directories".into()),
+        }
+    }
+}
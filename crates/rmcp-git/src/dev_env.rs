@@ -0,0 +1,633 @@
+//! Project type and toolchain detection for a directory: build manifests
+//! (Cargo.toml, package.json, pyproject.toml, go.mod...) with their
+//! workspace members and pinned versions, and the versions of the matching
+//! compilers and package managers found on `PATH`. Version commands run in
+//! the project directory so rustup and version-manager shims report the
+//! toolchain that directory would actually get.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Version commands still running after this are killed.
+pub const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An executable to report the version of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tool {
+    /// Names tried in order; the first one on `PATH` is used.
+    pub names: &'static [&'static str],
+    pub args: &'static [&'static str],
+    /// Left out when not installed rather than reported missing.
+    pub optional: bool,
+}
+
+const fn tool(names: &'static [&'static str], args: &'static [&'static str]) -> Tool {
+    Tool { names, args, optional: false }
+}
+
+const RUSTC: Tool = tool(&["rustc"], &["--version"]);
+const CARGO: Tool = tool(&["cargo"], &["--version"]);
+const RUSTUP: Tool = Tool {
+    names: &["rustup"],
+    args: &["show", "active-toolchain"],
+    optional: true,
+};
+const NODE: Tool = tool(&["node"], &["--version"]);
+const PYTHON: Tool = tool(&["python3", "python"], &["--version"]);
+const GO: Tool = tool(&["go"], &["version"]);
+const JAVA: Tool = tool(&["java"], &["-version"]);
+
+/// Reported when no project is found.
+pub const DEFAULT_TOOLS: &[Tool] = &[RUSTC, CARGO, NODE, PYTHON, GO];
+
+/// Build files recognised by name only: (file, kind, build command, tools).
+const MARKERS: &[(&str, &str, &str, &[Tool])] = &[
+    ("deno.json", "Deno", "deno task", &[tool(&["deno"], &["--version"])]),
+    ("CMakeLists.txt", "CMake", "cmake -B build && cmake --build build", &[tool(&["cmake"], &["--version"])]),
+    ("meson.build", "Meson", "meson setup build && meson compile -C build", &[tool(&["meson"], &["--version"])]),
+    ("build.gradle", "Gradle", "gradle build", &[JAVA]),
+    ("build.gradle.kts", "Gradle", "gradle build", &[JAVA]),
+    ("pom.xml", "Maven", "mvn package", &[tool(&["mvn"], &["--version"]), JAVA]),
+    ("Gemfile", "Ruby", "bundle install", &[tool(&["ruby"], &["--version"])]),
+    ("composer.json", "PHP", "composer install", &[tool(&["php"], &["--version"])]),
+    ("mix.exs", "Elixir", "mix compile", &[tool(&["elixir"], &["--version"])]),
+    ("flake.nix", "Nix", "nix build", &[tool(&["nix"], &["--version"])]),
+    ("Makefile", "Make", "make", &[tool(&["make"], &["--version"])]),
+];
+
+/// Files that pin a toolchain version for the directory.
+const PIN_FILES: &[&str] = &[
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    ".nvmrc",
+    ".node-version",
+    ".python-version",
+    ".go-version",
+    ".tool-versions",
+];
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    /// Relative to the workspace root.
+    pub path: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    /// Ecosystem, e.g. "Rust" or "Node.js".
+    pub kind: &'static str,
+    pub file: PathBuf,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Edition, required runtime version, package manager and the like.
+    pub facts: Vec<(&'static str, String)>,
+    pub members: Vec<Member>,
+    pub dependencies: Option<usize>,
+    /// Commands that build the project, in order.
+    pub build: Vec<String>,
+    pub tools: Vec<Tool>,
+}
+
+impl Manifest {
+    fn new(kind: &'static str, file: PathBuf) -> Self {
+        Self {
+            kind,
+            file,
+            name: None,
+            version: None,
+            facts: Vec::new(),
+            members: Vec::new(),
+            dependencies: None,
+            build: Vec::new(),
+            tools: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// The directory the manifests were found in: the requested one or its
+    /// nearest ancestor with a manifest.
+    pub root: PathBuf,
+    pub manifests: Vec<Manifest>,
+    /// (file, pinned version) for each pin file in the root.
+    pub pins: Vec<(&'static str, String)>,
+}
+
+impl Project {
+    /// Tools of every manifest, without duplicates, or the defaults when
+    /// there is no manifest.
+    pub fn tools(&self) -> Vec<Tool> {
+        if self.manifests.is_empty() {
+            return DEFAULT_TOOLS.to_vec();
+        }
+        let mut tools: Vec<Tool> = Vec::new();
+        for tool in self.manifests.iter().flat_map(|m| &m.tools) {
+            if !tools.contains(tool) {
+                tools.push(*tool);
+            }
+        }
+        tools
+    }
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn toml_str(table: Option<&toml::Table>, key: &str) -> Option<String> {
+    table?.get(key)?.as_str().map(str::to_string)
+}
+
+fn toml_table<'a>(table: Option<&'a toml::Table>, key: &str) -> Option<&'a toml::Table> {
+    table?.get(key)?.as_table()
+}
+
+fn toml_strings(table: Option<&toml::Table>, key: &str) -> Vec<String> {
+    table
+        .and_then(|t| t.get(key))
+        .and_then(toml::Value::as_array)
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+fn json_str(value: &serde_json::Value, pointer: &str) -> Option<String> {
+    value.pointer(pointer)?.as_str().map(str::to_string)
+}
+
+/// Matches one path component against a pattern with `*` wildcards.
+fn component_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else { return false };
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+/// Directories below `root` matching workspace `patterns` like
+/// `crates/*` that contain `manifest`, minus `exclude`. `**` is treated
+/// like `*`.
+fn expand_members(root: &Path, patterns: &[String], exclude: &[String], manifest: &str) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    for pattern in patterns {
+        let mut dirs = vec![root.to_path_buf()];
+        for component in pattern.trim_start_matches("./").split('/').filter(|c| !c.is_empty() && *c != ".") {
+            if !component.contains('*') {
+                dirs = dirs.into_iter().map(|d| d.join(component)).collect();
+                continue;
+            }
+            dirs = dirs
+                .iter()
+                .filter_map(|d| fs::read_dir(d).ok())
+                .flat_map(|entries| entries.flatten())
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .filter(|e| component_matches(component, &e.file_name().to_string_lossy()))
+                .map(|e| e.path())
+                .collect();
+        }
+        for dir in dirs {
+            let relative = dir.strip_prefix(root).unwrap_or(&dir).to_string_lossy().into_owned();
+            if dir.join(manifest).is_file() && !exclude.contains(&relative) && !found.contains(&dir) {
+                found.push(dir);
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+fn members(root: &Path, dirs: Vec<PathBuf>, name: impl Fn(&Path) -> Option<String>) -> Vec<Member> {
+    dirs.into_iter()
+        .map(|dir| {
+            let path = dir.strip_prefix(root).unwrap_or(&dir).to_string_lossy().into_owned();
+            Member {
+                path: if path.is_empty() { ".".to_string() } else { path },
+                name: name(&dir),
+            }
+        })
+        .collect()
+}
+
+fn rust(dir: &Path) -> Option<Manifest> {
+    let file = dir.join("Cargo.toml");
+    let table = read_toml(&file)?;
+    let mut manifest = Manifest::new("Rust", file);
+    let package = toml_table(Some(&table), "package");
+    manifest.name = toml_str(package, "name");
+    manifest.version = toml_str(package, "version");
+    if let Some(edition) = toml_str(package, "edition") {
+        manifest.facts.push(("edition", edition));
+    }
+    if let Some(rust_version) = toml_str(package, "rust-version") {
+        manifest.facts.push(("minimum Rust", rust_version));
+    }
+    manifest.dependencies = toml_table(Some(&table), "dependencies").map(|d| d.len());
+
+    let workspace = toml_table(Some(&table), "workspace");
+    if workspace.is_some() {
+        let dirs = expand_members(
+            dir,
+            &toml_strings(workspace, "members"),
+            &toml_strings(workspace, "exclude"),
+            "Cargo.toml",
+        );
+        manifest.members = members(dir, dirs, |d| {
+            toml_str(toml_table(read_toml(&d.join("Cargo.toml")).as_ref(), "package"), "name")
+        });
+    } else if let Some(root) = dir.ancestors().skip(1).find(|a| {
+        read_toml(&a.join("Cargo.toml")).is_some_and(|t| t.contains_key("workspace"))
+    }) {
+        manifest.facts.push(("workspace root", root.display().to_string()));
+    }
+
+    let whole_workspace = package.is_none() || !manifest.members.is_empty();
+    manifest.build.push(if whole_workspace { "cargo build --workspace" } else { "cargo build" }.to_string());
+    manifest.tools = vec![RUSTC, CARGO, RUSTUP];
+    Some(manifest)
+}
+
+fn node(dir: &Path) -> Option<Manifest> {
+    let file = dir.join("package.json");
+    let json = read_json(&file)?;
+    let mut manifest = Manifest::new("Node.js", file);
+    manifest.name = json_str(&json, "/name");
+    manifest.version = json_str(&json, "/version");
+    if let Some(engine) = json_str(&json, "/engines/node") {
+        manifest.facts.push(("node required", engine));
+    }
+
+    let declared = json_str(&json, "/packageManager");
+    let manager = if dir.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if dir.join("yarn.lock").exists() {
+        "yarn"
+    } else if dir.join("bun.lockb").exists() || dir.join("bun.lock").exists() {
+        "bun"
+    } else {
+        match declared.as_deref().and_then(|d| d.split('@').next()) {
+            Some("pnpm") => "pnpm",
+            Some("yarn") => "yarn",
+            Some("bun") => "bun",
+            _ => "npm",
+        }
+    };
+    manifest.facts.push(("package manager", declared.unwrap_or_else(|| manager.to_string())));
+
+    let scripts: Vec<String> = json
+        .get("scripts")
+        .and_then(|s| s.as_object())
+        .map(|s| s.keys().cloned().collect())
+        .unwrap_or_default();
+    if !scripts.is_empty() {
+        manifest.facts.push(("scripts", scripts.join(", ")));
+    }
+    manifest.dependencies = ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|key| json.get(key)?.as_object().map(|d| d.len()))
+        .reduce(|a, b| a + b);
+
+    // "workspaces": [...] or { "packages": [...] }, or pnpm's own file.
+    let mut patterns: Vec<String> = json
+        .get("workspaces")
+        .and_then(|w| w.as_array().or_else(|| w.get("packages")?.as_array()))
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    if let Ok(pnpm) = fs::read_to_string(dir.join("pnpm-workspace.yaml")) {
+        patterns.extend(
+            pnpm.lines()
+                .filter_map(|l| l.trim().strip_prefix('-'))
+                .map(|l| l.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+                .filter(|p| !p.starts_with('!')),
+        );
+    }
+    let dirs = expand_members(dir, &patterns, &[], "package.json");
+    manifest.members = members(dir, dirs, |d| json_str(&read_json(&d.join("package.json"))?, "/name"));
+
+    manifest.build.push(format!("{} install", manager));
+    if scripts.iter().any(|s| s == "build") {
+        manifest.build.push(format!("{} run build", manager));
+    }
+    manifest.tools = vec![NODE];
+    manifest.tools.push(match manager {
+        "pnpm" => tool(&["pnpm"], &["--version"]),
+        "yarn" => tool(&["yarn"], &["--version"]),
+        "bun" => tool(&["bun"], &["--version"]),
+        _ => tool(&["npm"], &["--version"]),
+    });
+    Some(manifest)
+}
+
+fn python(dir: &Path) -> Option<Manifest> {
+    let pyproject = dir.join("pyproject.toml");
+    let table = read_toml(&pyproject);
+    let file = match &table {
+        Some(_) => pyproject,
+        None => ["setup.py", "setup.cfg", "requirements.txt"]
+            .iter()
+            .map(|f| dir.join(f))
+            .find(|f| f.is_file())?,
+    };
+    let mut manifest = Manifest::new("Python", file);
+    let project = toml_table(table.as_ref(), "project");
+    let tool_table = toml_table(table.as_ref(), "tool");
+    let poetry = toml_table(tool_table, "poetry");
+    manifest.name = toml_str(project, "name").or_else(|| toml_str(poetry, "name"));
+    manifest.version = toml_str(project, "version").or_else(|| toml_str(poetry, "version"));
+    if let Some(requires) = toml_str(project, "requires-python") {
+        manifest.facts.push(("python required", requires));
+    }
+    if let Some(backend) = toml_str(toml_table(table.as_ref(), "build-system"), "build-backend") {
+        manifest.facts.push(("build backend", backend));
+    }
+    manifest.dependencies = project
+        .and_then(|p| p.get("dependencies")?.as_array().map(|d| d.len()))
+        .or_else(|| toml_table(poetry, "dependencies").map(|d| d.keys().filter(|k| *k != "python").count()));
+
+    let manager = if dir.join("uv.lock").exists() || tool_table.is_some_and(|t| t.contains_key("uv")) {
+        "uv"
+    } else if dir.join("poetry.lock").exists() || poetry.is_some() {
+        "poetry"
+    } else if dir.join("pdm.lock").exists() {
+        "pdm"
+    } else {
+        "pip"
+    };
+    manifest.facts.push(("package manager", manager.to_string()));
+    if let Some(venv) = [".venv", "venv"].iter().find(|v| dir.join(v).join("pyvenv.cfg").is_file()) {
+        manifest.facts.push(("virtualenv", venv.to_string()));
+    }
+
+    let uv_workspace = toml_table(toml_table(tool_table, "uv"), "workspace");
+    if uv_workspace.is_some() {
+        let dirs = expand_members(
+            dir,
+            &toml_strings(uv_workspace, "members"),
+            &toml_strings(uv_workspace, "exclude"),
+            "pyproject.toml",
+        );
+        manifest.members = members(dir, dirs, |d| {
+            toml_str(toml_table(read_toml(&d.join("pyproject.toml")).as_ref(), "project"), "name")
+        });
+    }
+
+    manifest.build.push(match manager {
+        "pip" if table.is_none() && manifest.file.ends_with("requirements.txt") => {
+            "pip install -r requirements.txt".to_string()
+        }
+        "pip" => "pip install -e .".to_string(),
+        "uv" => "uv sync".to_string(),
+        other => format!("{} install", other),
+    });
+    manifest.tools = vec![PYTHON];
+    match manager {
+        "uv" => manifest.tools.push(tool(&["uv"], &["--version"])),
+        "poetry" => manifest.tools.push(tool(&["poetry"], &["--version"])),
+        "pdm" => manifest.tools.push(tool(&["pdm"], &["--version"])),
+        _ => {}
+    }
+    Some(manifest)
+}
+
+fn go(dir: &Path) -> Option<Manifest> {
+    let work = fs::read_to_string(dir.join("go.work")).ok();
+    let module = fs::read_to_string(dir.join("go.mod")).ok();
+    if work.is_none() && module.is_none() {
+        return None;
+    }
+    let file = dir.join(if module.is_some() { "go.mod" } else { "go.work" });
+    let mut manifest = Manifest::new("Go", file);
+
+    let mut requires = 0;
+    let mut in_require = false;
+    for line in module.as_deref().unwrap_or("").lines().map(str::trim) {
+        if in_require {
+            if line == ")" {
+                in_require = false;
+            } else if !line.is_empty() && !line.starts_with("//") {
+                requires += 1;
+            }
+        } else if let Some(name) = line.strip_prefix("module ") {
+            manifest.name = Some(name.trim().trim_matches('"').to_string());
+        } else if let Some(version) = line.strip_prefix("go ") {
+            manifest.facts.push(("go required", version.trim().to_string()));
+        } else if let Some(toolchain) = line.strip_prefix("toolchain ") {
+            manifest.facts.push(("toolchain", toolchain.trim().to_string()));
+        } else if line == "require (" {
+            in_require = true;
+        } else if line.starts_with("require ") {
+            requires += 1;
+        }
+    }
+    if module.is_some() {
+        manifest.dependencies = Some(requires);
+    }
+
+    // go.work: `use ./a` or a `use (...)` block.
+    let mut uses = Vec::new();
+    let mut in_use = false;
+    for line in work.as_deref().unwrap_or("").lines().map(str::trim) {
+        if in_use {
+            if line == ")" {
+                in_use = false;
+            } else if !line.is_empty() && !line.starts_with("//") {
+                uses.push(line.to_string());
+            }
+        } else if line == "use (" {
+            in_use = true;
+        } else if let Some(path) = line.strip_prefix("use ") {
+            uses.push(path.trim().to_string());
+        }
+    }
+    let dirs = expand_members(dir, &uses, &[], "go.mod");
+    manifest.members = members(dir, dirs, |d| {
+        let contents = fs::read_to_string(d.join("go.mod")).ok()?;
+        contents.lines().find_map(|l| l.trim().strip_prefix("module ").map(|m| m.trim().to_string()))
+    });
+
+    manifest.build.push("go build ./...".to_string());
+    manifest.tools = vec![GO];
+    Some(manifest)
+}
+
+fn read_manifests(dir: &Path) -> Vec<Manifest> {
+    let mut manifests: Vec<Manifest> = [rust(dir), node(dir), python(dir), go(dir)].into_iter().flatten().collect();
+    for (file, kind, build, tools) in MARKERS {
+        let path = dir.join(file);
+        if !path.is_file() || manifests.iter().any(|m| m.kind == *kind) {
+            continue;
+        }
+        let mut manifest = Manifest::new(kind, path);
+        manifest.build.push(match *kind {
+            "Gradle" if dir.join("gradlew").is_file() => "./gradlew build".to_string(),
+            _ => build.to_string(),
+        });
+        manifest.tools = tools.to_vec();
+        manifests.push(manifest);
+    }
+    manifests
+}
+
+fn read_pins(dir: &Path) -> Vec<(&'static str, String)> {
+    PIN_FILES
+        .iter()
+        .filter_map(|file| {
+            let contents = fs::read_to_string(dir.join(file)).ok()?;
+            let value = if *file == "rust-toolchain.toml" {
+                toml_str(toml_table(contents.parse::<toml::Table>().ok().as_ref(), "toolchain"), "channel")?
+            } else {
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            (!value.is_empty()).then_some((*file, value))
+        })
+        .collect()
+}
+
+/// Looks for manifests in `start`, then in its ancestors up to the
+/// enclosing git repository's root.
+pub fn detect(start: &Path) -> Project {
+    for dir in start.ancestors() {
+        let manifests = read_manifests(dir);
+        if !manifests.is_empty() {
+            return Project {
+                root: dir.to_path_buf(),
+                pins: read_pins(dir),
+                manifests,
+            };
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    Project {
+        root: start.to_path_buf(),
+        manifests: Vec::new(),
+        pins: read_pins(start),
+    }
+}
+
+/// The first executable called `name` on `PATH`.
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        let candidate = dir.join(name);
+        if is_executable(&candidate) {
+            return Some(candidate);
+        }
+        #[cfg(windows)]
+        for extension in ["exe", "cmd", "bat"] {
+            let candidate = candidate.with_extension(extension);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        None
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolVersion {
+    /// The name the tool was found under, or its first name when missing.
+    pub name: &'static str,
+    pub optional: bool,
+    pub path: Option<PathBuf>,
+    /// First line the version command printed, or why there is none.
+    pub version: Result<String, String>,
+}
+
+fn run_version(program: &Path, args: &[&str], dir: &Path) -> Result<String, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        // Never let a rustup proxy download a toolchain pinned by the project.
+        .env("RUSTUP_AUTO_INSTALL", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let deadline = Instant::now() + VERSION_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("no answer within {}s", VERSION_TIMEOUT.as_secs()));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    // Some tools (java -version, old pythons) print their version to stderr.
+    let first_line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes).lines().map(str::trim).find(|l| !l.is_empty()).map(str::to_string)
+    };
+    let line = first_line(&output.stdout).or_else(|| first_line(&output.stderr));
+    match line {
+        Some(line) if output.status.success() => Ok(line),
+        Some(line) => Err(line),
+        None => Err(format!("exited with {}", output.status)),
+    }
+}
+
+/// Versions of `tools` as seen from `dir`, queried in parallel.
+pub fn tool_versions(tools: &[Tool], dir: &Path) -> Vec<ToolVersion> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = tools
+            .iter()
+            .map(|tool| {
+                scope.spawn(move || {
+                    let found = tool.names.iter().find_map(|name| Some((*name, find_on_path(name)?)));
+                    match found {
+                        Some((name, path)) => ToolVersion {
+                            name,
+                            optional: tool.optional,
+                            version: run_version(&path, tool.args, dir),
+                            path: Some(path),
+                        },
+                        None => ToolVersion {
+                            name: tool.names[0],
+                            optional: tool.optional,
+                            path: None,
+                            version: Err("not found on PATH".to_string()),
+                        },
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    })
+}
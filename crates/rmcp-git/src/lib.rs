@@ -1,3 +1,5 @@
+pub mod dev_env;
+
 use git2::{BranchType, Repository, StatusOptions};
 use rmcp::{
     handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
@@ -14,6 +16,13 @@ pub struct RepoPathParams {
     pub path: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DevEnvParams {
+    #[schemars(description = "Project directory; parent directories up to the git root are searched when it has \
+        no manifest (defaults to current directory)")]
+    pub path: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct GitServer {
     pub tool_router: ToolRouter<Self>,
@@ -74,7 +83,7 @@ impl GitServer {
                         .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
                         .unwrap_or_else(|| "unknown".to_string());
 
-                    result.push_str(&format!("\nLast Commit:\n"));
+                    result.push_str("\nLast Commit:\n");
                     result.push_str(&format!("  {} - {}\n", short_id, summary));
                     result.push_str(&format!("  Author: {}\n", commit.author().name().unwrap_or("unknown")));
                     result.push_str(&format!("  Date: {}\n", timestamp));
@@ -174,18 +183,16 @@ impl GitServer {
             .map_err(|e| McpError::internal_error(format!("Failed to push HEAD: {}", e), None))?;
 
         let mut count = 0;
-        for oid in revwalk.take(10) {
-            if let Ok(oid) = oid {
-                if let Ok(commit) = repo.find_commit(oid) {
-                    count += 1;
-                    let id_str = oid.to_string();
-                    let short_id = &id_str[..7];
-                    let summary = commit.summary().unwrap_or("(no message)").to_string();
-                    let author = commit.author();
-                    let author_name = author.name().unwrap_or("unknown");
-
-                    result.push_str(&format!("{} {} - {}\n", short_id, author_name, summary));
-                }
+        for oid in revwalk.take(10).flatten() {
+            if let Ok(commit) = repo.find_commit(oid) {
+                count += 1;
+                let id_str = oid.to_string();
+                let short_id = &id_str[..7];
+                let summary = commit.summary().unwrap_or("(no message)").to_string();
+                let author = commit.author();
+                let author_name = author.name().unwrap_or("unknown");
+
+                result.push_str(&format!("{} {} - {}\n", short_id, author_name, summary));
             }
         }
 
@@ -235,13 +242,11 @@ impl GitServer {
             .map_err(|e| McpError::internal_error(format!("Failed to list branches: {}", e), None))?;
 
         let mut local_count = 0;
-        for branch in local_branches {
-            if let Ok((branch, _)) = branch {
-                if let Ok(Some(name)) = branch.name() {
-                    local_count += 1;
-                    let marker = if Some(name.to_string()) == current { "* " } else { "  " };
-                    result.push_str(&format!("{}{}\n", marker, name));
-                }
+        for (branch, _) in local_branches.flatten() {
+            if let Ok(Some(name)) = branch.name() {
+                local_count += 1;
+                let marker = if Some(name.to_string()) == current { "* " } else { "  " };
+                result.push_str(&format!("{}{}\n", marker, name));
             }
         }
         if local_count == 0 {
@@ -254,12 +259,10 @@ impl GitServer {
             .map_err(|e| McpError::internal_error(format!("Failed to list remote branches: {}", e), None))?;
 
         let mut remote_count = 0;
-        for branch in remote_branches {
-            if let Ok((branch, _)) = branch {
-                if let Ok(Some(name)) = branch.name() {
-                    remote_count += 1;
-                    result.push_str(&format!("  {}\n", name));
-                }
+        for (branch, _) in remote_branches.flatten() {
+            if let Ok(Some(name)) = branch.name() {
+                remote_count += 1;
+                result.push_str(&format!("  {}\n", name));
             }
         }
         if remote_count == 0 {
@@ -415,6 +418,84 @@ impl GitServer {
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    #[rmcp::tool(description = "Detect the project type in a directory (Cargo.toml, package.json, \
+        pyproject.toml, go.mod...), its workspace members, pinned toolchain versions and build commands, \
+        plus the versions of the matching toolchains on PATH (rustc, cargo, node, python, go) and the \
+        active rustup toolchain")]
+    pub async fn get_dev_environment(
+        &self,
+        Parameters(params): Parameters<DevEnvParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let path = params
+            .path
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+        let path = path
+            .canonicalize()
+            .map_err(|e| McpError::invalid_params(format!("Cannot open '{}': {}", path.display(), e), None))?;
+        if !path.is_dir() {
+            return Err(McpError::invalid_params(format!("'{}' is not a directory", path.display()), None));
+        }
+
+        let (project, versions) = tokio::task::spawn_blocking(move || {
+            let project = dev_env::detect(&path);
+            let versions = dev_env::tool_versions(&project.tools(), &project.root);
+            (project, versions)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        let mut result = format!("Development Environment: {}\n\n", project.root.display());
+        if project.manifests.is_empty() {
+            result.push_str("No project manifest found here or in parent directories up to the git root.\n\n");
+        }
+        for manifest in &project.manifests {
+            let file = manifest.file.strip_prefix(&project.root).unwrap_or(&manifest.file);
+            result.push_str(&format!("{} ({})", manifest.kind, file.display()));
+            match (&manifest.name, &manifest.version) {
+                (Some(name), Some(version)) => result.push_str(&format!(": {} {}\n", name, version)),
+                (Some(name), None) => result.push_str(&format!(": {}\n", name)),
+                _ => result.push('\n'),
+            }
+            for (label, value) in &manifest.facts {
+                result.push_str(&format!("  {}: {}\n", label, value));
+            }
+            if let Some(dependencies) = manifest.dependencies {
+                result.push_str(&format!("  dependencies: {}\n", dependencies));
+            }
+            if !manifest.members.is_empty() {
+                result.push_str(&format!("  Workspace members ({}):\n", manifest.members.len()));
+                for member in &manifest.members {
+                    match &member.name {
+                        Some(name) if !member.path.ends_with(name.as_str()) => {
+                            result.push_str(&format!("    {} ({})\n", member.path, name))
+                        }
+                        _ => result.push_str(&format!("    {}\n", member.path)),
+                    }
+                }
+            }
+            result.push_str(&format!("  Build: {}\n\n", manifest.build.join(" && ")));
+        }
+
+        if !project.pins.is_empty() {
+            result.push_str("Pinned versions:\n");
+            for (file, version) in &project.pins {
+                result.push_str(&format!("  {}: {}\n", file, version));
+            }
+            result.push('\n');
+        }
+
+        result.push_str("Toolchains on PATH:\n");
+        for tool in versions.iter().filter(|v| !v.optional || v.path.is_some()) {
+            match &tool.version {
+                Ok(version) => result.push_str(&format!("  {:<8} {}\n", tool.name, version)),
+                Err(e) => result.push_str(&format!("  {:<8} ({})\n", tool.name, e)),
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
 }
 
 #[rmcp::tool_handler]
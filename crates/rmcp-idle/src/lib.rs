@@ -32,6 +32,18 @@ impl IdleServer {
         }
     }
 
+    /// user-idle's X11 backend dereferences a null display when no X server
+    /// is reachable, so refuse early instead of crashing the server.
+    fn ensure_x11_display() -> Result<(), McpError> {
+        if cfg!(target_os = "linux") && std::env::var_os("DISPLAY").is_none() {
+            return Err(McpError::internal_error(
+                "Failed to get idle time: no X11 display available (DISPLAY is not set)",
+                None,
+            ));
+        }
+        Ok(())
+    }
+
     fn format_duration(seconds: u64) -> String {
         if seconds < 60 {
             format!("{}s", seconds)
@@ -59,6 +71,7 @@ impl IdleServer {
 impl IdleServer {
     #[rmcp::tool(description = "Get user idle time (how long since last keyboard/mouse input)")]
     pub async fn get_idle_time(&self) -> Result<CallToolResult, McpError> {
+        Self::ensure_x11_display()?;
         let idle = UserIdle::get_time()
             .map_err(|e| McpError::internal_error(format!("Failed to get idle time: {}", e), None))?;
 
@@ -78,6 +91,7 @@ impl IdleServer {
         &self,
         Parameters(params): Parameters<IdleThresholdParams>,
     ) -> Result<CallToolResult, McpError> {
+        Self::ensure_x11_display()?;
         let idle = UserIdle::get_time()
             .map_err(|e| McpError::internal_error(format!("Failed to get idle time: {}", e), None))?;
 
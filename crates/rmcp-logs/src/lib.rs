@@ -0,0 +1,257 @@
+pub mod logs;
+
+use logs::{LogEntry, LogFilter, Source};
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
+    model::*,
+    ErrorData as McpError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct LogsServer {
+    pub tool_router: ToolRouter<Self>,
+}
+
+impl Default for LogsServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TailLogsParams {
+    #[schemars(description = "'journal' or one of the configured log file paths (default: all sources)")]
+    #[serde(default)]
+    pub source: Option<String>,
+    #[schemars(description = "Only this systemd unit, e.g. 'nginx' or 'backup.service' (journal only)")]
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[schemars(description = "Only this priority or more severe: emerg, alert, crit, err, warning, notice, info, debug \
+        or 0-7 (journal only)")]
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[schemars(description = "Only entries from the last N minutes (max 43200, 30 days)")]
+    #[serde(default)]
+    pub minutes: Option<u64>,
+    #[schemars(description = "Number of most recent lines (default 50, max 1000)")]
+    #[serde(default)]
+    pub lines: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchLogsParams {
+    #[schemars(description = "Regex matched against the message and its source, e.g. '(?i)out of memory|oom'")]
+    pub pattern: String,
+    #[schemars(description = "'journal' or one of the configured log file paths (default: all sources)")]
+    #[serde(default)]
+    pub source: Option<String>,
+    #[schemars(description = "Only this systemd unit, e.g. 'nginx' or 'backup.service' (journal only)")]
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[schemars(description = "Only this priority or more severe: emerg, alert, crit, err, warning, notice, info, debug \
+        or 0-7 (journal only)")]
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[schemars(description = "Search the last N minutes (default 1440, one day; max 43200, 30 days)")]
+    #[serde(default)]
+    pub minutes: Option<u64>,
+    #[schemars(description = "Maximum number of matches, newest kept (default 50, max 500)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else if seconds < 86400 {
+        format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
+    } else {
+        format!("{}d {}h", seconds / 86400, (seconds % 86400) / 3600)
+    }
+}
+
+/// Resolves the requested sources and the filter shared by both tools.
+fn build_query(
+    source: Option<&str>,
+    unit: Option<&str>,
+    priority: Option<&str>,
+    minutes: Option<u64>,
+    pattern: Option<&str>,
+    limit: usize,
+) -> Result<(Vec<Source>, LogFilter), McpError> {
+    let files = logs::configured_files();
+    let journal_only = unit.is_some() || priority.is_some();
+    let sources = match source {
+        None | Some("") if journal_only => vec![Source::Journal],
+        None | Some("") => std::iter::once(Source::Journal).chain(files.into_iter().map(Source::File)).collect(),
+        Some("journal") => vec![Source::Journal],
+        Some(path) if journal_only => {
+            return Err(McpError::invalid_params(
+                format!("'{}' is a plain log file; unit and priority filters only apply to the journal", path),
+                None,
+            ))
+        }
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if !files.contains(&path) {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "'{}' is not a configured log file (configured: {}; set RMCP_SENSORS_LOG_FILES to change)",
+                        path.display(),
+                        if files.is_empty() {
+                            "none".to_string()
+                        } else {
+                            files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ")
+                        }
+                    ),
+                    None,
+                ));
+            }
+            vec![Source::File(path)]
+        }
+    };
+
+    let priority = match priority {
+        Some(value) => Some(logs::parse_priority(value).ok_or_else(|| {
+            McpError::invalid_params(format!("Unknown priority '{}'", value), None)
+        })?),
+        None => None,
+    };
+    let pattern = pattern
+        .map(regex::Regex::new)
+        .transpose()
+        .map_err(|e| McpError::invalid_params(format!("Invalid regex: {}", e), None))?;
+    let since_usec = minutes.map(|m| {
+            let window_usec = m.min(logs::MAX_WINDOW_MINUTES) as i64 * 60_000_000;
+            chrono::Utc::now().timestamp_micros().saturating_sub(window_usec)
+        });
+
+    let filter = LogFilter {
+        unit: unit.map(str::to_string),
+        priority,
+        since_usec,
+        pattern,
+        limit,
+    };
+    Ok((sources, filter))
+}
+
+fn format_entry(entry: &LogEntry) -> String {
+    let mut line = match entry.timestamp_usec.and_then(chrono::DateTime::from_timestamp_micros) {
+        Some(time) => format!("{} ", time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")),
+        None => String::new(),
+    };
+    line.push_str(&entry.source);
+    if let Some(pid) = entry.pid {
+        line.push_str(&format!("[{}]", pid));
+    }
+    if let Some(priority) = entry.priority.filter(|p| *p <= 4) {
+        line.push_str(&format!(" <{}>", logs::priority_name(priority)));
+    }
+    line.push_str(": ");
+    line.push_str(&entry.message);
+    line
+}
+
+/// Reads the sources on a blocking thread and renders the entries.
+async fn run_query(title: String, sources: Vec<Source>, filter: LogFilter) -> Result<String, McpError> {
+    let labels: Vec<String> = sources.iter().map(Source::label).collect();
+    let (entries, errors) = tokio::task::spawn_blocking(move || logs::collect(&sources, &filter))
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    if entries.is_empty() && errors.len() == labels.len() {
+        return Err(McpError::internal_error(format!("No log source readable: {}", errors.join("; ")), None));
+    }
+
+    let mut output = format!("{} ({}):\n\n", title, labels.join(", "));
+    if entries.is_empty() {
+        output.push_str("No matching log lines.\n");
+    }
+    for entry in &entries {
+        output.push_str(&format_entry(entry));
+        output.push('\n');
+    }
+    if !errors.is_empty() {
+        output.push_str(&format!("\nUnreadable: {}\n", errors.join("; ")));
+    }
+    Ok(output)
+}
+
+#[rmcp::tool_router]
+impl LogsServer {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[rmcp::tool(description = "Show the most recent log lines from the systemd journal and configured log files, \
+        optionally filtered by unit, priority and time window. Credentials are masked.")]
+    pub async fn tail_logs(
+        &self,
+        Parameters(params): Parameters<TailLogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let lines = params.lines.unwrap_or(50).clamp(1, 1000);
+        let minutes = params.minutes.map(|m| m.min(logs::MAX_WINDOW_MINUTES));
+        let (sources, filter) = build_query(
+            params.source.as_deref(),
+            params.unit.as_deref(),
+            params.priority.as_deref(),
+            minutes,
+            None,
+            lines,
+        )?;
+        let mut title = format!("Last {} log lines", lines);
+        if let Some(minutes) = minutes {
+            title.push_str(&format!(" from the last {}", format_duration(minutes * 60)));
+        }
+        let output = run_query(title, sources, filter).await?;
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Search the systemd journal and configured log files with a regex, newest matches \
+        first kept, optionally filtered by unit, priority and time window. Credentials are masked.")]
+    pub async fn search_logs(
+        &self,
+        Parameters(params): Parameters<SearchLogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let limit = params.limit.unwrap_or(50).clamp(1, 500);
+        let minutes = params.minutes.unwrap_or(1440).min(logs::MAX_WINDOW_MINUTES);
+        let (sources, filter) = build_query(
+            params.source.as_deref(),
+            params.unit.as_deref(),
+            params.priority.as_deref(),
+            Some(minutes),
+            Some(&params.pattern),
+            limit,
+        )?;
+        let title = format!(
+            "Log lines matching '{}' in the last {}",
+            params.pattern,
+            format_duration(minutes * 60)
+        );
+        let output = run_query(title, sources, filter).await?;
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+}
+
+#[rmcp::tool_handler]
+impl ServerHandler for LogsServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some("Log server - tail and search the systemd journal and log files".into()),
+        }
+    }
+}
@@ -1,9 +1,43 @@
+pub mod sockets;
+
 use network_interface::{NetworkInterface, NetworkInterfaceConfig, Addr};
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, ServerHandler},
+    handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
     model::*,
     ErrorData as McpError,
 };
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sockets::{Protocol, Socket};
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ListeningPortsParams {
+    #[schemars(description = "Protocol: tcp, udp, tcp4, tcp6, udp4, udp6, unix, inet or all (default 'inet')")]
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[schemars(description = "Only show this port number")]
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectionsParams {
+    #[schemars(description = "Protocol: tcp, udp, tcp4, tcp6, udp4, udp6, unix, inet or all (default 'inet')")]
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[schemars(description = "Only sockets in this state, e.g. ESTABLISHED, TIME_WAIT, LISTEN (case-insensitive)")]
+    #[serde(default)]
+    pub state: Option<String>,
+    #[schemars(description = "Only sockets whose local or remote port is this")]
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[schemars(description = "Only sockets owned by this PID")]
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[schemars(description = "Maximum number of sockets to list (default 100)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
 
 #[derive(Debug)]
 pub struct NetworkServer {
@@ -38,7 +72,7 @@ impl NetworkServer {
                 Addr::V6(v6) => v6.ip.is_loopback(),
             });
 
-            result.push_str(&format!("{}", iface.name));
+            result.push_str(&iface.name);
             if is_loopback {
                 result.push_str(" (loopback)");
             }
@@ -82,6 +116,36 @@ impl NetworkServer {
 
         result
     }
+
+    fn socket_protocols(filter: Option<&str>) -> Result<Vec<Protocol>, McpError> {
+        match filter {
+            Some(filter) => Protocol::parse_filter(filter)
+                .ok_or_else(|| McpError::invalid_params(format!("Unknown protocol '{}'", filter), None)),
+            None => Ok(Protocol::INET.to_vec()),
+        }
+    }
+
+    fn socket_owner(socket: &Socket) -> String {
+        match (socket.pid, &socket.process) {
+            (Some(pid), Some(name)) => format!("{:<8} {}", pid, name),
+            _ => match socket.uid {
+                Some(uid) => format!("{:<8} (uid {})", "-", uid),
+                None => format!("{:<8} {}", "-", "-"),
+            },
+        }
+    }
+
+    fn unresolved_owners_note(sockets: &[&Socket]) -> String {
+        let unresolved = sockets.iter().filter(|s| s.pid.is_none() && s.inode != 0).count();
+        if unresolved == 0 {
+            String::new()
+        } else {
+            format!(
+                "\n{} socket(s) could not be matched to a process (other users' processes need root)\n",
+                unresolved
+            )
+        }
+    }
 }
 
 #[rmcp::tool_router]
@@ -95,6 +159,90 @@ impl NetworkServer {
 
         Ok(CallToolResult::success(vec![Content::text(formatted)]))
     }
+
+    #[rmcp::tool(description = "List listening TCP/UDP ports (and optionally unix sockets) with the owning \
+        process, e.g. to answer \"what's on port 8080?\"")]
+    pub async fn get_listening_ports(
+        &self,
+        Parameters(params): Parameters<ListeningPortsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let protocols = Self::socket_protocols(params.protocol.as_deref())?;
+        let mut sockets: Vec<_> = sockets::read_sockets(std::path::Path::new(sockets::PROC_ROOT), &protocols)
+            .into_iter()
+            .filter(|s| s.listening && (params.port.is_none() || s.local_port == params.port))
+            .collect();
+        sockets.sort_by(|a, b| (a.local_port, a.protocol.name(), &a.local).cmp(&(b.local_port, b.protocol.name(), &b.local)));
+
+        let mut output = String::from("Listening Ports:\n\n");
+        if sockets.is_empty() {
+            output.push_str("No listening sockets found.\n");
+        } else {
+            output.push_str(&format!("{:<6} {:<40} {:<8} {}\n", "Proto", "Local Address", "PID", "Process"));
+            output.push_str(&format!("{:-<70}\n", ""));
+            for s in &sockets {
+                output.push_str(&format!("{:<6} {:<40} {}", s.protocol.name(), s.local, Self::socket_owner(s)));
+                output.push('\n');
+            }
+            output.push_str(&format!("\nTotal: {} listening sockets\n", sockets.len()));
+            output.push_str(&Self::unresolved_owners_note(&sockets.iter().collect::<Vec<_>>()));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List open sockets (TCP/UDP/unix) with local and remote address, state and \
+        owning process; filter by protocol, state, port or PID")]
+    pub async fn get_connections(
+        &self,
+        Parameters(params): Parameters<ConnectionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let protocols = Self::socket_protocols(params.protocol.as_deref())?;
+        let state = params.state.as_deref().map(str::to_uppercase);
+        let sockets: Vec<_> = sockets::read_sockets(std::path::Path::new(sockets::PROC_ROOT), &protocols)
+            .into_iter()
+            .filter(|s| state.as_deref().is_none_or(|state| s.state == state))
+            .filter(|s| params.port.is_none() || s.local_port == params.port || s.remote_port == params.port)
+            .filter(|s| params.pid.is_none() || s.pid == params.pid)
+            .collect();
+
+        let mut by_state: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+        for s in &sockets {
+            *by_state.entry(s.state).or_default() += 1;
+        }
+        let states: Vec<_> = by_state.iter().map(|(state, n)| format!("{} {}", n, state)).collect();
+
+        let limit = params.limit.unwrap_or(100);
+
+        let mut output = String::from("Connections:\n\n");
+        if sockets.is_empty() {
+            output.push_str("No matching sockets.\n");
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        output.push_str(&format!(
+            "{:<6} {:<40} {:<40} {:<12} {:<8} {}\n",
+            "Proto", "Local Address", "Remote Address", "State", "PID", "Process"
+        ));
+        output.push_str(&format!("{:-<120}\n", ""));
+        for s in sockets.iter().take(limit) {
+            output.push_str(&format!(
+                "{:<6} {:<40} {:<40} {:<12} {}",
+                s.protocol.name(),
+                s.local,
+                s.remote.as_deref().unwrap_or("-"),
+                s.state,
+                Self::socket_owner(s)
+            ));
+            output.push('\n');
+        }
+        if sockets.len() > limit {
+            output.push_str(&format!("\n... and {} more sockets\n", sockets.len() - limit));
+        }
+        output.push_str(&format!("\nTotal: {} ({})\n", sockets.len(), states.join(", ")));
+        output.push_str(&Self::unresolved_owners_note(&sockets.iter().collect::<Vec<_>>()));
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 }
 
 #[rmcp::tool_handler]
@@ -106,7 +254,7 @@ impl ServerHandler for NetworkServer {
                 .enable_tools()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("Cross-platform network interface information server".into()),
+            instructions: Some("Network interface, listening port and socket information server".into()),
         }
     }
 }
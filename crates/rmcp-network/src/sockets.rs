@@ -0,0 +1,359 @@
+//! Socket table from `/proc/net/{tcp,tcp6,udp,udp6,unix}` with owning
+//! processes found by matching socket inodes against `/proc/<pid>/fd`.
+//! Linux-only; elsewhere the tables are simply empty.
+
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+
+pub const PROC_ROOT: &str = "/proc";
+
+/// `__SO_ACCEPTCON` in `/proc/net/unix` flags: the socket is listening.
+const UNIX_ACCEPTCON: u32 = 0x10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Unix,
+}
+
+impl Protocol {
+    pub const ALL: [Protocol; 5] = [Protocol::Tcp, Protocol::Tcp6, Protocol::Udp, Protocol::Udp6, Protocol::Unix];
+    pub const INET: [Protocol; 4] = [Protocol::Tcp, Protocol::Tcp6, Protocol::Udp, Protocol::Udp6];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Tcp6 => "tcp6",
+            Protocol::Udp => "udp",
+            Protocol::Udp6 => "udp6",
+            Protocol::Unix => "unix",
+        }
+    }
+
+    /// Expands a user filter ("tcp", "udp6", "unix", "all") into protocols.
+    /// "tcp" and "udp" include their IPv6 variants.
+    pub fn parse_filter(filter: &str) -> Option<Vec<Protocol>> {
+        match filter.to_lowercase().as_str() {
+            "tcp" => Some(vec![Protocol::Tcp, Protocol::Tcp6]),
+            "tcp4" => Some(vec![Protocol::Tcp]),
+            "tcp6" => Some(vec![Protocol::Tcp6]),
+            "udp" => Some(vec![Protocol::Udp, Protocol::Udp6]),
+            "udp4" => Some(vec![Protocol::Udp]),
+            "udp6" => Some(vec![Protocol::Udp6]),
+            "unix" => Some(vec![Protocol::Unix]),
+            "inet" => Some(Protocol::INET.to_vec()),
+            "all" => Some(Protocol::ALL.to_vec()),
+            _ => None,
+        }
+    }
+
+    fn is_tcp(self) -> bool {
+        matches!(self, Protocol::Tcp | Protocol::Tcp6)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Socket {
+    pub protocol: Protocol,
+    /// `ip:port` for inet sockets, the bound path (or `@abstract`) for unix.
+    pub local: String,
+    pub remote: Option<String>,
+    pub local_port: Option<u16>,
+    pub remote_port: Option<u16>,
+    pub state: &'static str,
+    pub listening: bool,
+    pub uid: Option<u32>,
+    pub inode: u64,
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+}
+
+fn tcp_state(code: u8) -> &'static str {
+    match code {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        0x0C => "NEW_SYN_RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Decodes the kernel's `ADDR:PORT` hex form. Addresses are printed as
+/// host-order 32-bit words, so each word is converted with native endianness.
+fn parse_inet_addr(field: &str) -> Option<SocketAddr> {
+    let (addr, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let ip = match addr.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(u32::from_str_radix(addr, 16).ok()?.to_ne_bytes())),
+        32 => {
+            let mut bytes = [0u8; 16];
+            for (i, chunk) in bytes.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            let v6 = Ipv6Addr::from(bytes);
+            match v6.to_ipv4_mapped() {
+                Some(v4) => IpAddr::V4(v4),
+                None => IpAddr::V6(v6),
+            }
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn parse_inet_table(contents: &str, protocol: Protocol) -> Vec<Socket> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let local = parse_inet_addr(fields[1])?;
+            let remote = parse_inet_addr(fields[2])?;
+            let state_code = u8::from_str_radix(fields[3], 16).ok()?;
+            let unconnected = remote.port() == 0 && remote.ip().is_unspecified();
+
+            let (state, listening) = if protocol.is_tcp() {
+                (tcp_state(state_code), state_code == 0x0A)
+            } else if unconnected {
+                ("UNCONN", true)
+            } else {
+                ("ESTABLISHED", false)
+            };
+
+            Some(Socket {
+                protocol,
+                local: local.to_string(),
+                remote: (!unconnected).then(|| remote.to_string()),
+                local_port: Some(local.port()),
+                remote_port: (!unconnected).then_some(remote.port()),
+                state,
+                listening,
+                uid: fields[7].parse().ok(),
+                inode: fields[9].parse().ok()?,
+                pid: None,
+                process: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_unix_table(contents: &str) -> Vec<Socket> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let flags = u32::from_str_radix(fields[3], 16).ok()?;
+            let kind = match fields[4] {
+                "0001" => "STREAM",
+                "0002" => "DGRAM",
+                "0005" => "SEQPACKET",
+                _ => "OTHER",
+            };
+            let listening = flags & UNIX_ACCEPTCON != 0;
+            let state = match (listening, fields[5]) {
+                (true, _) => "LISTEN",
+                (false, "03") => "CONNECTED",
+                _ => kind,
+            };
+            Some(Socket {
+                protocol: Protocol::Unix,
+                local: fields.get(7).map(|p| p.to_string()).unwrap_or_else(|| "(unnamed)".to_string()),
+                remote: None,
+                local_port: None,
+                remote_port: None,
+                state,
+                listening,
+                uid: None,
+                inode: fields[6].parse().ok()?,
+                pid: None,
+                process: None,
+            })
+        })
+        .collect()
+}
+
+/// Maps socket inodes to the first process holding them. Processes we may
+/// not inspect (other users' without root) are silently skipped.
+pub fn inode_owners(proc_root: &Path) -> HashMap<u64, (u32, String)> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return owners;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let name = fs::read_to_string(entry.path().join("comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+            }
+        }
+    }
+    owners
+}
+
+/// Reads the requested socket tables and attributes each socket to its
+/// owning process where possible.
+pub fn read_sockets(proc_root: &Path, protocols: &[Protocol]) -> Vec<Socket> {
+    let mut sockets = Vec::new();
+    for protocol in protocols {
+        let Ok(contents) = fs::read_to_string(proc_root.join("net").join(protocol.name())) else {
+            continue;
+        };
+        match protocol {
+            Protocol::Unix => sockets.extend(parse_unix_table(&contents)),
+            _ => sockets.extend(parse_inet_table(&contents, *protocol)),
+        }
+    }
+
+    let owners = inode_owners(proc_root);
+    for socket in &mut sockets {
+        if let Some((pid, name)) = owners.get(&socket.inode) {
+            socket.pid = Some(*pid);
+            socket.process = Some(name.clone());
+        }
+    }
+    sockets
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// An address in the kernel's hex form: host-order 32-bit words.
+    fn hex(octets: &[u8], port: u16) -> String {
+        let words: String = octets
+            .chunks(4)
+            .map(|w| format!("{:08X}", u32::from_ne_bytes([w[0], w[1], w[2], w[3]])))
+            .collect();
+        format!("{}:{:04X}", words, port)
+    }
+
+    fn inet_row(local: String, remote: String, state: &str, inode: u64) -> String {
+        format!(
+            "   0: {} {} {} 00000000:00000000 00:00000000 00000000  1000        0 {} 1 0000000000000000\n",
+            local, remote, state, inode
+        )
+    }
+
+    const INET_HEADER: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  \
+        timeout inode\n";
+
+    #[test]
+    fn parses_canned_tables() {
+        let proc_root = tempfile::tempdir().unwrap();
+        let net = proc_root.path().join("net");
+        fs::create_dir(&net).unwrap();
+        let tcp = INET_HEADER.to_string()
+            + &inet_row(hex(&[127, 0, 0, 1], 22), hex(&[0; 4], 0), "0A", 100)
+            + &inet_row(hex(&[10, 0, 0, 2], 40000), hex(&[10, 0, 0, 1], 443), "01", 101)
+            + "   2: garbage\n";
+        let mapped = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 192, 168, 1, 5];
+        let tcp6 = INET_HEADER.to_string()
+            + &inet_row(hex(&Ipv6Addr::LOCALHOST.octets(), 631), hex(&[0; 16], 0), "0A", 102)
+            + &inet_row(hex(&mapped, 8080), hex(&mapped, 50000), "06", 103);
+        let udp = INET_HEADER.to_string()
+            + &inet_row(hex(&[0; 4], 5353), hex(&[0; 4], 0), "07", 104)
+            + &inet_row(hex(&[10, 0, 0, 2], 50001), hex(&[9, 9, 9, 9], 53), "01", 105);
+        let unix = "Num       RefCount Protocol Flags    Type St Inode Path\n\
+            0000000000000000: 00000002 00000000 00010000 0001 01 106 /run/app.sock\n\
+            0000000000000000: 00000003 00000000 00000000 0001 03 107\n\
+            0000000000000000: 00000002 00000000 00000000 0002 01 108 @abstract\n";
+        fs::write(net.join("tcp"), tcp).unwrap();
+        fs::write(net.join("tcp6"), tcp6).unwrap();
+        fs::write(net.join("udp"), udp).unwrap();
+        fs::write(net.join("unix"), unix).unwrap();
+
+        let fd = proc_root.path().join("42/fd");
+        fs::create_dir_all(&fd).unwrap();
+        fs::write(proc_root.path().join("42/comm"), "sshd\n").unwrap();
+        std::os::unix::fs::symlink("socket:[100]", fd.join("3")).unwrap();
+        std::os::unix::fs::symlink("/dev/null", fd.join("4")).unwrap();
+
+        let sockets = read_sockets(proc_root.path(), &Protocol::ALL);
+        let summary: Vec<_> = sockets
+            .iter()
+            .map(|s| (s.protocol, s.local.as_str(), s.remote.as_deref(), s.state, s.listening))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Protocol::Tcp, "127.0.0.1:22", None, "LISTEN", true),
+                (Protocol::Tcp, "10.0.0.2:40000", Some("10.0.0.1:443"), "ESTABLISHED", false),
+                (Protocol::Tcp6, "[::1]:631", None, "LISTEN", true),
+                (Protocol::Tcp6, "192.168.1.5:8080", Some("192.168.1.5:50000"), "TIME_WAIT", false),
+                (Protocol::Udp, "0.0.0.0:5353", None, "UNCONN", true),
+                (Protocol::Udp, "10.0.0.2:50001", Some("9.9.9.9:53"), "ESTABLISHED", false),
+                (Protocol::Unix, "/run/app.sock", None, "LISTEN", true),
+                (Protocol::Unix, "(unnamed)", None, "CONNECTED", false),
+                (Protocol::Unix, "@abstract", None, "DGRAM", false),
+            ]
+        );
+        assert_eq!((sockets[0].uid, sockets[0].inode), (Some(1000), 100));
+        assert_eq!((sockets[0].pid, sockets[0].process.as_deref()), (Some(42), Some("sshd")));
+        assert!(sockets[1..].iter().all(|s| s.pid.is_none()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn finds_live_sockets_of_this_process() {
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("live.sock");
+        let _unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let sockets = read_sockets(Path::new(PROC_ROOT), &Protocol::ALL);
+        let own = |protocol: Protocol, local: &str| {
+            sockets
+                .iter()
+                .find(|s| s.protocol == protocol && s.local == local)
+                .unwrap_or_else(|| panic!("{} {} not listed", protocol.name(), local))
+        };
+        let tcp = own(Protocol::Tcp, &tcp.local_addr().unwrap().to_string());
+        assert_eq!((tcp.state, tcp.listening), ("LISTEN", true));
+        assert_eq!(tcp.pid, Some(std::process::id()));
+        let udp = own(Protocol::Udp, &udp.local_addr().unwrap().to_string());
+        assert_eq!((udp.state, udp.remote.as_deref()), ("UNCONN", None));
+        assert_eq!(udp.pid, Some(std::process::id()));
+        let unix = own(Protocol::Unix, path.to_str().unwrap());
+        assert_eq!((unix.state, unix.pid), ("LISTEN", Some(std::process::id())));
+    }
+}
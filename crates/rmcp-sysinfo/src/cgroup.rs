@@ -0,0 +1,293 @@
+//! Container detection and the resource limits of our own cgroup, so host
+//! totals can be put in perspective when running in a container or a
+//! systemd slice. cgroup v2 is read from the unified hierarchy; v1 hosts fall
+//! back to the per-controller `cpu`, `memory`, `pids` and `cpuacct` trees.
+//! A limit set on any ancestor applies to us too, so each limit is the
+//! tightest one between our cgroup and the root. Linux-only; elsewhere
+//! nothing is detected.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Filesystem root the probes are resolved against.
+pub const ROOT: &str = "/";
+
+/// v1 reports "no limit" as a page-aligned `i64::MAX`; anything this large
+/// is treated as unlimited.
+const V1_UNLIMITED: u64 = 1 << 62;
+
+/// A limit and the cgroup path it is set on, ours or an ancestor's.
+#[derive(Debug, Clone)]
+pub struct Limit<T> {
+    pub value: T,
+    pub set_on: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CgroupInfo {
+    pub version: u8,
+    /// Our cgroup path as seen in `/proc/self/cgroup`.
+    pub path: String,
+    /// CPU quota in cores (`quota / period`); `None` when unlimited.
+    pub cpu_quota: Option<Limit<f64>>,
+    pub memory_max: Option<Limit<u64>>,
+    /// v2 `memory.high` throttling threshold.
+    pub memory_high: Option<Limit<u64>>,
+    /// Memory use of the cgroup the memory limit is set on (ours when
+    /// unlimited), which is what the limit is enforced against.
+    pub memory_current: Option<u64>,
+    pub pids_max: Option<Limit<u64>>,
+    /// Likewise for the process limit.
+    pub pids_current: Option<u64>,
+    /// Cumulative CPU time of the cgroup in microseconds.
+    pub cpu_usage_us: Option<u64>,
+}
+
+impl CgroupInfo {
+    pub fn has_limits(&self) -> bool {
+        self.cpu_quota.is_some() || self.memory_max.is_some() || self.pids_max.is_some()
+    }
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number(path: impl AsRef<Path>) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// A v2 limit file: a number, or "max" for unlimited.
+fn read_limit(path: impl AsRef<Path>) -> Option<u64> {
+    read_trimmed(path).filter(|v| v != "max")?.parse().ok()
+}
+
+/// Best guess at the container runtime we run under, from marker files, the
+/// cgroup path of PID 1 and the `container=` variable systemd-style runtimes
+/// pass to init.
+pub fn detect_runtime(root: &Path) -> Option<&'static str> {
+    if root.join(".dockerenv").exists() {
+        return Some("docker");
+    }
+    if root.join("run/.containerenv").exists() {
+        return Some("podman");
+    }
+
+    let cgroups = read_trimmed(root.join("proc/1/cgroup")).unwrap_or_default();
+    for (marker, runtime) in [
+        ("kubepods", "kubernetes"),
+        ("libpod", "podman"),
+        ("docker", "docker"),
+        ("containerd", "containerd"),
+        ("lxc", "lxc"),
+    ] {
+        if cgroups.contains(marker) {
+            return Some(runtime);
+        }
+    }
+
+    let environ = fs::read(root.join("proc/1/environ")).unwrap_or_default();
+    environ
+        .split(|b| *b == 0)
+        .filter_map(|var| var.strip_prefix(b"container="))
+        .find_map(|value| match value {
+            b"lxc" | b"lxc-libvirt" => Some("lxc"),
+            b"podman" => Some("podman"),
+            b"docker" => Some("docker"),
+            b"systemd-nspawn" => Some("systemd-nspawn"),
+            b"wsl" => None,
+            _ => Some("container"),
+        })
+}
+
+/// The directory of our cgroup under `mount`, falling back to the mount
+/// itself when the path is hidden by a cgroup namespace.
+fn cgroup_dir(mount: PathBuf, path: &str) -> PathBuf {
+    let dir = mount.join(path.trim_start_matches('/'));
+    if dir.is_dir() {
+        dir
+    } else {
+        mount
+    }
+}
+
+/// `dir` and its ancestors up to and including `mount`, nearest first, with
+/// their cgroup paths.
+fn ancestry(mount: &Path, dir: &Path) -> Vec<(PathBuf, String)> {
+    dir.ancestors()
+        .take_while(|d| d.starts_with(mount))
+        .map(|d| {
+            let relative = d.strip_prefix(mount).unwrap_or(d);
+            (d.to_path_buf(), format!("/{}", relative.display()))
+        })
+        .collect()
+}
+
+/// The tightest value `read` finds along `ancestry`; on a tie the nearest
+/// cgroup wins.
+fn tightest<T: PartialOrd>(ancestry: &[(PathBuf, String)], read: impl Fn(&Path) -> Option<T>) -> Option<Limit<T>> {
+    let mut best: Option<Limit<T>> = None;
+    for (dir, path) in ancestry {
+        if let Some(value) = read(dir) {
+            if best.as_ref().is_none_or(|b| value < b.value) {
+                best = Some(Limit {
+                    value,
+                    set_on: path.clone(),
+                });
+            }
+        }
+    }
+    best
+}
+
+/// The directory of `limit`'s cgroup in `ancestry`, or of ours without one.
+fn limiting_dir<'a, T>(ancestry: &'a [(PathBuf, String)], limit: Option<&Limit<T>>) -> Option<&'a Path> {
+    let (dir, _) = match limit {
+        Some(limit) => ancestry.iter().find(|(_, path)| *path == limit.set_on)?,
+        None => ancestry.first()?,
+    };
+    Some(dir)
+}
+
+/// Limits and usage of the cgroup this process belongs to.
+pub fn read_cgroup(root: &Path) -> Option<CgroupInfo> {
+    let membership = read_trimmed(root.join("proc/self/cgroup"))?;
+    let mount = root.join("sys/fs/cgroup");
+
+    if mount.join("cgroup.controllers").exists() {
+        let path = membership.lines().find_map(|l| l.strip_prefix("0::"))?.to_string();
+        let dir = cgroup_dir(mount.clone(), &path);
+        let ancestry = ancestry(&mount, &dir);
+        let cpu_quota = tightest(&ancestry, |dir| {
+            let max = read_trimmed(dir.join("cpu.max"))?;
+            let (quota, period) = max.split_once(' ')?;
+            Some(quota.parse::<f64>().ok()? / period.parse::<f64>().ok()?)
+        });
+        let cpu_usage_us = read_trimmed(dir.join("cpu.stat")).and_then(|stat| {
+            stat.lines().find_map(|l| l.strip_prefix("usage_usec ")?.parse().ok())
+        });
+        let memory_max = tightest(&ancestry, |dir| read_limit(dir.join("memory.max")));
+        let pids_max = tightest(&ancestry, |dir| read_limit(dir.join("pids.max")));
+        return Some(CgroupInfo {
+            version: 2,
+            memory_current: limiting_dir(&ancestry, memory_max.as_ref())
+                .and_then(|dir| read_number(dir.join("memory.current"))),
+            pids_current: limiting_dir(&ancestry, pids_max.as_ref())
+                .and_then(|dir| read_number(dir.join("pids.current"))),
+            path,
+            cpu_quota,
+            memory_max,
+            memory_high: tightest(&ancestry, |dir| read_limit(dir.join("memory.high"))),
+            pids_max,
+            cpu_usage_us,
+        });
+    }
+
+    // v1 lines look like "4:memory:/docker/abc" or "2:cpu,cpuacct:/...".
+    let controller_path = |name: &str| {
+        membership.lines().find_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let controllers = parts.nth(1)?;
+            controllers.split(',').any(|c| c == name).then(|| parts.next().unwrap_or("/").to_string())
+        })
+    };
+    // Each controller is its own hierarchy with its own ancestry.
+    let controller_ancestry = |name: &str| {
+        let mount = mount.join(name);
+        let dir = cgroup_dir(mount.clone(), &controller_path(name)?);
+        Some(ancestry(&mount, &dir))
+    };
+
+    let cpu = controller_ancestry("cpu").unwrap_or_default();
+    let memory = controller_ancestry("memory").unwrap_or_default();
+    let pids = controller_ancestry("pids").unwrap_or_default();
+    let cpuacct = controller_ancestry("cpuacct").unwrap_or_default();
+
+    let cpu_quota = tightest(&cpu, |dir| {
+        let quota = read_trimmed(dir.join("cpu.cfs_quota_us"))?.parse::<i64>().ok()?;
+        let period = read_number(dir.join("cpu.cfs_period_us"))?;
+        (quota > 0 && period > 0).then(|| quota as f64 / period as f64)
+    });
+    let memory_max = tightest(&memory, |dir| {
+        read_number(dir.join("memory.limit_in_bytes")).filter(|limit| *limit < V1_UNLIMITED)
+    });
+    let pids_max = tightest(&pids, |dir| read_limit(dir.join("pids.max")));
+
+    Some(CgroupInfo {
+        version: 1,
+        path: controller_path("memory").or_else(|| controller_path("cpu")).unwrap_or_else(|| "/".to_string()),
+        memory_current: limiting_dir(&memory, memory_max.as_ref())
+            .and_then(|dir| read_number(dir.join("memory.usage_in_bytes"))),
+        pids_current: limiting_dir(&pids, pids_max.as_ref()).and_then(|dir| read_number(dir.join("pids.current"))),
+        cpu_quota,
+        memory_max,
+        memory_high: None,
+        pids_max,
+        cpu_usage_us: cpuacct
+            .first()
+            .and_then(|(dir, _)| read_number(dir.join("cpuacct.usage")))
+            .map(|ns| ns / 1000),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn v2_reports_tightest_limit_up_the_tree() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        let cgroup = root.join("sys/fs/cgroup");
+        write(root.join("proc/self/cgroup"), "0::/user.slice/app.scope\n");
+        write(cgroup.join("cgroup.controllers"), "cpu memory pids\n");
+
+        let slice = cgroup.join("user.slice");
+        write(slice.join("memory.max"), "1073741824\n");
+        write(slice.join("memory.current"), "536870912\n");
+        write(slice.join("pids.max"), "max\n");
+        write(slice.join("cpu.max"), "100000 100000\n");
+
+        let scope = slice.join("app.scope");
+        write(scope.join("memory.max"), "max\n");
+        write(scope.join("memory.current"), "104857600\n");
+        write(scope.join("pids.max"), "64\n");
+        write(scope.join("pids.current"), "5\n");
+        write(scope.join("cpu.max"), "200000 100000\n");
+
+        let info = read_cgroup(root).unwrap();
+        assert_eq!(info.version, 2);
+        assert_eq!(info.path, "/user.slice/app.scope");
+
+        let memory = info.memory_max.unwrap();
+        assert_eq!((memory.value, memory.set_on.as_str()), (1 << 30, "/user.slice"));
+        // Usage of the cgroup the limit is enforced on.
+        assert_eq!(info.memory_current, Some(512 << 20));
+
+        let pids = info.pids_max.unwrap();
+        assert_eq!((pids.value, pids.set_on.as_str()), (64, "/user.slice/app.scope"));
+        assert_eq!(info.pids_current, Some(5));
+
+        let cpu = info.cpu_quota.unwrap();
+        assert_eq!((cpu.value, cpu.set_on.as_str()), (1.0, "/user.slice"));
+        assert!(info.memory_high.is_none());
+    }
+
+    #[test]
+    fn v2_without_limits() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        write(root.join("proc/self/cgroup"), "0::/\n");
+        write(root.join("sys/fs/cgroup/cgroup.controllers"), "memory\n");
+        write(root.join("sys/fs/cgroup/memory.current"), "4096\n");
+
+        let info = read_cgroup(root).unwrap();
+        assert!(!info.has_limits());
+        assert_eq!(info.memory_current, Some(4096));
+    }
+}
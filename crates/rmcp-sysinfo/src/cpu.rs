@@ -0,0 +1,189 @@
+//! CPU topology, caches and frequency scaling read from
+//! `/sys/devices/system/cpu`. Everything here is Linux-only; on other
+//! platforms (or in sandboxes without sysfs) the readers return nothing and
+//! callers fall back to what sysinfo knows.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SYSFS_CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+#[derive(Debug, Clone)]
+pub struct Topology {
+    pub cpu: usize,
+    pub package: Option<u32>,
+    pub core: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cache {
+    pub level: u32,
+    pub kind: String,
+    pub size: String,
+    /// Number of distinct instances of this cache across the system.
+    pub instances: usize,
+}
+
+impl Cache {
+    /// Conventional short name: L1d, L1i, L2, L3.
+    pub fn name(&self) -> String {
+        match self.kind.as_str() {
+            "Data" => format!("L{}d", self.level),
+            "Instruction" => format!("L{}i", self.level),
+            _ => format!("L{}", self.level),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Scaling {
+    pub driver: Option<String>,
+    pub governor: Option<String>,
+    pub available_governors: Option<String>,
+    pub min_khz: Option<u64>,
+    pub max_khz: Option<u64>,
+    pub hardware_max_khz: Option<u64>,
+}
+
+impl Scaling {
+    /// The policy maximum has been lowered below what the hardware supports,
+    /// e.g. by a power profile or thermal daemon.
+    pub fn is_capped(&self) -> bool {
+        matches!((self.max_khz, self.hardware_max_khz), (Some(max), Some(hw)) if max < hw)
+    }
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number(path: impl AsRef<Path>) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// `cpuN` directories under `root`, in numeric order.
+fn cpu_dirs(root: &Path) -> Vec<(usize, PathBuf)> {
+    let mut cpus: Vec<_> = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let index = name.to_str()?.strip_prefix("cpu")?.parse().ok()?;
+            Some((index, entry.path()))
+        })
+        .collect();
+    cpus.sort_by_key(|(index, _)| *index);
+    cpus
+}
+
+pub fn read_topology(root: &Path) -> Vec<Topology> {
+    cpu_dirs(root)
+        .into_iter()
+        .map(|(cpu, dir)| Topology {
+            cpu,
+            package: read_number(dir.join("topology/physical_package_id")).map(|n| n as u32),
+            core: read_number(dir.join("topology/core_id")).map(|n| n as u32),
+        })
+        .collect()
+}
+
+/// Caches grouped by level and type, counting each physical instance once
+/// (a cache shared by several CPUs lists the same `shared_cpu_list`).
+pub fn read_caches(root: &Path) -> Vec<Cache> {
+    let mut instances: BTreeMap<(u32, String, String), Vec<String>> = BTreeMap::new();
+
+    for (_, dir) in cpu_dirs(root) {
+        let Ok(entries) = fs::read_dir(dir.join("cache")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let index = entry.path();
+            if !entry.file_name().to_string_lossy().starts_with("index") {
+                continue;
+            }
+            let (Some(level), Some(kind), Some(size)) = (
+                read_number(index.join("level")),
+                read_trimmed(index.join("type")),
+                read_trimmed(index.join("size")),
+            ) else {
+                continue;
+            };
+            let shared = read_trimmed(index.join("shared_cpu_list")).unwrap_or_default();
+            let seen = instances.entry((level as u32, kind, size)).or_default();
+            if !seen.contains(&shared) {
+                seen.push(shared);
+            }
+        }
+    }
+
+    instances
+        .into_iter()
+        .map(|((level, kind, size), shared)| Cache {
+            level,
+            kind,
+            size,
+            instances: shared.len(),
+        })
+        .collect()
+}
+
+/// The sysfs id of a CPU named the way sysinfo and `/proc/stat` name them
+/// ("cpu3"). Ids have gaps when CPUs are offline, so a CPU's position in a
+/// list is not its id.
+pub fn cpu_id(name: &str) -> Option<usize> {
+    name.strip_prefix("cpu")?.parse().ok()
+}
+
+/// Frequency scaling of every CPU that has a `cpufreq` directory, keyed by
+/// CPU id.
+pub fn read_scaling(root: &Path) -> BTreeMap<usize, Scaling> {
+    cpu_dirs(root)
+        .into_iter()
+        .map(|(cpu, dir)| (cpu, dir.join("cpufreq")))
+        .filter(|(_, dir)| dir.is_dir())
+        .map(|(cpu, dir)| {
+            let scaling = Scaling {
+                driver: read_trimmed(dir.join("scaling_driver")),
+                governor: read_trimmed(dir.join("scaling_governor")),
+                available_governors: read_trimmed(dir.join("scaling_available_governors")),
+                min_khz: read_number(dir.join("scaling_min_freq")),
+                max_khz: read_number(dir.join("scaling_max_freq")),
+                hardware_max_khz: read_number(dir.join("cpuinfo_max_freq")),
+            };
+            (cpu, scaling)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn scaling_is_keyed_by_cpu_id() {
+        let root = tempfile::tempdir().unwrap();
+        // cpu1 is offline and has no cpufreq directory.
+        fs::create_dir_all(root.path().join("cpu1")).unwrap();
+        for (cpu, max) in [(0, "2000000"), (2, "3000000")] {
+            let dir = root.path().join(format!("cpu{}/cpufreq", cpu));
+            write(dir.join("scaling_governor"), "powersave\n");
+            write(dir.join("scaling_max_freq"), max);
+            write(dir.join("cpuinfo_max_freq"), "3000000\n");
+        }
+
+        let scaling = read_scaling(root.path());
+        assert_eq!(scaling.keys().copied().collect::<Vec<_>>(), [0, 2]);
+        assert!(scaling[&0].is_capped());
+        assert!(!scaling[&2].is_capped());
+        assert_eq!(scaling[&2].governor.as_deref(), Some("powersave"));
+        assert_eq!(cpu_id("cpu2").and_then(|id| scaling.get(&id)).and_then(|s| s.max_khz), Some(3_000_000));
+        assert_eq!(cpu_id("cpufreq"), None);
+    }
+}
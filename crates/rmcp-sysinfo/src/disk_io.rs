@@ -0,0 +1,95 @@
+//! Block device activity from `/proc/diskstats`. The counters are cumulative
+//! since boot, so callers take two readings and turn the difference into
+//! rates. Linux-only; elsewhere there are no devices.
+
+use std::fs;
+use std::path::Path;
+
+pub const PROC_ROOT: &str = "/proc";
+pub const SYSFS_BLOCK_ROOT: &str = "/sys/block";
+
+/// `/proc/diskstats` counts sectors of 512 bytes regardless of the device's
+/// real sector size.
+const SECTOR_SIZE: u64 = 512;
+
+#[derive(Debug, Clone, Default)]
+pub struct DiskStats {
+    pub name: String,
+    /// Whole disk rather than a partition (it has a `/sys/block` entry).
+    pub is_disk: bool,
+    pub reads: u64,
+    pub read_bytes: u64,
+    pub writes: u64,
+    pub written_bytes: u64,
+    pub in_flight: u64,
+    /// Milliseconds the device had I/O in flight.
+    pub busy_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskRate {
+    pub name: String,
+    pub is_disk: bool,
+    pub read_per_sec: f64,
+    pub written_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// Share of the window the device was busy, 0-100.
+    pub utilization: f64,
+    pub in_flight: u64,
+}
+
+impl DiskRate {
+    pub fn is_idle(&self) -> bool {
+        self.read_iops == 0.0 && self.write_iops == 0.0 && self.in_flight == 0
+    }
+}
+
+pub fn read_diskstats(proc_root: &Path, sysfs_block: &Path) -> Vec<DiskStats> {
+    let Ok(contents) = fs::read_to_string(proc_root.join("diskstats")) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            if fields.len() < 14 {
+                return None;
+            }
+            let number = |i: usize| fields[i].parse::<u64>().ok();
+            let name = fields[2].to_string();
+            Some(DiskStats {
+                is_disk: sysfs_block.join(&name).exists(),
+                name,
+                reads: number(3)?,
+                read_bytes: number(5)? * SECTOR_SIZE,
+                writes: number(7)?,
+                written_bytes: number(9)? * SECTOR_SIZE,
+                in_flight: number(11)?,
+                busy_ms: number(12)?,
+            })
+        })
+        .collect()
+}
+
+/// Rates between two readings taken `seconds` apart, in device order.
+/// Devices that appeared in between are skipped.
+pub fn rates(before: &[DiskStats], after: &[DiskStats], seconds: f64) -> Vec<DiskRate> {
+    after
+        .iter()
+        .filter_map(|now| {
+            let then = before.iter().find(|d| d.name == now.name)?;
+            let per_sec = |now: u64, then: u64| now.saturating_sub(then) as f64 / seconds;
+            Some(DiskRate {
+                name: now.name.clone(),
+                is_disk: now.is_disk,
+                read_per_sec: per_sec(now.read_bytes, then.read_bytes),
+                written_per_sec: per_sec(now.written_bytes, then.written_bytes),
+                read_iops: per_sec(now.reads, then.reads),
+                write_iops: per_sec(now.writes, then.writes),
+                utilization: (per_sec(now.busy_ms, then.busy_ms) / 10.0).min(100.0),
+                in_flight: now.in_flight,
+            })
+        })
+        .collect()
+}
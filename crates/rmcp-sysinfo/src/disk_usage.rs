@@ -0,0 +1,386 @@
+//! Space used below a directory, walked in parallel the way `du -x` does it:
+//! allocated blocks rather than apparent sizes, hard links counted once, no
+//! symlinks followed and other filesystems skipped. The walk stops at a
+//! deadline and reports what it saw so far. Other platforms get apparent
+//! sizes and no filesystem boundary.
+
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Device, inode, link count and allocated bytes of an entry.
+#[cfg(unix)]
+fn stat(meta: &fs::Metadata) -> (u64, u64, u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (meta.dev(), meta.ino(), meta.nlink(), meta.blocks() * 512)
+}
+
+#[cfg(not(unix))]
+fn stat(meta: &fs::Metadata) -> (u64, u64, u64, u64) {
+    (0, 0, 1, meta.len())
+}
+
+/// What a directory is if it is safe to delete or prune because its owner
+/// rebuilds or re-downloads it.
+fn reclaimable(path: &Path, name: &str, home: Option<&Path>) -> Option<&'static str> {
+    let parent = path.parent()?;
+    match name {
+        "target" if parent.join("Cargo.toml").is_file() => Some("Rust build output (cargo clean)"),
+        "node_modules" => Some("npm packages (reinstalled by npm install)"),
+        ".tox" | ".nox" => Some("Python test environments"),
+        ".gradle" => Some("Gradle caches"),
+        "DerivedData" if parent.ends_with("Library/Developer/Xcode") => Some("Xcode build output"),
+        ".cache" if Some(parent) == home || parent.parent() == Some(Path::new("/home")) => {
+            Some("user cache (~/.cache)")
+        }
+        "registry" if parent.file_name().is_some_and(|p| p == ".cargo") => Some("Cargo registry cache"),
+        "repository" if parent.file_name().is_some_and(|p| p == ".m2") => Some("Maven repository cache"),
+        "go-build" if parent.file_name().is_some_and(|p| p == ".cache") => Some("Go build cache"),
+        "docker" if parent == Path::new("/var/lib") => Some("Docker images and volumes (docker system prune)"),
+        "containers" if parent == Path::new("/var/lib") || parent.ends_with(".local/share") => {
+            Some("Podman images and volumes (podman system prune)")
+        }
+        "archives" if parent == Path::new("/var/cache/apt") => Some("downloaded packages (apt clean)"),
+        "journal" if parent == Path::new("/var/log") => Some("systemd journal (journalctl --vacuum-size)"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reclaimable {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub what: &'static str,
+}
+
+#[derive(Debug, Default)]
+pub struct Usage {
+    pub total_bytes: u64,
+    pub files: u64,
+    pub dirs: u64,
+    /// Directories down to the requested depth, largest first.
+    pub largest_dirs: Vec<Entry>,
+    /// Largest files anywhere below the root, largest first.
+    pub largest_files: Vec<Entry>,
+    /// Cache and build directories, largest first. Nested ones are counted
+    /// as part of the outermost.
+    pub reclaimable: Vec<Reclaimable>,
+    /// Mount points below the root that were not descended into.
+    pub other_filesystems: Vec<PathBuf>,
+    /// Directories that could not be read (usually permissions).
+    pub unreadable: u64,
+    /// The walk hit its deadline; sizes are lower bounds.
+    pub incomplete: bool,
+}
+
+struct Job {
+    path: PathBuf,
+    depth: usize,
+    /// Where bytes below this directory are accounted: the directory
+    /// itself within the requested depth, its ancestor at that depth below.
+    anchor: PathBuf,
+    /// Index into the reclaimable list this directory belongs to.
+    reclaim: Option<usize>,
+}
+
+#[derive(Default)]
+struct Tally {
+    by_anchor: HashMap<PathBuf, u64>,
+    by_reclaim: HashMap<usize, u64>,
+    files: BinaryHeap<Reverse<(u64, PathBuf)>>,
+    total_bytes: u64,
+    file_count: u64,
+    dir_count: u64,
+    unreadable: u64,
+}
+
+struct Queue {
+    jobs: Vec<Job>,
+    /// Jobs queued or being worked on; the walk is over at zero.
+    pending: usize,
+}
+
+struct Walk<'a> {
+    device: u64,
+    max_depth: usize,
+    top_n: usize,
+    deadline: Instant,
+    home: Option<&'a Path>,
+    queue: Mutex<Queue>,
+    ready: Condvar,
+    /// (device, inode) of files with more than one link already counted.
+    linked: Mutex<HashSet<(u64, u64)>>,
+    reclaimable: Mutex<Vec<(PathBuf, &'static str)>>,
+    other_filesystems: Mutex<Vec<PathBuf>>,
+    /// A directory was left half-read at the deadline.
+    cut_short: AtomicBool,
+}
+
+impl<'a> Walk<'a> {
+    fn new(
+        root: &Path,
+        device: u64,
+        max_depth: usize,
+        top_n: usize,
+        deadline: Instant,
+        home: Option<&'a Path>,
+    ) -> Self {
+        Self {
+            device,
+            max_depth,
+            top_n,
+            deadline,
+            home,
+            queue: Mutex::new(Queue {
+                jobs: vec![Job {
+                    path: root.to_path_buf(),
+                    depth: 0,
+                    anchor: root.to_path_buf(),
+                    reclaim: None,
+                }],
+                pending: 1,
+            }),
+            ready: Condvar::new(),
+            linked: Mutex::new(HashSet::new()),
+            reclaimable: Mutex::new(Vec::new()),
+            other_filesystems: Mutex::new(Vec::new()),
+            cut_short: AtomicBool::new(false),
+        }
+    }
+
+    fn next_job(&self) -> Option<Job> {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if Instant::now() >= self.deadline {
+                return None;
+            }
+            if let Some(job) = queue.jobs.pop() {
+                return Some(job);
+            }
+            if queue.pending == 0 {
+                return None;
+            }
+            queue = self
+                .ready
+                .wait_timeout(queue, Duration::from_millis(50))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    fn finish_job(&self, children: Vec<Job>) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.pending += children.len();
+        queue.pending -= 1;
+        queue.jobs.extend(children);
+        self.ready.notify_all();
+    }
+
+    fn work(&self) -> Tally {
+        let mut tally = Tally::default();
+        while let Some(job) = self.next_job() {
+            let children = self.visit(&job, &mut tally);
+            self.finish_job(children);
+        }
+        tally
+    }
+
+    fn add(&self, tally: &mut Tally, job: &Job, bytes: u64) {
+        tally.total_bytes += bytes;
+        *tally.by_anchor.entry(job.anchor.clone()).or_default() += bytes;
+        if let Some(index) = job.reclaim {
+            *tally.by_reclaim.entry(index).or_default() += bytes;
+        }
+    }
+
+    fn visit(&self, job: &Job, tally: &mut Tally) -> Vec<Job> {
+        tally.dir_count += 1;
+        let Ok(entries) = fs::read_dir(&job.path) else {
+            tally.unreadable += 1;
+            return Vec::new();
+        };
+        let mut children = Vec::new();
+        for entry in entries.flatten() {
+            // A single huge directory must not run past the budget either.
+            if Instant::now() >= self.deadline {
+                self.cut_short.store(true, Ordering::Relaxed);
+                break;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            let (device, inode, links, bytes) = stat(&meta);
+            let path = entry.path();
+            if meta.is_dir() {
+                if device != self.device {
+                    self.other_filesystems.lock().unwrap_or_else(|e| e.into_inner()).push(path);
+                    continue;
+                }
+                let depth = job.depth + 1;
+                let reclaim = job.reclaim.or_else(|| {
+                    let what = reclaimable(&path, &entry.file_name().to_string_lossy(), self.home)?;
+                    let mut list = self.reclaimable.lock().unwrap_or_else(|e| e.into_inner());
+                    list.push((path.clone(), what));
+                    Some(list.len() - 1)
+                });
+                let child = Job {
+                    anchor: if depth <= self.max_depth { path.clone() } else { job.anchor.clone() },
+                    path,
+                    depth,
+                    reclaim,
+                };
+                // The directory's own blocks belong to it, not its parent.
+                self.add(tally, &child, bytes);
+                children.push(child);
+                continue;
+            }
+            if links > 1 && !self.linked.lock().unwrap_or_else(|e| e.into_inner()).insert((device, inode))
+            {
+                continue;
+            }
+            tally.file_count += 1;
+            self.add(tally, job, bytes);
+            if meta.is_file() {
+                tally.files.push(Reverse((bytes, path)));
+                if tally.files.len() > self.top_n {
+                    tally.files.pop();
+                }
+            }
+        }
+        children
+    }
+}
+
+/// Walks `root` with up to `threads` workers until done or `budget` runs
+/// out. Directory sizes are reported down to `max_depth` levels below root.
+pub fn scan(root: &Path, max_depth: usize, top_n: usize, budget: Duration, threads: usize) -> std::io::Result<Usage> {
+    let meta = fs::metadata(root)?;
+    if !meta.is_dir() {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "not a directory"));
+    }
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let (device, _, _, root_bytes) = stat(&meta);
+    let walk = Walk::new(root, device, max_depth, top_n, Instant::now() + budget, home.as_deref());
+
+    let tallies: Vec<Tally> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1)).map(|_| scope.spawn(|| walk.work())).collect();
+        workers.into_iter().filter_map(|w| w.join().ok()).collect()
+    });
+
+    let mut usage = Usage {
+        // The root's own blocks, like every other directory's.
+        total_bytes: root_bytes,
+        incomplete: walk.queue.lock().unwrap_or_else(|e| e.into_inner()).pending > 0
+            || walk.cut_short.load(Ordering::Relaxed),
+        ..Default::default()
+    };
+    let mut by_anchor: HashMap<PathBuf, u64> = HashMap::new();
+    let mut by_reclaim: HashMap<usize, u64> = HashMap::new();
+    let mut files = Vec::new();
+    for tally in tallies {
+        usage.total_bytes += tally.total_bytes;
+        usage.files += tally.file_count;
+        usage.dirs += tally.dir_count;
+        usage.unreadable += tally.unreadable;
+        for (anchor, bytes) in tally.by_anchor {
+            *by_anchor.entry(anchor).or_default() += bytes;
+        }
+        for (index, bytes) in tally.by_reclaim {
+            *by_reclaim.entry(index).or_default() += bytes;
+        }
+        files.extend(tally.files.into_iter().map(|Reverse((bytes, path))| Entry { path, bytes }));
+    }
+
+    // Roll each anchor's bytes up into its ancestors below the root.
+    let mut totals: HashMap<&Path, u64> = HashMap::new();
+    for (anchor, bytes) in &by_anchor {
+        for dir in anchor.ancestors().take_while(|a| *a != root) {
+            *totals.entry(dir).or_default() += bytes;
+        }
+    }
+    usage.largest_dirs = totals
+        .into_iter()
+        .map(|(path, bytes)| Entry { path: path.to_path_buf(), bytes })
+        .collect();
+    usage.largest_dirs.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    usage.largest_dirs.truncate(top_n);
+
+    files.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    files.truncate(top_n);
+    usage.largest_files = files;
+
+    let found = walk.reclaimable.into_inner().unwrap_or_else(|e| e.into_inner());
+    usage.reclaimable = found
+        .into_iter()
+        .enumerate()
+        .map(|(index, (path, what))| Reclaimable {
+            path,
+            what,
+            bytes: by_reclaim.get(&index).copied().unwrap_or(0),
+        })
+        .filter(|r| r.bytes > 0)
+        .collect();
+    usage.reclaimable.sort_by_key(|r| Reverse(r.bytes));
+
+    usage.other_filesystems = walk.other_filesystems.into_inner().unwrap_or_else(|e| e.into_inner());
+    usage.other_filesystems.sort();
+    Ok(usage)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn blocks(path: &Path) -> u64 {
+        stat(&fs::symlink_metadata(path).unwrap()).3
+    }
+
+    #[test]
+    fn total_counts_every_directory_once_including_root() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("top.bin"), vec![1u8; 64 * 1024]).unwrap();
+        fs::write(root.join("a/b/inner.bin"), vec![2u8; 128 * 1024]).unwrap();
+        fs::hard_link(root.join("a/b/inner.bin"), root.join("a/link.bin")).unwrap();
+
+        let usage = scan(root, 1, 10, Duration::from_secs(30), 2).unwrap();
+        let expected: u64 = ["", "a", "a/b", "top.bin", "a/b/inner.bin"]
+            .iter()
+            .map(|p| blocks(&root.join(p)))
+            .sum();
+        assert!(!usage.incomplete);
+        assert_eq!(usage.total_bytes, expected);
+        assert_eq!((usage.files, usage.dirs), (2, 3));
+        assert_eq!(usage.largest_dirs[0].path, root.join("a"));
+        // Either name of the hard-linked file, counted once.
+        assert_eq!(usage.largest_files.len(), 2);
+        assert_eq!(usage.largest_files[0].bytes, blocks(&root.join("a/b/inner.bin")));
+    }
+
+    #[test]
+    fn deadline_stops_inside_a_directory() {
+        let root = tempfile::tempdir().unwrap();
+        for i in 0..50 {
+            fs::write(root.path().join(i.to_string()), "x").unwrap();
+        }
+        let device = stat(&fs::metadata(root.path()).unwrap()).0;
+        let walk = Walk::new(root.path(), device, 1, 10, Instant::now(), None);
+        let job = walk.queue.lock().unwrap().jobs.pop().unwrap();
+        let mut tally = Tally::default();
+        walk.visit(&job, &mut tally);
+        assert_eq!(tally.file_count, 0);
+        assert!(walk.cut_short.load(Ordering::Relaxed));
+
+        let usage = scan(root.path(), 1, 10, Duration::ZERO, 1).unwrap();
+        assert!(usage.incomplete);
+    }
+}
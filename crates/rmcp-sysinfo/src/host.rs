@@ -0,0 +1,172 @@
+//! Host identity that sysinfo does not cover: product, board and firmware
+//! data and the chassis type from DMI (`/sys/class/dmi/id`), hypervisor
+//! detection and the configured timezone. DMI is absent on most ARM boards and
+//! hidden in some containers; everything here is then simply unknown.
+
+use std::fs;
+use std::path::Path;
+
+/// Filesystem root the probes are resolved against.
+pub const ROOT: &str = "/";
+
+#[derive(Debug, Clone, Default)]
+pub struct Dmi {
+    pub sys_vendor: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    pub board_vendor: Option<String>,
+    pub board_name: Option<String>,
+    pub bios_vendor: Option<String>,
+    pub bios_version: Option<String>,
+    pub bios_date: Option<String>,
+    /// SMBIOS chassis type code (3 = desktop, 10 = notebook, 23 = rack mount, ...).
+    pub chassis_type: Option<u8>,
+}
+
+impl Dmi {
+    /// "LENOVO 20XW0055GE", skipping vendor placeholders.
+    pub fn product(&self) -> Option<String> {
+        let parts: Vec<&str> = [&self.sys_vendor, &self.product_name]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Firmware leaves these in fields it does not fill in.
+fn is_placeholder(value: &str) -> bool {
+    let value = value.to_ascii_lowercase();
+    value.is_empty()
+        || value == "none"
+        || value == "default string"
+        || value.contains("to be filled")
+        || value.contains("not specified")
+        || value.contains("not applicable")
+        || value == "system product name"
+        || value == "system manufacturer"
+        || value == "0123456789"
+}
+
+fn read_field(dir: &Path, name: &str) -> Option<String> {
+    let value = fs::read_to_string(dir.join(name)).ok()?.trim().to_string();
+    (!is_placeholder(&value)).then_some(value)
+}
+
+pub fn read_dmi(root: &Path) -> Option<Dmi> {
+    let dir = root.join("sys/class/dmi/id");
+    if !dir.is_dir() {
+        return None;
+    }
+    Some(Dmi {
+        sys_vendor: read_field(&dir, "sys_vendor"),
+        product_name: read_field(&dir, "product_name"),
+        product_version: read_field(&dir, "product_version"),
+        board_vendor: read_field(&dir, "board_vendor"),
+        board_name: read_field(&dir, "board_name"),
+        bios_vendor: read_field(&dir, "bios_vendor"),
+        bios_version: read_field(&dir, "bios_version"),
+        bios_date: read_field(&dir, "bios_date"),
+        chassis_type: read_field(&dir, "chassis_type").and_then(|t| t.parse().ok()),
+    })
+}
+
+/// SMBIOS name of a chassis type and the broad class it belongs to (the
+/// same classes `hostnamectl` reports), if any.
+pub fn chassis(code: u8) -> (&'static str, Option<&'static str>) {
+    match code {
+        3 => ("Desktop", Some("desktop")),
+        4 => ("Low Profile Desktop", Some("desktop")),
+        5 => ("Pizza Box", Some("desktop")),
+        6 => ("Mini Tower", Some("desktop")),
+        7 => ("Tower", Some("desktop")),
+        8 => ("Portable", Some("laptop")),
+        9 => ("Laptop", Some("laptop")),
+        10 => ("Notebook", Some("laptop")),
+        11 => ("Hand Held", Some("handset")),
+        12 => ("Docking Station", None),
+        13 => ("All in One", Some("desktop")),
+        14 => ("Sub Notebook", Some("laptop")),
+        15 => ("Space-saving", Some("desktop")),
+        16 => ("Lunch Box", Some("desktop")),
+        17 => ("Main Server Chassis", Some("server")),
+        23 => ("Rack Mount Chassis", Some("server")),
+        24 => ("Sealed-case PC", Some("desktop")),
+        25 => ("Multi-system Chassis", Some("server")),
+        28 => ("Blade", Some("server")),
+        29 => ("Blade Enclosure", Some("server")),
+        30 => ("Tablet", Some("tablet")),
+        31 => ("Convertible", Some("convertible")),
+        32 => ("Detachable", Some("convertible")),
+        33 => ("IoT Gateway", Some("embedded")),
+        34 => ("Embedded PC", Some("embedded")),
+        35 => ("Mini PC", Some("desktop")),
+        36 => ("Stick PC", Some("desktop")),
+        1 => ("Other", None),
+        _ => ("Unknown", None),
+    }
+}
+
+/// The hypervisor we run under, from `/sys/hypervisor`, DMI vendor strings
+/// and finally the CPU's `hypervisor` flag, roughly as `systemd-detect-virt`
+/// does it.
+pub fn detect_hypervisor(root: &Path, dmi: Option<&Dmi>) -> Option<&'static str> {
+    if let Ok(kind) = fs::read_to_string(root.join("sys/hypervisor/type")) {
+        if kind.trim() == "xen" {
+            return Some("xen");
+        }
+    }
+
+    if let Some(dmi) = dmi {
+        let fields = [&dmi.sys_vendor, &dmi.product_name, &dmi.bios_vendor, &dmi.board_vendor];
+        for field in fields.into_iter().flatten() {
+            let vendor = match field.as_str() {
+                v if v.starts_with("KVM") => "kvm",
+                v if v.starts_with("QEMU") => "qemu",
+                v if v.starts_with("VMware") || v.starts_with("VMW") => "vmware",
+                v if v.starts_with("innotek GmbH") || v.starts_with("VirtualBox") || v.starts_with("Oracle Corporation") => {
+                    "oracle"
+                }
+                v if v.starts_with("Xen") => "xen",
+                v if v.starts_with("Bochs") => "bochs",
+                v if v.starts_with("Parallels") => "parallels",
+                v if v.starts_with("BHYVE") => "bhyve",
+                v if v.starts_with("Amazon EC2") => "amazon",
+                v if v.starts_with("Google Compute Engine") => "google",
+                v if v == "Virtual Machine" && dmi.sys_vendor.as_deref() == Some("Microsoft Corporation") => {
+                    "microsoft"
+                }
+                _ => continue,
+            };
+            return Some(vendor);
+        }
+    }
+
+    let cpuinfo = fs::read_to_string(root.join("proc/cpuinfo")).unwrap_or_default();
+    cpuinfo
+        .lines()
+        .find(|l| l.starts_with("flags"))
+        .is_some_and(|flags| flags.split_whitespace().any(|f| f == "hypervisor"))
+        .then_some("unknown hypervisor")
+}
+
+/// The configured timezone name: `TZ`, then `/etc/timezone`, then the zone
+/// `/etc/localtime` links to.
+pub fn timezone(root: &Path) -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':');
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+    if let Ok(name) = fs::read_to_string(root.join("etc/timezone")) {
+        let name = name.trim();
+        if !name.is_empty() {
+            return Some(name.to_string());
+        }
+    }
+    let target = fs::read_link(root.join("etc/localtime")).ok()?;
+    let target = target.to_string_lossy();
+    target.split_once("zoneinfo/").map(|(_, zone)| zone.to_string())
+}
@@ -0,0 +1,303 @@
+//! Hardware monitor chips from `/sys/class/hwmon` (temperatures, fans,
+//! voltages, currents, power, energy, humidity) and RAPL energy counters from
+//! `/sys/class/powercap`, which callers read twice to get CPU package watts.
+//! Linux-only. RAPL counters are root-only on most kernels since 5.10.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SYSFS_HWMON_ROOT: &str = "/sys/class/hwmon";
+pub const SYSFS_POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+    Current,
+    Power,
+    Energy,
+    Humidity,
+}
+
+impl SensorKind {
+    pub const ALL: [SensorKind; 7] = [
+        SensorKind::Temperature,
+        SensorKind::Fan,
+        SensorKind::Voltage,
+        SensorKind::Current,
+        SensorKind::Power,
+        SensorKind::Energy,
+        SensorKind::Humidity,
+    ];
+
+    /// Attribute prefix in sysfs, e.g. `in` for `in0_input`.
+    fn prefix(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "temp",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "in",
+            SensorKind::Current => "curr",
+            SensorKind::Power => "power",
+            SensorKind::Energy => "energy",
+            SensorKind::Humidity => "humidity",
+        }
+    }
+
+    /// Divisor from the sysfs integer to the unit below.
+    fn scale(self) -> f64 {
+        match self {
+            SensorKind::Fan => 1.0,
+            SensorKind::Power | SensorKind::Energy => 1_000_000.0,
+            _ => 1000.0,
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+            SensorKind::Current => "A",
+            SensorKind::Power => "W",
+            SensorKind::Energy => "J",
+            SensorKind::Humidity => "%",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reading {
+    pub kind: SensorKind,
+    /// The chip's label, or the attribute name ("fan1") when it has none.
+    pub label: String,
+    pub value: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+    /// The chip flags the reading as out of range.
+    pub alarm: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Chip {
+    /// Driver name, e.g. "coretemp", "nct6775", "thinkpad".
+    pub name: String,
+    /// The `hwmonN` directory name.
+    pub id: String,
+    pub readings: Vec<Reading>,
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_scaled(dir: &Path, attribute: &str, scale: f64) -> Option<f64> {
+    read_trimmed(dir.join(attribute))?.parse::<f64>().ok().map(|v| v / scale)
+}
+
+/// Sensor indices present for `prefix`, in order ("fan1", "fan2", ...).
+fn indices(dir: &Path, prefix: &str) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut found: Vec<u32> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let rest = name.strip_prefix(prefix)?;
+            let index = rest.strip_suffix("_input").or_else(|| rest.strip_suffix("_average"))?;
+            index.parse().ok()
+        })
+        .collect();
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+fn read_readings(dir: &Path) -> Vec<Reading> {
+    let mut readings = Vec::new();
+    for kind in SensorKind::ALL {
+        let prefix = kind.prefix();
+        let scale = kind.scale();
+        for index in indices(dir, prefix) {
+            let attribute = |suffix: &str| format!("{}{}_{}", prefix, index, suffix);
+            // Power meters often only provide a running average.
+            let Some(value) = read_scaled(dir, &attribute("input"), scale)
+                .or_else(|| read_scaled(dir, &attribute("average"), scale))
+            else {
+                continue;
+            };
+            readings.push(Reading {
+                kind,
+                label: read_trimmed(dir.join(attribute("label"))).unwrap_or_else(|| format!("{}{}", prefix, index)),
+                value,
+                min: read_scaled(dir, &attribute("min"), scale),
+                max: read_scaled(dir, &attribute("max"), scale),
+                crit: read_scaled(dir, &attribute("crit"), scale),
+                alarm: read_trimmed(dir.join(attribute("alarm"))).is_some_and(|a| a == "1"),
+            });
+        }
+    }
+    readings
+}
+
+/// Every hwmon chip with at least one readable sensor, ordered by `hwmonN`.
+pub fn read_chips(root: &Path) -> Vec<Chip> {
+    let Ok(entries) = fs::read_dir(root) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    dirs.sort_by_key(|p| {
+        p.file_name()
+            .and_then(|n| n.to_str()?.strip_prefix("hwmon")?.parse::<u32>().ok())
+            .unwrap_or(u32::MAX)
+    });
+
+    dirs.iter()
+        .filter_map(|dir| {
+            // Old drivers keep their attributes on the parent device.
+            let mut readings = read_readings(dir);
+            if readings.is_empty() {
+                readings = read_readings(&dir.join("device"));
+            }
+            if readings.is_empty() {
+                return None;
+            }
+            Some(Chip {
+                name: read_trimmed(dir.join("name")).unwrap_or_else(|| "unknown".to_string()),
+                id: dir.file_name()?.to_string_lossy().into_owned(),
+                readings,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct RaplZone {
+    /// "package-0", or "package-0/core" for a subzone.
+    pub name: String,
+    /// `None` when the counter is not readable (usually needs root).
+    pub energy_uj: Option<u64>,
+    /// The counter wraps around at this value.
+    pub max_energy_uj: u64,
+}
+
+/// RAPL zones and subzones, e.g. `intel-rapl:0` and `intel-rapl:0:1`. AMD
+/// CPUs expose theirs under the same names.
+pub fn read_rapl(root: &Path) -> Vec<RaplZone> {
+    let Ok(entries) = fs::read_dir(root) else { return Vec::new() };
+    let mut dirs: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|e| (e.file_name().to_string_lossy().into_owned(), e.path()))
+        .filter(|(name, _)| name.starts_with("intel-rapl:"))
+        .collect();
+    dirs.sort();
+
+    dirs.iter()
+        .map(|(id, dir)| {
+            let name = read_trimmed(dir.join("name")).unwrap_or_else(|| id.clone());
+            // Subzones are named after what they measure; qualify them with
+            // their package.
+            let name = match id.rsplit_once(':').filter(|(parent, _)| parent.matches(':').count() == 1) {
+                Some((parent, _)) => {
+                    let package = read_trimmed(root.join(parent).join("name")).unwrap_or_else(|| parent.to_string());
+                    format!("{}/{}", package, name)
+                }
+                None => name,
+            };
+            RaplZone {
+                name,
+                energy_uj: read_trimmed(dir.join("energy_uj")).and_then(|e| e.parse().ok()),
+                max_energy_uj: read_trimmed(dir.join("max_energy_range_uj"))
+                    .and_then(|e| e.parse().ok())
+                    .unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+/// Average watts per zone between two readings taken `seconds` apart.
+pub fn rapl_watts(before: &[RaplZone], after: &[RaplZone], seconds: f64) -> Vec<(String, f64)> {
+    after
+        .iter()
+        .filter_map(|now| {
+            let then = before.iter().find(|z| z.name == now.name)?;
+            let (start, end) = (then.energy_uj?, now.energy_uj?);
+            let used = if end >= start {
+                end - start
+            } else {
+                // The counter wrapped in between.
+                now.max_energy_uj.saturating_sub(start) + end
+            };
+            Some((now.name.clone(), used as f64 / 1_000_000.0 / seconds))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn chips_are_scaled_to_units() {
+        let root = tempfile::tempdir().unwrap();
+        let chip = root.path().join("hwmon0");
+        write(chip.join("name"), "nct6775\n");
+        write(chip.join("temp1_input"), "45500\n");
+        write(chip.join("temp1_label"), "CPU\n");
+        write(chip.join("temp1_crit"), "100000\n");
+        write(chip.join("fan1_input"), "1200\n");
+        write(chip.join("fan1_alarm"), "1\n");
+        write(chip.join("in0_input"), "1032\n");
+        write(chip.join("power1_average"), "15500000\n");
+        // Old drivers keep their attributes on the device.
+        write(root.path().join("hwmon10/device/temp2_input"), "30000\n");
+        fs::create_dir_all(root.path().join("hwmon2")).unwrap();
+
+        let chips = read_chips(root.path());
+        assert_eq!(chips.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), ["hwmon0", "hwmon10"]);
+        let readings = &chips[0].readings;
+        assert_eq!(chips[0].name, "nct6775");
+
+        let temp = readings.iter().find(|r| r.kind == SensorKind::Temperature).unwrap();
+        assert_eq!((temp.label.as_str(), temp.value, temp.crit), ("CPU", 45.5, Some(100.0)));
+        let fan = readings.iter().find(|r| r.kind == SensorKind::Fan).unwrap();
+        assert_eq!((fan.label.as_str(), fan.value, fan.alarm), ("fan1", 1200.0, true));
+        let volts = readings.iter().find(|r| r.kind == SensorKind::Voltage).unwrap();
+        assert_eq!(volts.value, 1.032);
+        let power = readings.iter().find(|r| r.kind == SensorKind::Power).unwrap();
+        assert_eq!(power.value, 15.5);
+
+        assert_eq!(chips[1].name, "unknown");
+        assert_eq!(chips[1].readings[0].value, 30.0);
+    }
+
+    #[test]
+    fn rapl_energy_becomes_watts() {
+        let root = tempfile::tempdir().unwrap();
+        let package = root.path().join("intel-rapl:0");
+        write(package.join("name"), "package-0\n");
+        write(package.join("energy_uj"), "1000000\n");
+        write(package.join("max_energy_range_uj"), "262143328850\n");
+        let core = root.path().join("intel-rapl:0:0");
+        write(core.join("name"), "core\n");
+        write(core.join("energy_uj"), "262142328850\n");
+        write(core.join("max_energy_range_uj"), "262143328850\n");
+        fs::create_dir_all(root.path().join("intel-rapl:1")).unwrap();
+
+        let before = read_rapl(root.path());
+        assert_eq!(
+            before.iter().map(|z| z.name.as_str()).collect::<Vec<_>>(),
+            ["package-0", "package-0/core", "intel-rapl:1"]
+        );
+        assert!(before[2].energy_uj.is_none());
+
+        write(package.join("energy_uj"), "31000000\n");
+        // The core counter wraps around.
+        write(core.join("energy_uj"), "2000000\n");
+        let watts = rapl_watts(&before, &read_rapl(root.path()), 2.0);
+        assert_eq!(watts, [("package-0".to_string(), 15.0), ("package-0/core".to_string(), 1.5)]);
+    }
+}
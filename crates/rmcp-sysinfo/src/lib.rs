@@ -1,11 +1,32 @@
+pub mod cgroup;
+pub mod cpu;
+pub mod disk_io;
+pub mod disk_usage;
+pub mod host;
+pub mod hwmon;
+pub mod mounts;
+pub mod net_io;
+pub mod pressure;
+pub mod proc_files;
+pub mod process_query;
+pub mod process_tree;
+pub mod thermal;
+
 use rmcp::{
     handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
     model::*,
     ErrorData as McpError,
 };
 use schemars::JsonSchema;
+use proc_files::FdKind;
+use process_query::{ProcessQuery, SortKey};
+use process_tree::ProcessTree;
 use serde::{Deserialize, Serialize};
-use sysinfo::{System, Disks, Networks, Components, Users, CpuRefreshKind, MemoryRefreshKind, RefreshKind};
+use std::path::PathBuf;
+use sysinfo::{
+    System, Disks, Networks, Components, Users, CpuRefreshKind, MemoryRefreshKind, ProcessRefreshKind,
+    ProcessesToUpdate, RefreshKind,
+};
 
 #[derive(Debug)]
 pub struct SysinfoServer {
@@ -49,6 +70,136 @@ pub struct ProcessIdParams {
     pub pid: u32,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SystemInfoParams {
+    #[schemars(description = "Compute CPU and memory percentages against this process's cgroup limits \
+        (container or systemd slice) instead of host totals (default false)")]
+    #[serde(default)]
+    pub relative_to_cgroup: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HwmonParams {
+    #[schemars(description = "Window for measuring CPU package power in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ThermalParams {
+    #[schemars(description = "Window for watching throttle counters and CPU load in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DiskInfoParams {
+    #[schemars(description = "Also list pseudo filesystems such as tmpfs, overlay and squashfs (default false)")]
+    #[serde(default)]
+    pub include_pseudo: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiskHogsParams {
+    #[schemars(description = "Directory to investigate, e.g. '/' or '/home/me'")]
+    pub path: String,
+    #[schemars(description = "How many levels below the path to report directory sizes for (default 2, max 6)")]
+    #[serde(default)]
+    pub depth: Option<usize>,
+    #[schemars(description = "Number of directories, files and reclaimable entries to show (default 10, max 100)")]
+    #[serde(default)]
+    pub top_n: Option<usize>,
+    #[schemars(description = "Time budget for the walk in seconds (default 10, max 120)")]
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DiskIoParams {
+    #[schemars(description = "Sampling window for rates in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = "Number of top processes by disk I/O to show (default 5)")]
+    #[serde(default)]
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkStatsParams {
+    #[schemars(description = "Sampling window for rates in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = "Number of processes to show in the per-process estimate (default 5)")]
+    #[serde(default)]
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessFilesParams {
+    #[schemars(description = "Process ID (PID) to list open files for")]
+    pub pid: u32,
+    #[schemars(description = "Only show descriptors and mappings whose target contains this text, e.g. a file path")]
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct QueryProcessesParams {
+    #[schemars(description = "Only processes owned by this user name")]
+    #[serde(default)]
+    pub user: Option<String>,
+    #[schemars(description = "Regex matched against the process name")]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[schemars(description = "Regex matched against the full command line")]
+    #[serde(default)]
+    pub cmdline: Option<String>,
+    #[schemars(description = "Substring of the executable path, e.g. '/usr/lib/firefox'")]
+    #[serde(default)]
+    pub exe: Option<String>,
+    #[schemars(description = "Only processes whose working directory is inside this path")]
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[schemars(description = "Status: running, sleeping, zombie, disk-sleep, stopped or dead")]
+    #[serde(default)]
+    pub status: Option<String>,
+    #[schemars(description = "Minimum CPU usage in percent")]
+    #[serde(default)]
+    pub min_cpu: Option<f32>,
+    #[schemars(description = "Minimum resident memory in MB")]
+    #[serde(default)]
+    pub min_memory_mb: Option<u64>,
+    #[schemars(description = "Only processes running for at least this many seconds")]
+    #[serde(default)]
+    pub min_age_seconds: Option<u64>,
+    #[schemars(description = "Only processes running for at most this many seconds")]
+    #[serde(default)]
+    pub max_age_seconds: Option<u64>,
+    #[schemars(description = "Sort by: cpu, memory, age, pid, name or user (default 'cpu')")]
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    #[schemars(description = "Sort ascending instead of descending (default false)")]
+    #[serde(default)]
+    pub ascending: Option<bool>,
+    #[schemars(description = "Columns to show: pid, ppid, user, status, cpu, memory, age, name, exe, cwd, cmd \
+        (default pid, cpu, memory, name)")]
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[schemars(description = "Maximum number of rows (default 50)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessTreeParams {
+    #[schemars(description = "Root the tree at this PID (default: the whole system)")]
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[schemars(description = "Maximum depth below the root to expand (default: unlimited)")]
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
 fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
@@ -65,6 +216,37 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// "Vcore: 1.032 V (min 0.800 V, max 1.500 V)" with the precision each kind
+/// of sensor warrants.
+fn format_reading(reading: &hwmon::Reading) -> String {
+    let decimals = match reading.kind {
+        hwmon::SensorKind::Fan => 0,
+        hwmon::SensorKind::Voltage | hwmon::SensorKind::Current => 3,
+        hwmon::SensorKind::Power => 2,
+        _ => 1,
+    };
+    let unit = reading.kind.unit();
+    let value = |v: f64| {
+        if unit.starts_with('°') || unit == "%" {
+            format!("{:.*}{}", decimals, v, unit)
+        } else {
+            format!("{:.*} {}", decimals, v, unit)
+        }
+    };
+    let mut line = format!("{}: {}", reading.label, value(reading.value));
+    let limits: Vec<String> = [("min", reading.min), ("max", reading.max), ("crit", reading.crit)]
+        .into_iter()
+        .filter_map(|(name, limit)| Some(format!("{} {}", name, value(limit.filter(|l| *l != 0.0)?))))
+        .collect();
+    if !limits.is_empty() {
+        line.push_str(&format!(" ({})", limits.join(", ")));
+    }
+    if reading.alarm {
+        line.push_str(" ALARM");
+    }
+    line
+}
+
 fn format_duration(seconds: u64) -> String {
     if seconds < 60 {
         format!("{}s", seconds)
@@ -89,8 +271,12 @@ fn format_duration(seconds: u64) -> String {
 
 #[rmcp::tool_router]
 impl SysinfoServer {
-    #[rmcp::tool(description = "Get system overview: CPU usage, memory, disk space, uptime")]
-    pub async fn get_system_info(&self) -> Result<CallToolResult, McpError> {
+    #[rmcp::tool(description = "Get system overview: CPU usage, memory, disk space, uptime. Inside a container \
+        or systemd slice also reports the cgroup CPU quota and memory limit")]
+    pub async fn get_system_info(
+        &self,
+        Parameters(params): Parameters<SystemInfoParams>,
+    ) -> Result<CallToolResult, McpError> {
         let mut sys = System::new_with_specifics(
             RefreshKind::nothing()
                 .with_cpu(CpuRefreshKind::everything())
@@ -98,19 +284,55 @@ impl SysinfoServer {
         );
 
         // Need to wait a bit for CPU measurement
+        let root = std::path::Path::new(cgroup::ROOT);
+        let cgroup_before = cgroup::read_cgroup(root);
+        let started = std::time::Instant::now();
         std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
         sys.refresh_cpu_all();
+        let cgroup_info = cgroup::read_cgroup(root);
+        let runtime = cgroup::detect_runtime(root);
+
+        // Cores the cgroup used over the sampling interval
+        let cgroup_cores = match (
+            cgroup_before.as_ref().and_then(|c| c.cpu_usage_us),
+            cgroup_info.as_ref().and_then(|c| c.cpu_usage_us),
+        ) {
+            (Some(before), Some(after)) => {
+                Some(after.saturating_sub(before) as f64 / started.elapsed().as_micros().max(1) as f64)
+            }
+            _ => None,
+        };
 
         let disks = Disks::new_with_refreshed_list();
 
         // CPU info
         let cpu_count = sys.cpus().len();
-        let cpu_usage: f32 = sys.cpus().iter().map(|c| c.cpu_usage()).sum::<f32>() / cpu_count as f32;
+        let mut cpu_usage: f32 = sys.cpus().iter().map(|c| c.cpu_usage()).sum::<f32>() / cpu_count as f32;
         let cpu_name = sys.cpus().first().map(|c| c.brand()).unwrap_or("Unknown");
 
         // Memory info
-        let total_mem = sys.total_memory();
-        let used_mem = sys.used_memory();
+        let mut total_mem = sys.total_memory();
+        let mut used_mem = sys.used_memory();
+
+        // Optionally measure against the cgroup instead of the host
+        let relative = params.relative_to_cgroup.unwrap_or(false);
+        let mut cpu_scope = "";
+        let mut mem_scope = "";
+        if relative {
+            if let (Some(quota), Some(cores)) =
+                (cgroup_info.as_ref().and_then(|c| c.cpu_quota.as_ref()).map(|q| q.value), cgroup_cores)
+            {
+                cpu_usage = (cores / quota * 100.0) as f32;
+                cpu_scope = " of cgroup quota";
+            }
+            if let Some((max, current)) =
+                cgroup_info.as_ref().and_then(|c| Some((c.memory_max.as_ref()?.value, c.memory_current?)))
+            {
+                total_mem = max;
+                used_mem = current;
+                mem_scope = " of cgroup limit";
+            }
+        }
         let mem_percent = (used_mem as f64 / total_mem as f64 * 100.0) as u64;
 
         // Swap info
@@ -133,13 +355,13 @@ impl SysinfoServer {
         // Load average (Unix only)
         let load = System::load_average();
 
-        let output = format!(
+        let mut output = format!(
             "System Information:\n\
              \n\
              CPU: {} ({} cores)\n\
-             CPU Usage: {:.1}%\n\
+             CPU Usage: {:.1}%{}\n\
              \n\
-             Memory: {} / {} ({:.0}%)\n\
+             Memory: {} / {} ({:.0}%{})\n\
              Swap: {} / {}\n\
              \n\
              Disk: {} / {} free\n\
@@ -147,38 +369,681 @@ impl SysinfoServer {
              Uptime: {}h {}m\n\
              Load Average: {:.2} {:.2} {:.2} (1m 5m 15m)",
             cpu_name, cpu_count,
-            cpu_usage,
-            format_bytes(used_mem), format_bytes(total_mem), mem_percent,
+            cpu_usage, cpu_scope,
+            format_bytes(used_mem), format_bytes(total_mem), mem_percent, mem_scope,
             format_bytes(used_swap), format_bytes(total_swap),
             format_bytes(free_disk), format_bytes(total_disk),
             uptime_hours, uptime_mins,
             load.one, load.five, load.fifteen
         );
 
+        // Container and cgroup limits
+        let limits = cgroup_info.as_ref().filter(|c| c.has_limits());
+        if runtime.is_some() || limits.is_some() {
+            output.push_str(&format!("\n\nContainer: {}", runtime.unwrap_or("none detected")));
+        }
+        if let Some(c) = limits {
+            output.push_str(&format!("\n  Cgroup: v{} {}", c.version, c.path));
+            // Limits inherited from an ancestor name it.
+            let set_on = |set_on: &str| {
+                if set_on == c.path { String::new() } else { format!(" [set on {}]", set_on) }
+            };
+            match (&c.cpu_quota, cgroup_cores) {
+                (Some(quota), Some(cores)) => output.push_str(&format!(
+                    "\n  CPU Quota: {:.2} cores (using {:.2}){}",
+                    quota.value,
+                    cores,
+                    set_on(&quota.set_on)
+                )),
+                (Some(quota), None) => {
+                    output.push_str(&format!("\n  CPU Quota: {:.2} cores{}", quota.value, set_on(&quota.set_on)))
+                }
+                (None, _) => output.push_str("\n  CPU Quota: unlimited"),
+            }
+            match (&c.memory_max, c.memory_current) {
+                (Some(max), Some(current)) => output.push_str(&format!(
+                    "\n  Memory Limit: {} (using {}, {:.0}%){}",
+                    format_bytes(max.value),
+                    format_bytes(current),
+                    current as f64 / max.value as f64 * 100.0,
+                    set_on(&max.set_on)
+                )),
+                (Some(max), None) => {
+                    output.push_str(&format!("\n  Memory Limit: {}{}", format_bytes(max.value), set_on(&max.set_on)))
+                }
+                (None, Some(current)) => {
+                    output.push_str(&format!("\n  Memory Limit: unlimited (using {})", format_bytes(current)))
+                }
+                (None, None) => output.push_str("\n  Memory Limit: unlimited"),
+            }
+            if let Some(high) = &c.memory_high {
+                output.push_str(&format!(
+                    "\n  Memory Throttle (memory.high): {}{}",
+                    format_bytes(high.value),
+                    set_on(&high.set_on)
+                ));
+            }
+            if let Some(max) = &c.pids_max {
+                output.push_str(&format!(
+                    "\n  Processes: {} / {}{}",
+                    c.pids_current.unwrap_or(0),
+                    max.value,
+                    set_on(&max.set_on)
+                ));
+            }
+        }
+        if relative && cpu_scope.is_empty() && mem_scope.is_empty() {
+            output.push_str("\n\nNo cgroup CPU quota or memory limit applies; percentages are against host totals.");
+        }
+
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get detailed disk usage for all mounted filesystems")]
-    pub async fn get_disk_info(&self) -> Result<CallToolResult, McpError> {
-        let disks = Disks::new_with_refreshed_list();
+    #[rmcp::tool(description = "Get host identity: hostname, OS and distribution, kernel, architecture, boot time, \
+        timezone, virtualization or container, and chassis (laptop, desktop, server) with the hardware model")]
+    pub async fn get_host_info(&self) -> Result<CallToolResult, McpError> {
+        let root = std::path::Path::new(host::ROOT);
+        let dmi = host::read_dmi(root);
+        let hypervisor = host::detect_hypervisor(root, dmi.as_ref());
+        let runtime = cgroup::detect_runtime(std::path::Path::new(cgroup::ROOT));
+
+        let mut output = String::from("Host Information:\n\n");
+        output.push_str(&format!("Hostname: {}\n", System::host_name().unwrap_or_else(|| "unknown".to_string())));
+        let os = match (System::name(), System::os_version()) {
+            (Some(name), Some(version)) => format!("{} {}", name, version),
+            (Some(name), None) => name,
+            _ => System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        };
+        output.push_str(&format!("OS: {}\n", os));
+        let like = System::distribution_id_like();
+        if like.is_empty() {
+            output.push_str(&format!("Distribution ID: {}\n", System::distribution_id()));
+        } else {
+            output.push_str(&format!("Distribution ID: {} (like {})\n", System::distribution_id(), like.join(", ")));
+        }
+        output.push_str(&format!("Kernel: {}\n", System::kernel_version().unwrap_or_else(|| "unknown".to_string())));
+        output.push_str(&format!("Architecture: {}\n", System::cpu_arch()));
+        if let Some(boot) = chrono::DateTime::from_timestamp(System::boot_time() as i64, 0) {
+            output.push_str(&format!(
+                "Boot Time: {} (up {})\n",
+                boot.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                format_duration(System::uptime())
+            ));
+        }
+        let offset = chrono::Local::now().format("%:z");
+        match host::timezone(root) {
+            Some(zone) => output.push_str(&format!("Timezone: {} (UTC{})\n", zone, offset)),
+            None => output.push_str(&format!("Timezone: UTC{}\n", offset)),
+        }
+        output.push_str(&format!("Virtualization: {}\n", hypervisor.unwrap_or("none detected")));
+        if let Some(runtime) = runtime {
+            output.push_str(&format!("Container: {}\n", runtime));
+        }
+
+        match dmi.as_ref().and_then(|d| d.chassis_type) {
+            Some(code) => {
+                let (name, class) = host::chassis(code);
+                match class {
+                    Some(class) => output.push_str(&format!("Chassis: {} ({})\n", class, name)),
+                    None => output.push_str(&format!("Chassis: {}\n", name)),
+                }
+            }
+            None => output.push_str("Chassis: unknown (no DMI data)\n"),
+        }
+        if let Some(dmi) = &dmi {
+            if let Some(product) = dmi.product() {
+                output.push_str(&format!("Hardware: {}\n", product));
+            }
+            if let Some(version) = &dmi.product_version {
+                output.push_str(&format!("  Version: {}\n", version));
+            }
+            let board: Vec<&str> = [&dmi.board_vendor, &dmi.board_name].into_iter().flatten().map(String::as_str).collect();
+            if !board.is_empty() {
+                output.push_str(&format!("  Board: {}\n", board.join(" ")));
+            }
+            let bios: Vec<&str> = [&dmi.bios_vendor, &dmi.bios_version, &dmi.bios_date]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            if !bios.is_empty() {
+                output.push_str(&format!("  Firmware: {}\n", bios.join(" ")));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get Linux pressure stall information (how much time tasks waited on CPU, memory \
+        and I/O) and a memory breakdown (available, cache, buffers, dirty, shmem, slab, swap, zswap, huge pages). \
+        Use to tell real memory pressure from memory that is only used as cache")]
+    pub async fn get_memory_pressure(&self) -> Result<CallToolResult, McpError> {
+        let proc_root = std::path::Path::new(pressure::PROC_ROOT);
+        let resources: Vec<_> = [("cpu", "CPU"), ("memory", "Memory"), ("io", "I/O")]
+            .into_iter()
+            .filter_map(|(resource, label)| Some((resource, label, pressure::read_pressure(proc_root, resource)?)))
+            .collect();
+        let meminfo = pressure::read_meminfo(proc_root);
+        if resources.is_empty() && meminfo.is_empty() {
+            return Err(McpError::internal_error(
+                "Neither /proc/pressure nor /proc/meminfo is readable (Linux only)".to_string(),
+                None,
+            ));
+        }
+        let mem = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+        let total = mem("MemTotal");
+        let available = mem("MemAvailable");
+        let available_percent = if total > 0 { available as f64 / total as f64 * 100.0 } else { 0.0 };
+        let cache = mem("Cached") + mem("Buffers") + mem("SReclaimable");
+        let memory_psi = resources.iter().find(|(r, _, _)| *r == "memory").map(|(_, _, p)| *p);
+
+        // Stalls are what users feel; low MemAvailable alone can be a cold cache.
+        let verdict = match memory_psi {
+            Some(p) if p.full.is_some_and(|f| f.avg10 >= 5.0) || p.some.avg10 >= 20.0 => {
+                "high memory pressure: tasks are stalling on memory"
+            }
+            Some(p) if p.some.avg10 >= 5.0 || p.some.avg60 >= 5.0 => "some memory pressure: occasional stalls on memory",
+            _ if total > 0 && available_percent < 10.0 => "low on available memory, but no stalls yet",
+            _ if total > 0 && cache > total - available.min(total) => "no memory pressure; most used memory is cache",
+            _ => "no memory pressure",
+        };
+
+        let mut output = String::from("Memory Pressure:\n\n");
+        output.push_str(&format!("Assessment: {}\n", verdict));
+
+        if resources.is_empty() {
+            output.push_str("\nPressure stall information: not available (needs Linux 4.20+ with CONFIG_PSI)\n");
+        } else {
+            output.push_str("\nPressure stall information (share of time stalled, avg10 / avg60 / avg300):\n");
+            let averages = |a: &pressure::Averages| format!("{:5.1}% / {:5.1}% / {:5.1}%", a.avg10, a.avg60, a.avg300);
+            for (resource, label, p) in &resources {
+                output.push_str(&format!("  {:<7} some {}\n", label, averages(&p.some)));
+                // System-wide CPU "full" is always zero and only confuses.
+                if let Some(full) = p.full.filter(|_| *resource != "cpu") {
+                    output.push_str(&format!("  {:<7} full {}\n", "", averages(&full)));
+                }
+            }
+        }
+
+        if !meminfo.is_empty() {
+            let line = |label: &str, bytes: u64, note: &str| {
+                if note.is_empty() {
+                    format!("  {:<20} {:>10}\n", label, format_bytes(bytes))
+                } else {
+                    format!("  {:<20} {:>10}  {}\n", label, format_bytes(bytes), note)
+                }
+            };
+            output.push_str("\nMemory breakdown:\n");
+            output.push_str(&line("Total", total, ""));
+            output.push_str(&line("Available", available, &format!("{:.0}% (free + reclaimable cache)", available_percent)));
+            output.push_str(&line("Free", mem("MemFree"), ""));
+            output.push_str(&line("Applications", mem("AnonPages"), "anonymous memory, not reclaimable without swap"));
+            output.push_str(&line("Page cache", mem("Cached"), "file cache, reclaimable"));
+            output.push_str(&line("Buffers", mem("Buffers"), ""));
+            output.push_str(&line("Shared (tmpfs/shm)", mem("Shmem"), "counted in page cache but not reclaimable"));
+            output.push_str(&line("Slab reclaimable", mem("SReclaimable"), ""));
+            output.push_str(&line("Slab unreclaimable", mem("SUnreclaim"), ""));
+            output.push_str(&line("Dirty", mem("Dirty"), "waiting to be written to disk"));
+            if mem("Writeback") > 0 {
+                output.push_str(&line("Writeback", mem("Writeback"), ""));
+            }
+            if mem("SwapTotal") > 0 {
+                output.push_str(&line(
+                    "Swap used",
+                    mem("SwapTotal").saturating_sub(mem("SwapFree")),
+                    &format!("of {}", format_bytes(mem("SwapTotal"))),
+                ));
+                output.push_str(&line("Swap cached", mem("SwapCached"), ""));
+            } else {
+                output.push_str("  Swap                 none\n");
+            }
+            if mem("Zswapped") > 0 {
+                output.push_str(&line(
+                    "Zswap",
+                    mem("Zswap"),
+                    &format!("compressed, holding {}", format_bytes(mem("Zswapped"))),
+                ));
+            }
+            if mem("HugePages_Total") > 0 {
+                output.push_str(&format!(
+                    "  {:<20} {:>10}  {} free, {} each\n",
+                    "Huge pages",
+                    mem("HugePages_Total"),
+                    mem("HugePages_Free"),
+                    format_bytes(mem("Hugepagesize"))
+                ));
+            }
+            if mem("AnonHugePages") > 0 {
+                output.push_str(&line("Transparent huge", mem("AnonHugePages"), ""));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get per-core CPU usage and frequency, physical vs logical cores, architecture, \
+        cache sizes and frequency governor (spot a single pegged core or a throttled clock)")]
+        pub async fn get_cpu_details(&self) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_with_specifics(
+            RefreshKind::nothing().with_cpu(CpuRefreshKind::everything()),
+        );
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_cpu_all();
+
+        let root = std::path::Path::new(cpu::SYSFS_CPU_ROOT);
+        let cpus = sys.cpus();
+        let logical = cpus.len();
+        let physical = System::physical_core_count();
+        let average = cpus.iter().map(|c| c.cpu_usage()).sum::<f32>() / logical.max(1) as f32;
+        let pegged: Vec<_> = cpus.iter().filter(|c| c.cpu_usage() >= 90.0).map(|c| c.name()).collect();
+        let scaling = cpu::read_scaling(root);
+        let capped = scaling.values().filter(|s| s.is_capped()).count();
+
+        let mut output = String::from("CPU Details:\n\n");
+        if let Some(first) = cpus.first() {
+            output.push_str(&format!("Model: {}\n", first.brand()));
+            if !first.vendor_id().is_empty() {
+                output.push_str(&format!("Vendor: {}\n", first.vendor_id()));
+            }
+        }
+        output.push_str(&format!("Architecture: {}\n", System::cpu_arch()));
+
+        let topology = cpu::read_topology(root);
+        let packages: std::collections::BTreeSet<_> = topology.iter().filter_map(|t| t.package).collect();
+        output.push_str(&format!(
+            "Cores: {} physical, {} logical",
+            physical.map(|p| p.to_string()).unwrap_or_else(|| "unknown".to_string()),
+            logical
+        ));
+        if !packages.is_empty() {
+            output.push_str(&format!(" ({} package(s))", packages.len()));
+        }
+        output.push('\n');
+        output.push_str(&format!("Average usage: {:.1}%\n", average));
+
+        output.push_str("\nPer-core:\n");
+        for c in cpus {
+            let id = cpu::cpu_id(c.name());
+            output.push_str(&format!("  {:<6} {:>5.1}%  {:>5} MHz", c.name(), c.cpu_usage(), c.frequency()));
+            if let Some(s) = id.and_then(|id| scaling.get(&id)) {
+                if let (Some(min), Some(max)) = (s.min_khz, s.max_khz) {
+                    output.push_str(&format!("  ({}-{} MHz)", min / 1000, max / 1000));
+                }
+                if s.is_capped() {
+                    output.push_str(" [capped]");
+                }
+            }
+            if c.cpu_usage() >= 90.0 {
+                output.push_str(" [pegged]");
+            }
+            if let Some(t) = topology.iter().find(|t| Some(t.cpu) == id) {
+                if let (Some(package), Some(core)) = (t.package, t.core) {
+                    output.push_str(&format!("  package {} core {}", package, core));
+                }
+            }
+            output.push('\n');
+        }
+
+        let caches = cpu::read_caches(root);
+        if !caches.is_empty() {
+            output.push_str("\nCaches:\n");
+            for cache in &caches {
+                output.push_str(&format!("  {}: {}", cache.name(), cache.size));
+                if cache.instances > 1 {
+                    output.push_str(&format!(" x {}", cache.instances));
+                }
+                output.push('\n');
+            }
+        }
+
+        let mut governors: Vec<_> = scaling.values().filter_map(|s| s.governor.clone()).collect();
+        governors.sort();
+        governors.dedup();
+        if let Some(s) = scaling.values().next() {
+            output.push_str("\nFrequency scaling:\n");
+            if let Some(driver) = &s.driver {
+                output.push_str(&format!("  Driver: {}\n", driver));
+            }
+            if !governors.is_empty() {
+                output.push_str(&format!("  Governor: {}\n", governors.join(", ")));
+            }
+            if let Some(hw) = s.hardware_max_khz {
+                output.push_str(&format!("  Hardware max: {} MHz\n", hw / 1000));
+            }
+            if let Some(available) = &s.available_governors {
+                output.push_str(&format!("  Available governors: {}\n", available));
+            }
+        } else {
+            output.push_str("\nFrequency scaling: not exposed on this system\n");
+        }
+
+        if !pegged.is_empty() || capped > 0 {
+            output.push_str("\nNotes:\n");
+            if !pegged.is_empty() {
+                output.push_str(&format!("  Pegged (>=90%): {}\n", pegged.join(", ")));
+            }
+            if capped > 0 {
+                output.push_str(&format!(
+                    "  {} core(s) have a policy max below the hardware max (power profile or thermal limit)\n",
+                    capped
+                ));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get disk activity: read/write bytes per second, IOPS and busy time per block device, \
+        plus the processes doing the most disk I/O. Use when the machine is sluggish but CPU is idle")]
+    pub async fn get_disk_io(
+        &self,
+        Parameters(params): Parameters<DiskIoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let interval = std::time::Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000));
+        let proc_root = std::path::Path::new(disk_io::PROC_ROOT);
+        let sysfs_block = std::path::Path::new(disk_io::SYSFS_BLOCK_ROOT);
+        let refresh = ProcessRefreshKind::nothing().with_disk_usage();
+
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        let before = disk_io::read_diskstats(proc_root, sysfs_block);
+        let started = std::time::Instant::now();
+        tokio::time::sleep(interval).await;
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        let after = disk_io::read_diskstats(proc_root, sysfs_block);
+        let seconds = started.elapsed().as_secs_f64();
+
+        let devices: Vec<_> = disk_io::rates(&before, &after, seconds)
+            .into_iter()
+            .filter(|d| d.is_disk && !d.is_idle())
+            .collect();
+
+        let mut processes: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none())
+            .map(|p| {
+                let io = p.disk_usage();
+                (p, io.read_bytes as f64 / seconds, io.written_bytes as f64 / seconds)
+            })
+            .filter(|(_, read, written)| *read > 0.0 || *written > 0.0)
+            .collect();
+        processes.sort_by(|a, b| (b.1 + b.2).partial_cmp(&(a.1 + a.2)).unwrap_or(std::cmp::Ordering::Equal));
+
+        let rate = |bytes: f64| format!("{}/s", format_bytes(bytes as u64));
+
+        let mut output = format!("Disk I/O (rates over {:.1}s):\n\n", seconds);
+
+        if devices.is_empty() {
+            output.push_str("No disk activity.\n");
+        } else {
+            output.push_str(&format!(
+                "{:<12} {:>12} {:>12} {:>8} {:>8} {:>6}\n",
+                "Device", "Read", "Write", "R IOPS", "W IOPS", "Busy"
+            ));
+            for d in &devices {
+                output.push_str(&format!(
+                    "{:<12} {:>12} {:>12} {:>8.0} {:>8.0} {:>5.0}%",
+                    d.name,
+                    rate(d.read_per_sec),
+                    rate(d.written_per_sec),
+                    d.read_iops,
+                    d.write_iops,
+                    d.utilization
+                ));
+                if d.utilization >= 90.0 {
+                    output.push_str(" [saturated]");
+                }
+                output.push('\n');
+            }
+        }
+
+        output.push_str("\nTop processes by disk I/O:\n");
+        if processes.is_empty() {
+            output.push_str("  (none; other users' processes need root)\n");
+        }
+        for (p, read, written) in processes.iter().take(params.top.unwrap_or(5)) {
+            output.push_str(&format!(
+                "  {:>7}  {:<20} read {}, write {}\n",
+                p.pid(),
+                p.name().to_string_lossy(),
+                rate(*read),
+                rate(*written)
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
+    #[rmcp::tool(description = "Get disk usage for mounted filesystems: used/total with reserved blocks, inode usage, \
+        mount flags (read-only, noexec), SSD vs HDD, removable and network mounts and the underlying block device. \
+        Pseudo filesystems (tmpfs, overlay, squashfs, ...) are skipped unless include_pseudo is set")]
+    pub async fn get_disk_info(
+        &self,
+        Parameters(params): Parameters<DiskInfoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let all_disks = Disks::new_with_refreshed_list();
+        let include_pseudo = params.include_pseudo.unwrap_or(false);
+        let disks: Vec<_> = all_disks
+            .iter()
+            .filter(|d| include_pseudo || !mounts::is_pseudo(&d.file_system().to_string_lossy()))
+            .collect();
+        let mount_table = mounts::read_mounts(std::path::Path::new(mounts::MOUNTINFO));
+        // statvfs separates root-reserved blocks from used ones; sysinfo only
+        // knows total and available.
+        let spaces: Vec<mounts::Space> = disks
+            .iter()
+            .map(|d| {
+                mounts::space(d.mount_point()).unwrap_or(mounts::Space {
+                    total_bytes: d.total_space(),
+                    free_bytes: d.available_space(),
+                    available_bytes: d.available_space(),
+                    inodes_total: 0,
+                    inodes_free: 0,
+                })
+            })
+            .collect();
+        let inode_percent = |s: &mounts::Space| {
+            let used = s.inodes_total.saturating_sub(s.inodes_free);
+            (s.inodes_total > 0).then(|| used as f64 / s.inodes_total as f64 * 100.0)
+        };
         let mut output = String::from("Disk Usage:\n\n");
+        if disks.is_empty() {
+            output.push_str("No filesystems found.\n");
+        }
 
-        for disk in disks.iter() {
-            let total = disk.total_space();
-            let free = disk.available_space();
-            let used = total - free;
-            let percent = if total > 0 { (used as f64 / total as f64 * 100.0) as u64 } else { 0 };
+        for (disk, space) in disks.iter().zip(&spaces) {
+            let fs_type = disk.file_system().to_string_lossy();
+            let mount = mounts::find(&mount_table, disk.mount_point());
+            let mut traits = vec![fs_type.to_string()];
+            match disk.kind() {
+                sysinfo::DiskKind::SSD => traits.push("SSD".to_string()),
+                sysinfo::DiskKind::HDD => traits.push("HDD".to_string()),
+                sysinfo::DiskKind::Unknown(_) => {}
+            }
+            if disk.is_removable() {
+                traits.push("removable".to_string());
+            }
+            if mounts::is_network(&fs_type) {
+                traits.push("network".to_string());
+            }
 
             output.push_str(&format!(
-                "{} ({})\n  {} / {} ({:.0}% used)\n  Mount: {}\n\n",
+                "{} ({})\n  {} / {} ({:.0}% used)",
                 disk.name().to_string_lossy(),
-                disk.file_system().to_string_lossy(),
-                format_bytes(used),
-                format_bytes(total),
-                percent,
-                disk.mount_point().display()
+                traits.join(", "),
+                format_bytes(space.used_bytes()),
+                format_bytes(space.total_bytes),
+                space.used_percent()
             ));
+            if space.reserved_bytes() > 0 {
+                output.push_str(&format!(", {} reserved for root", format_bytes(space.reserved_bytes())));
+            }
+            output.push('\n');
+            if let Some(percent) = inode_percent(space) {
+                output.push_str(&format!(
+                    "  Inodes: {} / {} ({:.0}% used)\n",
+                    space.inodes_total - space.inodes_free,
+                    space.inodes_total,
+                    percent
+                ));
+            }
+
+            output.push_str(&format!("  Mount: {}", disk.mount_point().display()));
+            let mut flags: Vec<&str> = mounts::NOTABLE_OPTIONS
+                .iter()
+                .copied()
+                .filter(|o| mount.is_some_and(|m| m.has_option(o)))
+                .collect();
+            if disk.is_read_only() && !flags.contains(&"ro") {
+                flags.insert(0, "ro");
+            }
+            if !flags.is_empty() {
+                output.push_str(&format!(" [{}]", flags.join(", ")));
+            }
+            output.push('\n');
+
+            if let Some(mount) = mount {
+                let device = mounts::block_device(std::path::Path::new(mounts::SYSFS_BLOCK_ROOT), &mount.source);
+                // Skip a device line that would only repeat the name above.
+                let device = device.filter(|d| {
+                    d.mapper_name.is_some()
+                        || d.disk.is_some()
+                        || !d.slaves.is_empty()
+                        || d.model.is_some()
+                        || !disk.name().to_string_lossy().ends_with(&d.name)
+                });
+                match device {
+                    Some(device) => {
+                        output.push_str(&format!("  Device: {}", device.name));
+                        if let Some(mapper) = &device.mapper_name {
+                            output.push_str(&format!(" ({})", mapper));
+                        }
+                        if let Some(parent) = &device.disk {
+                            output.push_str(&format!(" on {}", parent));
+                        }
+                        if !device.slaves.is_empty() {
+                            output.push_str(&format!(" on {}", device.slaves.join(" + ")));
+                        }
+                        if let Some(model) = &device.model {
+                            output.push_str(&format!(", {}", model));
+                        }
+                        output.push('\n');
+                    }
+                    None if mounts::is_network(&fs_type) => {
+                        output.push_str(&format!("  Source: {}\n", mount.source));
+                    }
+                    None => {}
+                }
+            }
+            output.push('\n');
+        }
+
+        let hidden = all_disks.iter().count() - disks.len();
+        if hidden > 0 {
+            output.push_str(&format!(
+                "{} pseudo filesystem(s) hidden (tmpfs, overlay, ...); set include_pseudo to show them.\n",
+                hidden
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Find what is filling a disk: walks a directory tree in parallel (one filesystem, \
+        within a time budget) and reports the largest directories and files, calling out cache and build \
+        directories (target/, node_modules, ~/.cache, container images) as reclaimable")]
+    pub async fn find_disk_hogs(
+        &self,
+        Parameters(params): Parameters<DiskHogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let root = PathBuf::from(&params.path);
+        if !root.is_dir() {
+            return Err(McpError::invalid_params(format!("'{}' is not a directory", root.display()), None));
+        }
+        let depth = params.depth.unwrap_or(2).clamp(1, 6);
+        let top_n = params.top_n.unwrap_or(10).clamp(1, 100);
+        let budget = std::time::Duration::from_secs(params.timeout_secs.unwrap_or(10).clamp(1, 120));
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get()).min(8);
+
+        let started = std::time::Instant::now();
+        let scan_root = root.clone();
+        let usage = tokio::task::spawn_blocking(move || disk_usage::scan(&scan_root, depth, top_n, budget, threads))
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?
+            .map_err(|e| McpError::internal_error(format!("Cannot scan {}: {}", root.display(), e), None))?;
+        let elapsed = started.elapsed().as_secs_f64();
+        let reclaimable_bytes: u64 = usage.reclaimable.iter().map(|r| r.bytes).sum();
+        let share = |bytes: u64| bytes as f64 / usage.total_bytes.max(1) as f64 * 100.0;
+        let mut output = format!(
+            "Disk usage under {}: {} in {} files, {} directories ({:.1}s)\n",
+            root.display(),
+            format_bytes(usage.total_bytes),
+            usage.files,
+            usage.dirs,
+            elapsed
+        );
+        if usage.incomplete {
+            output.push_str(&format!(
+                "Stopped after the {}s time budget; sizes are lower bounds.\n",
+                budget.as_secs()
+            ));
+        }
+
+        output.push_str(&format!("\nLargest directories (depth {}):\n", depth));
+        if usage.largest_dirs.is_empty() {
+            output.push_str("  (none)\n");
+        }
+        for dir in &usage.largest_dirs {
+            output.push_str(&format!(
+                "  {:>10}  {:>3.0}%  {}\n",
+                format_bytes(dir.bytes),
+                share(dir.bytes),
+                dir.path.display()
+            ));
+        }
+
+        output.push_str("\nLargest files:\n");
+        if usage.largest_files.is_empty() {
+            output.push_str("  (none)\n");
+        }
+        for file in &usage.largest_files {
+            output.push_str(&format!("  {:>10}  {}\n", format_bytes(file.bytes), file.path.display()));
+        }
+
+        if !usage.reclaimable.is_empty() {
+            output.push_str(&format!(
+                "\nReclaimable ({} total, {:.0}%):\n",
+                format_bytes(reclaimable_bytes),
+                share(reclaimable_bytes)
+            ));
+            for entry in usage.reclaimable.iter().take(top_n) {
+                output.push_str(&format!(
+                    "  {:>10}  {} - {}\n",
+                    format_bytes(entry.bytes),
+                    entry.path.display(),
+                    entry.what
+                ));
+            }
+            if usage.reclaimable.len() > top_n {
+                output.push_str(&format!("  ... and {} more\n", usage.reclaimable.len() - top_n));
+            }
+        }
+
+        if !usage.other_filesystems.is_empty() {
+            let mounts: Vec<_> = usage.other_filesystems.iter().map(|p| p.display().to_string()).collect();
+            if mounts.len() <= 5 {
+                output.push_str(&format!("\nOther filesystems (not counted): {}\n", mounts.join(", ")));
+            } else {
+                output.push_str(&format!(
+                    "\nOther filesystems (not counted): {}, ... and {} more\n",
+                    mounts[..5].join(", "),
+                    mounts.len() - 5
+                ));
+            }
+        }
+        if usage.unreadable > 0 {
+            output.push_str(&format!("Unreadable directories (not counted): {}\n", usage.unreadable));
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
@@ -200,7 +1065,7 @@ impl SysinfoServer {
 
         match sort_by.as_str() {
             "memory" | "mem" => {
-                processes.sort_by(|a, b| b.memory().cmp(&a.memory()));
+                processes.sort_by_key(|p| std::cmp::Reverse(p.memory()));
             }
             _ => {
                 processes.sort_by(|a, b| b.cpu_usage().partial_cmp(&a.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal));
@@ -315,6 +1180,14 @@ impl SysinfoServer {
             output.push_str(&format!("Working Dir: {}\n", cwd.display()));
         }
 
+        let proc_root = std::path::Path::new(proc_files::PROC_ROOT);
+        if let Ok(fds) = proc_files::read_fds(proc_root, params.pid) {
+            match proc_files::read_nofile_limit(proc_root, params.pid).and_then(|l| l.soft) {
+                Some(soft) => output.push_str(&format!("Open Files: {} / {} (limit)\n", fds.len(), soft)),
+                None => output.push_str(&format!("Open Files: {}\n", fds.len())),
+            }
+        }
+
         let cmd = proc.cmd();
         if !cmd.is_empty() {
             let cmd_str: Vec<_> = cmd.iter().map(|s| s.to_string_lossy()).collect();
@@ -329,6 +1202,227 @@ impl SysinfoServer {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
+    #[rmcp::tool(description = "List a process's open file descriptors (files, sockets, pipes, devices), \
+        its fd count against the open-file limit, held file locks and memory-mapped libraries. \
+        Use for 'too many open files' and 'who has this file open or locked'")]
+    pub async fn get_process_files(
+        &self,
+        Parameters(params): Parameters<ProcessFilesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let proc_root = std::path::Path::new(proc_files::PROC_ROOT);
+        let fds = proc_files::read_fds(proc_root, params.pid).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                McpError::internal_error(format!("Process {} not found", params.pid), None)
+            }
+            std::io::ErrorKind::PermissionDenied => McpError::internal_error(
+                format!("Cannot read open files of PID {}: permission denied (other users' processes need root)", params.pid),
+                None,
+            ),
+            _ => McpError::internal_error(format!("Failed to read open files of PID {}: {}", params.pid, e), None),
+        })?;
+        let name = std::fs::read_to_string(proc_root.join(params.pid.to_string()).join("comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        let limit = proc_files::read_nofile_limit(proc_root, params.pid).unwrap_or_default();
+        let libraries: Vec<_> = proc_files::read_mapped_files(proc_root, params.pid)
+            .into_iter()
+            .filter(|m| proc_files::is_library(&m.path))
+            .collect();
+        let locks = proc_files::read_locks(proc_root, params.pid);
+
+        let mut output = format!("Open Files (PID {}, {}):\n\n", params.pid, name);
+        match limit.soft.filter(|soft| *soft > 0) {
+            Some(soft) => {
+                let usage = fds.len() as f64 / soft as f64 * 100.0;
+                output.push_str(&format!("File Descriptors: {} / {} ({:.0}% of limit)\n", fds.len(), soft, usage));
+                if usage >= 80.0 {
+                    output.push_str("WARNING: close to the open-file limit; new opens will fail with EMFILE\n");
+                }
+            }
+            None => output.push_str(&format!("File Descriptors: {} (no limit)\n", fds.len())),
+        }
+        if let Some(hard) = limit.hard {
+            output.push_str(&format!("Hard Limit: {}\n", hard));
+        }
+
+        let filter = params.path.as_deref();
+        let matches = |target: &str| filter.is_none_or(|f| target.contains(f));
+
+        for kind in FdKind::ALL {
+            let rows: Vec<_> = fds.iter().filter(|f| f.kind == kind && matches(&f.target)).collect();
+            if rows.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("\n{} ({}):\n", kind.name(), rows.len()));
+            for f in rows.iter().take(50) {
+                output.push_str(&format!("  {:>5}  {}\n", f.fd, f.target));
+            }
+            if rows.len() > 50 {
+                output.push_str(&format!("  ... and {} more\n", rows.len() - 50));
+            }
+        }
+
+        if !locks.is_empty() {
+            output.push_str(&format!("\nHeld Locks ({}):\n", locks.len()));
+            for lock in &locks {
+                let file = fds
+                    .iter()
+                    .find(|f| f.inode == Some(lock.inode) && f.kind == FdKind::File)
+                    .map(|f| f.target.clone())
+                    .unwrap_or_else(|| format!("inode {}", lock.inode));
+                output.push_str(&format!("  {} {} {} (bytes {})\n", lock.class, lock.mode, file, lock.range));
+            }
+        }
+
+        let libraries: Vec<_> = libraries.iter().filter(|m| matches(&m.path)).collect();
+        if !libraries.is_empty() {
+            output.push_str(&format!("\nMapped Libraries ({}):\n", libraries.len()));
+            for m in libraries.iter().take(50) {
+                output.push_str(&format!("  {:>10}  {}\n", format_bytes(m.size), m.path));
+            }
+            if libraries.len() > 50 {
+                output.push_str(&format!("  ... and {} more\n", libraries.len() - 50));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Query processes by user, name/command-line regex, executable path, cwd prefix, \
+        status (e.g. zombie), minimum CPU/memory and age, with any sort key and chosen columns")]
+    pub async fn query_processes(
+        &self,
+        Parameters(params): Parameters<QueryProcessesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let regex = |pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(regex::Regex::new)
+                .transpose()
+                .map_err(|e| McpError::invalid_params(format!("Invalid regex: {}", e), None))
+        };
+        let statuses = match params.status.as_deref() {
+            Some(status) => Some(process_query::parse_status(status).ok_or_else(|| {
+                McpError::invalid_params(format!("Unknown status '{}'", status), None)
+            })?),
+            None => None,
+        };
+        let query = ProcessQuery {
+            user: params.user.clone(),
+            name: regex(&params.name)?,
+            cmdline: regex(&params.cmdline)?,
+            exe: params.exe.clone(),
+            cwd_prefix: params
+                .cwd
+                .as_deref()
+                .map(|cwd| std::fs::canonicalize(cwd).unwrap_or_else(|_| PathBuf::from(cwd))),
+            statuses,
+            min_cpu: params.min_cpu,
+            min_memory_bytes: params.min_memory_mb.map(|mb| mb * 1024 * 1024),
+            min_age_secs: params.min_age_seconds,
+            max_age_secs: params.max_age_seconds,
+        };
+
+        let sort_key = match params.sort_by.as_deref() {
+            Some(key) => SortKey::parse(key)
+                .ok_or_else(|| McpError::invalid_params(format!("Unknown sort key '{}'", key), None))?,
+            None => SortKey::Cpu,
+        };
+
+        let fields: Vec<&str> = match &params.fields {
+            Some(fields) => {
+                if let Some(unknown) = fields.iter().find(|f| !process_query::FIELDS.contains(&f.as_str())) {
+                    return Err(McpError::invalid_params(
+                        format!("Unknown field '{}'. Known fields: {}", unknown, process_query::FIELDS.join(", ")),
+                        None,
+                    ));
+                }
+                fields.iter().map(String::as_str).collect()
+            }
+            None => process_query::DEFAULT_FIELDS.to_vec(),
+        };
+        let limit = params.limit.unwrap_or(50);
+
+        let mut sys = System::new_all();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_all();
+        let users = Users::new_with_refreshed_list();
+
+        let mut matches: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none() && query.matches(p, &users))
+            .collect();
+        process_query::sort(&mut matches, sort_key, !params.ascending.unwrap_or(false), &users);
+
+        let mut output = format!("Matching processes: {}\n\n", matches.len());
+        if matches.is_empty() {
+            output.push_str("No processes match the query.\n");
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let shown = &matches[..matches.len().min(limit)];
+        output.push_str(&process_query::render_table(
+            shown,
+            &fields,
+            &users,
+            format_bytes,
+            format_duration,
+        ));
+        if matches.len() > shown.len() {
+            output.push_str(&format!("\n... and {} more (raise limit)\n", matches.len() - shown.len()));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Show the process tree rooted at a PID (or the whole system) with CPU and \
+        memory aggregated per subtree, e.g. total RAM of a browser or what a build spawned. Kernel threads \
+        are counted but not shown")]
+    pub async fn get_process_tree(
+        &self,
+        Parameters(params): Parameters<ProcessTreeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_all();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_all();
+
+        let tree = ProcessTree::build(&sys);
+
+        let roots: Vec<sysinfo::Pid> = match params.pid {
+            Some(pid) => {
+                let pid = sysinfo::Pid::from_u32(pid);
+                if tree.get(pid).is_none() {
+                    return Err(McpError::invalid_params(format!("Process {} not found", pid), None));
+                }
+                vec![pid]
+            }
+            None => tree.roots().to_vec(),
+        };
+
+        let mut output = match params.pid {
+            Some(pid) => format!("Process Tree (PID {}):\n\n", pid),
+            None => format!(
+                "Process Tree ({} processes, {} kernel threads not shown):\n\n",
+                tree.process_count(),
+                tree.kernel_threads()
+            ),
+        };
+
+        if params.pid.is_some() {
+            let ancestors = tree.ancestors(&sys, roots[0]);
+            if !ancestors.is_empty() {
+                let chain: Vec<_> = ancestors.iter().map(|n| format!("{}({})", n.name, n.pid)).collect();
+                output.push_str(&format!("Ancestors: {}\n\n", chain.join(" > ")));
+            }
+        }
+
+        output.push_str(&tree.render(&roots, params.max_depth, 200, format_bytes));
+        output.push('\n');
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     #[rmcp::tool(description = "List all running processes (sorted by CPU usage)")]
     pub async fn list_processes(&self) -> Result<CallToolResult, McpError> {
         let mut sys = System::new_all();
@@ -368,17 +1462,50 @@ impl SysinfoServer {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get network interface I/O statistics (bytes sent/received)")]
-    pub async fn get_network_stats(&self) -> Result<CallToolResult, McpError> {
-        let networks = Networks::new_with_refreshed_list();
-
-        let mut output = String::from("Network Interface Statistics:\n\n");
+    #[rmcp::tool(description = "Get network interface throughput (bytes/s, packets/s, errors/s over a short \
+        sampling window) with lifetime counters, plus an upper-bound estimate of which processes are doing network \
+        I/O")]
+    pub async fn get_network_stats(
+        &self,
+        Parameters(params): Parameters<NetworkStatsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let interval = std::time::Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000));
+        let proc_root = std::path::Path::new(net_io::PROC_ROOT);
+
+        // One window for interfaces and processes, timed from the interface
+        // baseline; each /proc walk takes about as long as the other.
+        let mut networks = Networks::new_with_refreshed_list();
+        let started = std::time::Instant::now();
+        let before = net_io::sample(proc_root);
+        let namespaces_before = net_io::sample_namespaces(proc_root);
+        tokio::time::sleep(interval).await;
+        networks.refresh(true);
+        let seconds = started.elapsed().as_secs_f64();
+        let after = net_io::sample(proc_root);
+        let namespaces_after = net_io::sample_namespaces(proc_root);
+        let rate = |count: u64| count as f64 / seconds;
+
+        let mut output = format!("Network Interface Statistics (rates over {:.1}s):\n\n", seconds);
 
         if networks.iter().count() == 0 {
             output.push_str("No network interfaces found.\n");
         } else {
-            for (name, data) in networks.iter() {
+            let mut interfaces: Vec<_> = networks.iter().collect();
+            interfaces.sort_by_key(|(name, _)| name.as_str());
+            for (name, data) in interfaces {
                 output.push_str(&format!("{}:\n", name));
+                output.push_str(&format!(
+                    "  Receive: {}/s, {:.0} packets/s, {:.0} errors/s\n",
+                    format_bytes(rate(data.received()) as u64),
+                    rate(data.packets_received()),
+                    rate(data.errors_on_received())
+                ));
+                output.push_str(&format!(
+                    "  Transmit: {}/s, {:.0} packets/s, {:.0} errors/s\n",
+                    format_bytes(rate(data.transmitted()) as u64),
+                    rate(data.packets_transmitted()),
+                    rate(data.errors_on_transmitted())
+                ));
                 output.push_str(&format!("  Received: {}\n", format_bytes(data.total_received())));
                 output.push_str(&format!("  Transmitted: {}\n", format_bytes(data.total_transmitted())));
                 output.push_str(&format!("  Packets In: {}\n", data.total_packets_received()));
@@ -389,6 +1516,60 @@ impl SysinfoServer {
             }
         }
 
+        let top = params.top.unwrap_or(5);
+        let mut namespaces: Vec<_> = namespaces_after
+            .iter()
+            .filter_map(|(id, now)| {
+                let then = namespaces_before.get(id)?;
+                Some((
+                    now,
+                    rate(now.received.saturating_sub(then.received)),
+                    rate(now.transmitted.saturating_sub(then.transmitted)),
+                ))
+            })
+            .collect();
+        namespaces.sort_by(|a, b| (b.1 + b.2).partial_cmp(&(a.1 + a.2)).unwrap_or(std::cmp::Ordering::Equal));
+        if !namespaces.is_empty() {
+            output.push_str("Isolated network namespaces (exact, e.g. containers):\n");
+            for (namespace, rx, tx) in namespaces.iter().take(top) {
+                let mut members: Vec<_> = namespace
+                    .processes
+                    .iter()
+                    .map(|(pid, name)| format!("{} ({})", name, pid))
+                    .collect();
+                if members.len() > 3 {
+                    let more = members.len() - 3;
+                    members.truncate(3);
+                    members.push(format!("+{} more", more));
+                }
+                output.push_str(&format!(
+                    "  in {}/s, out {}/s: {}\n",
+                    format_bytes(*rx as u64),
+                    format_bytes(*tx as u64),
+                    members.join(", ")
+                ));
+            }
+            output.push('\n');
+        }
+
+        let processes = net_io::rates(&before, &after, seconds);
+        output.push_str(
+            "Socket-holding processes by read/write I/O (upper bound: includes cached file I/O and pipes, \
+             misses send/recv):\n",
+        );
+        if processes.is_empty() {
+            output.push_str("  (no activity seen; other users' processes need root)\n");
+        }
+        for p in processes.iter().take(top) {
+            output.push_str(&format!(
+                "  {:>7}  {:<20} in {}/s, out {}/s\n",
+                p.pid,
+                p.name,
+                format_bytes(p.read_per_sec as u64),
+                format_bytes(p.written_per_sec as u64)
+            ));
+        }
+
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
@@ -418,7 +1599,207 @@ impl SysinfoServer {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get logged in users")]
+    #[rmcp::tool(description = "Read all hardware monitor sensors (fan RPMs, voltages, currents, power draw, \
+        temperatures) from /sys/class/hwmon, and CPU package power in watts from RAPL over a short window")]
+    pub async fn get_hwmon(
+        &self,
+        Parameters(params): Parameters<HwmonParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let chips = hwmon::read_chips(std::path::Path::new(hwmon::SYSFS_HWMON_ROOT));
+        let powercap = std::path::Path::new(hwmon::SYSFS_POWERCAP_ROOT);
+        let rapl_before = hwmon::read_rapl(powercap);
+        let rapl_readable = rapl_before.iter().any(|z| z.energy_uj.is_some());
+
+        let mut output = String::from("Hardware Monitors:\n\n");
+        if chips.is_empty() {
+            output.push_str("No hwmon sensors found.\n");
+        }
+        for chip in &chips {
+            output.push_str(&format!("{} ({}):\n", chip.name, chip.id));
+            for reading in &chip.readings {
+                output.push_str(&format!("  {}\n", format_reading(reading)));
+            }
+        }
+
+        if rapl_readable {
+            let interval = std::time::Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000));
+            let started = std::time::Instant::now();
+            tokio::time::sleep(interval).await;
+            let rapl_after = hwmon::read_rapl(powercap);
+            let watts = hwmon::rapl_watts(&rapl_before, &rapl_after, started.elapsed().as_secs_f64());
+            output.push_str(&format!("\nCPU Power (RAPL, over {:.1}s):\n", started.elapsed().as_secs_f64()));
+            for (zone, watts) in watts {
+                output.push_str(&format!("  {}: {:.2} W\n", zone, watts));
+            }
+        } else if !rapl_before.is_empty() {
+            output.push_str("\nCPU Power (RAPL): energy counters are not readable (root only on this kernel)\n");
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Diagnose thermals: whether the CPU is throttling right now (throttle counters, \
+        clock vs hardware max under load, thermal zone trip points) and which sensor is closest to critical")]
+    pub async fn diagnose_thermals(
+        &self,
+        Parameters(params): Parameters<ThermalParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let cpu_root = std::path::Path::new(cpu::SYSFS_CPU_ROOT);
+        let mut sys = System::new_with_specifics(
+            RefreshKind::nothing().with_cpu(CpuRefreshKind::everything()),
+        );
+        let throttle_before = thermal::read_throttle(cpu_root);
+        let interval = std::time::Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000))
+            .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        let started = std::time::Instant::now();
+        tokio::time::sleep(interval).await;
+        sys.refresh_cpu_all();
+        let window = started.elapsed().as_secs_f64();
+        let throttle_after = thermal::read_throttle(cpu_root);
+        let new_events = throttle_before
+            .zip(throttle_after)
+            .map(|(before, after)| after.events_since(&before));
+
+        let components = Components::new_with_refreshed_list();
+        let sensors: Vec<(String, f64, Option<f64>)> = components
+            .iter()
+            .filter_map(|c| Some((c.label().to_string(), c.temperature()? as f64, c.critical().map(f64::from))))
+            .collect();
+        let hot: Vec<_> = sensors
+            .iter()
+            .filter(|(_, temp, critical)| *temp >= thermal::hot_threshold(*critical))
+            .collect();
+        let closest = sensors
+            .iter()
+            .filter_map(|(label, temp, critical)| critical.map(|c| (label, *temp, c)))
+            .min_by(|a, b| (a.2 - a.1).total_cmp(&(b.2 - b.1)));
+
+        let zones = thermal::read_zones(std::path::Path::new(thermal::SYSFS_THERMAL_ROOT));
+        let tripped: Vec<_> = zones
+            .iter()
+            .filter_map(|z| z.crossed_trips().into_iter().max_by(|a, b| a.temp_c.total_cmp(&b.temp_c)).map(|t| (z, t)))
+            .collect();
+
+        let cpus = sys.cpus();
+        let load = cpus.iter().map(|c| c.cpu_usage() as f64).sum::<f64>() / cpus.len().max(1) as f64;
+        let scaling = cpu::read_scaling(cpu_root);
+        let capped = scaling.values().filter(|s| s.is_capped()).count();
+        let hardware_max_khz = scaling.values().filter_map(|s| s.hardware_max_khz).max();
+        let clock_mhz = cpus.iter().map(|c| c.frequency()).sum::<u64>() / cpus.len().max(1) as u64;
+        let clock_ratio = hardware_max_khz
+            .filter(|hw| *hw > 0 && clock_mhz > 0)
+            .map(|hw| clock_mhz as f64 * 1000.0 / hw as f64);
+        let slow_under_load = load >= thermal::BUSY_LOAD_PERCENT
+            && clock_ratio.is_some_and(|r| r < thermal::SLOW_CLOCK_RATIO);
+
+        let verdict = match (new_events.filter(|n| *n > 0), tripped.first()) {
+            (Some(events), _) => format!("throttling now ({} throttle event(s) in {:.1}s)", events, window),
+            (None, Some((zone, trip))) => format!(
+                "throttling now ({} is past its {} trip point at {:.1}°C)",
+                zone.kind, trip.kind, trip.temp_c
+            ),
+            (None, None) if slow_under_load => format!(
+                "probably throttling (clock at {:.0}% of hardware max under {:.0}% load)",
+                clock_ratio.unwrap_or_default() * 100.0,
+                load
+            ),
+            (None, None) if !hot.is_empty() => format!("hot but not throttling ({} sensor(s) hot)", hot.len()),
+            (None, None) => "OK, not throttling".to_string(),
+        };
+
+        let mut output = String::from("Thermal Diagnosis:\n\n");
+        output.push_str(&format!("Verdict: {}\n", verdict));
+        match closest {
+            Some((label, temp, critical)) => output.push_str(&format!(
+                "Closest to critical: {} at {:.1}°C (critical {:.1}°C, {:.1}°C headroom)\n",
+                label,
+                temp,
+                critical,
+                critical - temp
+            )),
+            None => output.push_str("Closest to critical: no sensor reports a critical temperature\n"),
+        }
+
+        output.push_str("\nTemperatures:\n");
+        if sensors.is_empty() {
+            output.push_str("  No temperature sensors found.\n");
+        }
+        for (label, temp, critical) in &sensors {
+            let is_hot = *temp >= thermal::hot_threshold(*critical);
+            output.push_str(&format!("  {}: {:.1}°C", label, temp));
+            if let Some(critical) = critical {
+                output.push_str(&format!(" (critical: {:.1}°C)", critical));
+            }
+            if is_hot {
+                output.push_str(" [hot]");
+            }
+            output.push('\n');
+        }
+
+        output.push_str("\nCPU Clock:\n");
+        output.push_str(&format!("  Load: {:.0}% average over {:.1}s\n", load, window));
+        match (hardware_max_khz, clock_ratio) {
+            (Some(hw), Some(ratio)) => output.push_str(&format!(
+                "  Frequency: {} MHz average of {} MHz hardware max ({:.0}%)\n",
+                clock_mhz,
+                hw / 1000,
+                ratio * 100.0
+            )),
+            _ => output.push_str(&format!("  Frequency: {} MHz average (hardware max not exposed)\n", clock_mhz)),
+        }
+        if capped > 0 {
+            output.push_str(&format!("  {} CPU(s) capped below hardware max by the scaling policy\n", capped));
+        }
+
+        output.push_str("\nThrottle Counters:\n");
+        match throttle_after {
+            Some(counters) => {
+                output.push_str(&format!(
+                    "  During sample: {} new event(s)\n",
+                    new_events.unwrap_or_default()
+                ));
+                output.push_str(&format!(
+                    "  Since boot: {} core event(s) ({} throttled), {} package event(s) ({} throttled)\n",
+                    counters.core_events,
+                    format_duration(counters.core_time_ms / 1000),
+                    counters.package_events,
+                    format_duration(counters.package_time_ms / 1000)
+                ));
+            }
+            None => output.push_str("  Not exposed on this system (Intel CPUs only)\n"),
+        }
+
+        output.push_str("\nThermal Zones:\n");
+        if zones.is_empty() {
+            output.push_str("  No thermal zones found.\n");
+        }
+        for zone in &zones {
+            output.push_str(&format!("  {} ({}): ", zone.kind, zone.id));
+            match zone.temp_c {
+                Some(temp) => output.push_str(&format!("{:.1}°C", temp)),
+                None => output.push_str("unreadable"),
+            }
+            let trips: Vec<_> = zone
+                .trips
+                .iter()
+                .map(|t| format!("{} at {:.1}°C", t.kind, t.temp_c))
+                .collect();
+            if !trips.is_empty() {
+                output.push_str(&format!(", trips: {}", trips.join(", ")));
+            }
+            if !zone.crossed_trips().is_empty() {
+                output.push_str(" [tripped]");
+            } else if let (Some(temp), Some(next)) = (zone.temp_c, zone.next_trip()) {
+                output.push_str(&format!(" ({:.1}°C below {})", next.temp_c - temp, next.kind));
+            }
+            output.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List the user accounts on this machine, system accounts included. \
+        For who is actually logged in, use get_sessions")]
     pub async fn get_users(&self) -> Result<CallToolResult, McpError> {
         let users = Users::new_with_refreshed_list();
 
@@ -454,7 +1835,7 @@ impl ServerHandler for SysinfoServer {
                 .enable_tools()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("System information server - CPU, memory, disk, processes".into()),
+            instructions: Some("System information server - CPU, memory, disk, processes, open files".into()),
         }
     }
 }
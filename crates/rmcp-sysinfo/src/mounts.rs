@@ -0,0 +1,202 @@
+//! Mount table details that sysinfo's `Disks` leaves out: mount options and
+//! source from `/proc/self/mountinfo`, block and inode counts from
+//! `statvfs(3)` (so reserved blocks are not counted as used) and the block
+//! device behind a mount from `/sys/class/block`. Linux-only apart from
+//! `statvfs`, which works on every Unix.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MOUNTINFO: &str = "/proc/self/mountinfo";
+pub const SYSFS_BLOCK_ROOT: &str = "/sys/class/block";
+
+/// Virtual and in-memory filesystems that say nothing about disk space.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts", "devtmpfs",
+    "efivarfs", "fuse.portal", "fusectl", "hugetlbfs", "mqueue", "nsfs", "overlay", "proc", "pstore",
+    "ramfs", "rpc_pipefs", "securityfs", "selinuxfs", "squashfs", "sysfs", "tmpfs", "tracefs",
+];
+
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "9p", "afs", "ceph", "cifs", "davfs", "fuse.rclone", "fuse.sshfs", "glusterfs", "ncpfs", "nfs", "nfs4",
+    "smb3", "smbfs", "sshfs",
+];
+
+/// Options worth flagging in a normal listing.
+pub const NOTABLE_OPTIONS: &[&str] = &["ro", "noexec", "nosuid", "nodev"];
+
+pub fn is_pseudo(fs_type: &str) -> bool {
+    PSEUDO_FILESYSTEMS.contains(&fs_type)
+}
+
+pub fn is_network(fs_type: &str) -> bool {
+    NETWORK_FILESYSTEMS.contains(&fs_type)
+}
+
+#[derive(Debug, Clone)]
+pub struct Mount {
+    pub mount_point: PathBuf,
+    /// What is mounted: a device path, `server:/export`, `//host/share`...
+    pub source: String,
+    /// Per-mount options followed by superblock options.
+    pub options: Vec<String>,
+}
+
+impl Mount {
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|o| o == option)
+    }
+}
+
+/// Mount points escape spaces, tabs, newlines and backslashes as octal.
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(index) = rest.find('\\') {
+        out.push_str(&rest[..index]);
+        let code = rest.get(index + 1..index + 4).and_then(|c| u8::from_str_radix(c, 8).ok());
+        match code {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn read_mounts(mountinfo: &Path) -> Vec<Mount> {
+    let Ok(contents) = fs::read_to_string(mountinfo) else { return Vec::new() };
+    contents
+        .lines()
+        .filter_map(|line| {
+            // id parent major:minor root mount_point options [optional...] - fs_type source super_options
+            let (before, after) = line.split_once(" - ")?;
+            let before: Vec<&str> = before.split(' ').collect();
+            let mut after = after.split(' ');
+            after.next()?; // fs_type, which sysinfo already reports
+            let source = unescape(after.next()?);
+            let mut options: Vec<String> = before.get(5)?.split(',').map(str::to_string).collect();
+            for option in after.next().unwrap_or("").split(',').filter(|o| !o.is_empty()) {
+                if !options.iter().any(|o| o == option) {
+                    options.push(option.to_string());
+                }
+            }
+            Some(Mount {
+                mount_point: PathBuf::from(unescape(before.get(4)?)),
+                source,
+                options,
+            })
+        })
+        .collect()
+}
+
+/// The mount that was mounted last at `mount_point`; earlier ones are
+/// hidden beneath it.
+pub fn find<'a>(mounts: &'a [Mount], mount_point: &Path) -> Option<&'a Mount> {
+    mounts.iter().rev().find(|m| m.mount_point == mount_point)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Space {
+    pub total_bytes: u64,
+    /// Free including the blocks reserved for root.
+    pub free_bytes: u64,
+    /// Free for unprivileged users.
+    pub available_bytes: u64,
+    /// Zero on filesystems without fixed inode tables (btrfs, ZFS, NFS).
+    pub inodes_total: u64,
+    pub inodes_free: u64,
+}
+
+impl Space {
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.free_bytes)
+    }
+
+    pub fn reserved_bytes(&self) -> u64 {
+        self.free_bytes.saturating_sub(self.available_bytes)
+    }
+
+    /// Share of the space usable by ordinary users, as `df` computes it.
+    pub fn used_percent(&self) -> f64 {
+        let usable = self.used_bytes() + self.available_bytes;
+        if usable == 0 {
+            0.0
+        } else {
+            self.used_bytes() as f64 / usable as f64 * 100.0
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn space(mount_point: &Path) -> Option<Space> {
+    let stat = rustix::fs::statvfs(mount_point).ok()?;
+    let block = if stat.f_frsize > 0 { stat.f_frsize } else { stat.f_bsize };
+    Some(Space {
+        total_bytes: stat.f_blocks * block,
+        free_bytes: stat.f_bfree * block,
+        available_bytes: stat.f_bavail * block,
+        inodes_total: stat.f_files,
+        inodes_free: stat.f_ffree,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn space(_mount_point: &Path) -> Option<Space> {
+    None
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockDevice {
+    /// Kernel name of the device, e.g. "nvme0n1p2" or "dm-0".
+    pub name: String,
+    /// Device-mapper name ("cryptroot", "vg-home") when it is one.
+    pub mapper_name: Option<String>,
+    /// The whole disk a partition belongs to, e.g. "nvme0n1".
+    pub disk: Option<String>,
+    /// Disks underneath a device-mapper or RAID device.
+    pub slaves: Vec<String>,
+    pub model: Option<String>,
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Resolves a mount source like `/dev/mapper/root` or
+/// `/dev/disk/by-uuid/...` to its kernel block device.
+pub fn block_device(sysfs_block: &Path, source: &str) -> Option<BlockDevice> {
+    if !source.starts_with("/dev/") {
+        return None;
+    }
+    let resolved = fs::canonicalize(source).unwrap_or_else(|_| PathBuf::from(source));
+    let name = resolved.file_name()?.to_string_lossy().into_owned();
+    let dir = sysfs_block.join(&name);
+    if !dir.exists() {
+        return None;
+    }
+    // A partition's sysfs directory sits inside the disk's.
+    let disk = dir
+        .join("partition")
+        .exists()
+        .then(|| fs::canonicalize(&dir).ok()?.parent()?.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .flatten();
+    let mut slaves: Vec<String> = fs::read_dir(dir.join("slaves"))
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    slaves.sort();
+    let model_dir = sysfs_block.join(disk.as_deref().unwrap_or(&name)).join("device");
+    Some(BlockDevice {
+        mapper_name: read_trimmed(dir.join("dm/name")),
+        model: read_trimmed(model_dir.join("model")),
+        name,
+        disk,
+        slaves,
+    })
+}
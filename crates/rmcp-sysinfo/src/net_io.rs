@@ -0,0 +1,204 @@
+//! Per-process network usage, as far as Linux exposes it.
+//!
+//! The kernel keeps exact byte counters per network namespace, so processes
+//! in their own namespace (containers, sandboxes) are reported exactly from
+//! `/proc/<pid>/net/dev`. Processes in the host namespace have no byte
+//! counters of their own; for those holding an inet socket we take the
+//! `read`/`write` I/O from `/proc/<pid>/io` that did not hit storage
+//! (`rchar - read_bytes`, `wchar - write_bytes`). That is an upper bound
+//! rather than a measurement: file reads served from the page cache and
+//! writes not yet flushed count too, as do pipes and terminals, while
+//! `send`/`recv` traffic is missed.
+//! Other users' processes need root.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+pub const PROC_ROOT: &str = "/proc";
+
+const INET_TABLES: [&str; 4] = ["tcp", "tcp6", "udp", "udp6"];
+
+/// I/O counters of one process that did not reach storage: network, but
+/// also page-cache hits, unflushed writes, pipes and terminals.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IoCounters {
+    pub read: u64,
+    pub written: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessRate {
+    pub pid: u32,
+    pub name: String,
+    pub read_per_sec: f64,
+    pub written_per_sec: f64,
+}
+
+/// Traffic counters of a non-host network namespace and the processes in it.
+#[derive(Debug, Clone, Default)]
+pub struct Namespace {
+    pub processes: Vec<(u32, String)>,
+    pub received: u64,
+    pub transmitted: u64,
+}
+
+fn inet_inodes(proc_root: &Path) -> HashSet<u64> {
+    INET_TABLES
+        .iter()
+        .filter_map(|table| fs::read_to_string(proc_root.join("net").join(table)).ok())
+        .flat_map(|contents| {
+            contents
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.split_whitespace().nth(9)?.parse().ok())
+                .collect::<Vec<u64>>()
+        })
+        .filter(|inode| *inode != 0)
+        .collect()
+}
+
+/// PIDs (with their command names) holding at least one TCP or UDP socket.
+pub fn socket_processes(proc_root: &Path) -> HashMap<u32, String> {
+    let inodes = inet_inodes(proc_root);
+    let mut processes = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return processes;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let holds_socket = fds.flatten().any(|fd| {
+            fs::read_link(fd.path())
+                .ok()
+                .and_then(|target| {
+                    let target = target.to_str()?.strip_prefix("socket:[")?.strip_suffix(']')?;
+                    target.parse::<u64>().ok()
+                })
+                .is_some_and(|inode| inodes.contains(&inode))
+        });
+        if holds_socket {
+            let name = fs::read_to_string(entry.path().join("comm"))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default();
+            processes.insert(pid, name);
+        }
+    }
+    processes
+}
+
+pub fn read_io(proc_root: &Path, pid: u32) -> Option<IoCounters> {
+    let contents = fs::read_to_string(proc_root.join(pid.to_string()).join("io")).ok()?;
+    let mut values: HashMap<&str, u64> = HashMap::new();
+    for line in contents.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            if let Ok(value) = value.trim().parse() {
+                values.insert(key, value);
+            }
+        }
+    }
+    let get = |key: &str| values.get(key).copied().unwrap_or(0);
+    Some(IoCounters {
+        read: get("rchar").saturating_sub(get("read_bytes")),
+        written: get("wchar").saturating_sub(get("write_bytes")),
+    })
+}
+
+/// Reads the counters of every socket-holding process. Pair two of these
+/// with [`rates`].
+pub fn sample(proc_root: &Path) -> HashMap<u32, (String, IoCounters)> {
+    socket_processes(proc_root)
+        .into_iter()
+        .filter_map(|(pid, name)| Some((pid, (name, read_io(proc_root, pid)?))))
+        .collect()
+}
+
+/// Per-second rates between two samples taken `seconds` apart, busiest
+/// first. Processes missing from either sample are skipped.
+pub fn rates(
+    before: &HashMap<u32, (String, IoCounters)>,
+    after: &HashMap<u32, (String, IoCounters)>,
+    seconds: f64,
+) -> Vec<ProcessRate> {
+    let mut rates: Vec<_> = after
+        .iter()
+        .filter_map(|(pid, (name, now))| {
+            let (_, then) = before.get(pid)?;
+            Some(ProcessRate {
+                pid: *pid,
+                name: name.clone(),
+                read_per_sec: now.read.saturating_sub(then.read) as f64 / seconds,
+                written_per_sec: now.written.saturating_sub(then.written) as f64 / seconds,
+            })
+        })
+        .filter(|r| r.read_per_sec > 0.0 || r.written_per_sec > 0.0)
+        .collect();
+    rates.sort_by(|a, b| {
+        (b.read_per_sec + b.written_per_sec)
+            .partial_cmp(&(a.read_per_sec + a.written_per_sec))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    rates
+}
+
+/// Byte totals over every interface but loopback in `/proc/<pid>/net/dev`.
+fn read_net_dev(proc_root: &Path, pid: u32) -> Option<(u64, u64)> {
+    let contents = fs::read_to_string(proc_root.join(pid.to_string()).join("net/dev")).ok()?;
+    let mut totals = (0, 0);
+    for line in contents.lines().skip(2) {
+        let Some((name, counters)) = line.split_once(':') else { continue };
+        if name.trim() == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = counters.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+        if fields.len() >= 9 {
+            totals.0 += fields[0];
+            totals.1 += fields[8];
+        }
+    }
+    Some(totals)
+}
+
+/// Network namespaces other than our own, keyed by namespace id
+/// (`net:[4026532...]`).
+pub fn sample_namespaces(proc_root: &Path) -> HashMap<String, Namespace> {
+    let mut namespaces: HashMap<String, Namespace> = HashMap::new();
+    let Ok(own) = fs::read_link(proc_root.join("self/ns/net")) else {
+        return namespaces;
+    };
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return namespaces;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(ns) = fs::read_link(entry.path().join("ns/net")) else {
+            continue;
+        };
+        if ns == own {
+            continue;
+        }
+        let name = fs::read_to_string(entry.path().join("comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        let namespace = namespaces.entry(ns.to_string_lossy().to_string()).or_default();
+        if namespace.processes.is_empty() {
+            if let Some((received, transmitted)) = read_net_dev(proc_root, pid) {
+                namespace.received = received;
+                namespace.transmitted = transmitted;
+            }
+        }
+        namespace.processes.push((pid, name));
+    }
+    for namespace in namespaces.values_mut() {
+        namespace.processes.sort();
+    }
+    namespaces
+}
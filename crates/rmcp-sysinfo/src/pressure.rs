@@ -0,0 +1,80 @@
+//! Pressure stall information from `/proc/pressure/{cpu,memory,io}` and the
+//! memory breakdown from `/proc/meminfo`. Linux-only: PSI needs 4.20+ built
+//! with `CONFIG_PSI` (and not booted with `psi=0`).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub const PROC_ROOT: &str = "/proc";
+
+/// Share of wall time (percent) that tasks were stalled, averaged over the
+/// last 10, 60 and 300 seconds, plus the total stall time in microseconds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Averages {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pressure {
+    /// At least one task was stalled.
+    pub some: Averages,
+    /// All non-idle tasks were stalled at once. The kernel reports zeros for
+    /// CPU at the system level.
+    pub full: Option<Averages>,
+}
+
+fn parse_averages(line: &str) -> Averages {
+    let mut averages = Averages::default();
+    for field in line.split_whitespace().skip(1) {
+        let Some((key, value)) = field.split_once('=') else { continue };
+        match key {
+            "avg10" => averages.avg10 = value.parse().unwrap_or_default(),
+            "avg60" => averages.avg60 = value.parse().unwrap_or_default(),
+            "avg300" => averages.avg300 = value.parse().unwrap_or_default(),
+            "total" => averages.total_us = value.parse().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    averages
+}
+
+/// Reads `/proc/pressure/<resource>` for "cpu", "memory" or "io".
+pub fn read_pressure(proc_root: &Path, resource: &str) -> Option<Pressure> {
+    let contents = fs::read_to_string(proc_root.join("pressure").join(resource)).ok()?;
+    let mut pressure = Pressure::default();
+    let mut seen = false;
+    for line in contents.lines() {
+        if line.starts_with("some ") {
+            pressure.some = parse_averages(line);
+            seen = true;
+        } else if line.starts_with("full ") {
+            pressure.full = Some(parse_averages(line));
+        }
+    }
+    seen.then_some(pressure)
+}
+
+/// `/proc/meminfo` with sizes converted to bytes. Page counts such as
+/// `HugePages_Total` are kept as plain numbers.
+pub fn read_meminfo(proc_root: &Path) -> HashMap<String, u64> {
+    let Ok(contents) = fs::read_to_string(proc_root.join("meminfo")) else {
+        return HashMap::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
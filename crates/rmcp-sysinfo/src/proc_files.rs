@@ -0,0 +1,196 @@
+//! Open file descriptors, the open-file limit, memory-mapped files and held
+//! locks of a single process, read from `/proc/<pid>/{fd,limits,maps}` and
+//! `/proc/locks`. Linux-only; other users' processes need root.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const PROC_ROOT: &str = "/proc";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FdKind {
+    File,
+    Socket,
+    Pipe,
+    Device,
+    AnonInode,
+    Other,
+}
+
+impl FdKind {
+    pub const ALL: [FdKind; 6] = [
+        FdKind::File,
+        FdKind::Socket,
+        FdKind::Pipe,
+        FdKind::Device,
+        FdKind::AnonInode,
+        FdKind::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FdKind::File => "Regular files",
+            FdKind::Socket => "Sockets",
+            FdKind::Pipe => "Pipes",
+            FdKind::Device => "Devices",
+            FdKind::AnonInode => "Anonymous inodes",
+            FdKind::Other => "Other",
+        }
+    }
+
+    /// Classifies a `/proc/<pid>/fd/N` link target.
+    fn of(target: &str) -> Self {
+        if target.starts_with("socket:[") {
+            FdKind::Socket
+        } else if target.starts_with("pipe:[") {
+            FdKind::Pipe
+        } else if target.starts_with("anon_inode:") {
+            FdKind::AnonInode
+        } else if target.starts_with("/dev/") {
+            FdKind::Device
+        } else if target.starts_with('/') {
+            FdKind::File
+        } else {
+            FdKind::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub target: String,
+    pub kind: FdKind,
+    /// Inode of the open file, used to match entries in `/proc/locks`.
+    pub inode: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileLimit {
+    /// `None` means unlimited.
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MappedFile {
+    pub path: String,
+    /// Sum of all mappings of this file, in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Lock {
+    /// POSIX, FLOCK or OFDLCK.
+    pub class: String,
+    /// READ or WRITE.
+    pub mode: String,
+    pub inode: u64,
+    pub range: String,
+}
+
+/// File descriptors in fd order. Fails when the process is gone or not ours
+/// to inspect.
+pub fn read_fds(proc_root: &Path, pid: u32) -> io::Result<Vec<OpenFile>> {
+    let dir = proc_root.join(pid.to_string()).join("fd");
+    let mut files: Vec<_> = fs::read_dir(&dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let fd = entry.file_name().to_str()?.parse().ok()?;
+            let target = fs::read_link(entry.path()).ok()?.to_string_lossy().to_string();
+            Some(OpenFile {
+                fd,
+                kind: FdKind::of(&target),
+                inode: inode_of(&entry.path()),
+                target,
+            })
+        })
+        .collect();
+    files.sort_by_key(|f| f.fd);
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn inode_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| m.ino())
+}
+
+#[cfg(not(unix))]
+fn inode_of(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// The `Max open files` row of `/proc/<pid>/limits` (`RLIMIT_NOFILE`).
+pub fn read_nofile_limit(proc_root: &Path, pid: u32) -> Option<FileLimit> {
+    let contents = fs::read_to_string(proc_root.join(pid.to_string()).join("limits")).ok()?;
+    let line = contents.lines().find(|l| l.starts_with("Max open files"))?;
+    let mut values = line["Max open files".len()..].split_whitespace();
+    let parse = |v: Option<&str>| v.and_then(|v| v.parse().ok());
+    Some(FileLimit {
+        soft: parse(values.next()),
+        hard: parse(values.next()),
+    })
+}
+
+/// File-backed mappings from `/proc/<pid>/maps`, one entry per file, in path
+/// order. Pseudo mappings such as `[heap]` and `[stack]` are skipped.
+pub fn read_mapped_files(proc_root: &Path, pid: u32) -> Vec<MappedFile> {
+    let Ok(contents) = fs::read_to_string(proc_root.join(pid.to_string()).join("maps")) else {
+        return Vec::new();
+    };
+
+    let mut sizes: BTreeMap<String, u64> = BTreeMap::new();
+    for line in contents.lines() {
+        let mut fields = line.splitn(6, char::is_whitespace);
+        let Some(range) = fields.next() else { continue };
+        let Some(path) = fields.nth(4).map(str::trim) else { continue };
+        if !path.starts_with('/') {
+            continue;
+        }
+        let size = range
+            .split_once('-')
+            .and_then(|(start, end)| {
+                Some(u64::from_str_radix(end, 16).ok()? - u64::from_str_radix(start, 16).ok()?)
+            })
+            .unwrap_or(0);
+        *sizes.entry(path.to_string()).or_default() += size;
+    }
+
+    sizes.into_iter().map(|(path, size)| MappedFile { path, size }).collect()
+}
+
+/// Whether a mapped file looks like a shared library rather than a data file.
+pub fn is_library(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.ends_with(".so") || name.contains(".so.")
+}
+
+/// Locks held by `pid` according to `/proc/locks`. Blocked waiters (`->`
+/// lines) are not included.
+pub fn read_locks(proc_root: &Path, pid: u32) -> Vec<Lock> {
+    let Ok(contents) = fs::read_to_string(proc_root.join("locks")) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            if fields.len() < 8 || fields[1] == "->" {
+                return None;
+            }
+            if fields[4].parse::<u32>().ok()? != pid {
+                return None;
+            }
+            let inode = fields[5].rsplit(':').next()?.parse().ok()?;
+            Some(Lock {
+                class: fields[1].to_string(),
+                mode: fields[3].to_string(),
+                inode,
+                range: format!("{}-{}", fields[6], fields[7]),
+            })
+        })
+        .collect()
+}
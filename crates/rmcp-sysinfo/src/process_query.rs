@@ -0,0 +1,228 @@
+//! Filtering, sorting and projection over sysinfo's process table for the
+//! `query_processes` tool.
+
+use regex::Regex;
+use std::path::PathBuf;
+use sysinfo::{Process, ProcessStatus, Users};
+
+/// Columns a query can project, in the order they are accepted.
+pub const FIELDS: &[&str] = &["pid", "ppid", "user", "status", "cpu", "memory", "age", "name", "exe", "cwd", "cmd"];
+
+pub const DEFAULT_FIELDS: &[&str] = &["pid", "cpu", "memory", "name"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Pid,
+    Cpu,
+    Memory,
+    Age,
+    Name,
+    User,
+}
+
+impl SortKey {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "pid" => Some(SortKey::Pid),
+            "cpu" => Some(SortKey::Cpu),
+            "memory" | "mem" => Some(SortKey::Memory),
+            "age" | "start" => Some(SortKey::Age),
+            "name" => Some(SortKey::Name),
+            "user" => Some(SortKey::User),
+            _ => None,
+        }
+    }
+}
+
+/// Maps the status names users ask for onto sysinfo's statuses.
+pub fn parse_status(s: &str) -> Option<Vec<ProcessStatus>> {
+    let statuses = match s.to_lowercase().replace('_', "-").as_str() {
+        "running" | "run" => vec![ProcessStatus::Run],
+        "sleeping" | "sleep" => vec![ProcessStatus::Sleep, ProcessStatus::Idle],
+        "zombie" => vec![ProcessStatus::Zombie],
+        "disk-sleep" | "uninterruptible" | "d" => vec![ProcessStatus::UninterruptibleDiskSleep],
+        "stopped" | "stop" => vec![ProcessStatus::Stop, ProcessStatus::Tracing],
+        "dead" => vec![ProcessStatus::Dead],
+        _ => return None,
+    };
+    Some(statuses)
+}
+
+#[derive(Debug, Default)]
+pub struct ProcessQuery {
+    pub user: Option<String>,
+    pub name: Option<Regex>,
+    pub cmdline: Option<Regex>,
+    pub exe: Option<String>,
+    pub cwd_prefix: Option<PathBuf>,
+    pub statuses: Option<Vec<ProcessStatus>>,
+    pub min_cpu: Option<f32>,
+    pub min_memory_bytes: Option<u64>,
+    pub min_age_secs: Option<u64>,
+    pub max_age_secs: Option<u64>,
+}
+
+impl ProcessQuery {
+    pub fn matches(&self, p: &Process, users: &Users) -> bool {
+        if let Some(user) = &self.user {
+            let name = p.user_id().and_then(|uid| users.get_user_by_id(uid)).map(|u| u.name());
+            if name != Some(user.as_str()) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.name {
+            if !re.is_match(&p.name().to_string_lossy()) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.cmdline {
+            if !re.is_match(&command_line(p)) {
+                return false;
+            }
+        }
+        if let Some(exe) = &self.exe {
+            if !p.exe().is_some_and(|path| path.to_string_lossy().contains(exe.as_str())) {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.cwd_prefix {
+            if !p.cwd().is_some_and(|cwd| cwd.starts_with(prefix)) {
+                return false;
+            }
+        }
+        if let Some(statuses) = &self.statuses {
+            if !statuses.contains(&p.status()) {
+                return false;
+            }
+        }
+        if self.min_cpu.is_some_and(|min| p.cpu_usage() < min) {
+            return false;
+        }
+        if self.min_memory_bytes.is_some_and(|min| p.memory() < min) {
+            return false;
+        }
+        if self.min_age_secs.is_some_and(|min| p.run_time() < min) {
+            return false;
+        }
+        if self.max_age_secs.is_some_and(|max| p.run_time() > max) {
+            return false;
+        }
+        true
+    }
+}
+
+pub fn command_line(p: &Process) -> String {
+    p.cmd().iter().map(|s| s.to_string_lossy()).collect::<Vec<_>>().join(" ")
+}
+
+fn user_name(p: &Process, users: &Users) -> String {
+    p.user_id()
+        .and_then(|uid| users.get_user_by_id(uid))
+        .map(|u| u.name().to_string())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+/// Sorts in place; `descending` is the natural order for cpu, memory and
+/// age (biggest first).
+pub fn sort(processes: &mut [&Process], key: SortKey, descending: bool, users: &Users) {
+    processes.sort_by(|a, b| {
+        let ordering = match key {
+            SortKey::Pid => a.pid().cmp(&b.pid()),
+            SortKey::Cpu => a.cpu_usage().partial_cmp(&b.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal),
+            SortKey::Memory => a.memory().cmp(&b.memory()),
+            SortKey::Age => a.run_time().cmp(&b.run_time()),
+            SortKey::Name => a.name().cmp(b.name()),
+            SortKey::User => user_name(a, users).cmp(&user_name(b, users)),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn field_value(
+    p: &Process,
+    field: &str,
+    users: &Users,
+    format_bytes: fn(u64) -> String,
+    format_duration: fn(u64) -> String,
+) -> String {
+    match field {
+        "pid" => p.pid().to_string(),
+        "ppid" => p.parent().map(|pid| pid.to_string()).unwrap_or_else(|| "-".to_string()),
+        "user" => user_name(p, users),
+        "status" => p.status().to_string(),
+        "cpu" => format!("{:.1}", p.cpu_usage()),
+        "memory" => format_bytes(p.memory()),
+        "age" => format_duration(p.run_time()),
+        "name" => p.name().to_string_lossy().to_string(),
+        "exe" => p.exe().map(|e| e.display().to_string()).unwrap_or_else(|| "-".to_string()),
+        "cwd" => p.cwd().map(|c| c.display().to_string()).unwrap_or_else(|| "-".to_string()),
+        "cmd" => command_line(p),
+        _ => String::new(),
+    }
+}
+
+fn field_width(field: &str) -> usize {
+    match field {
+        "pid" | "ppid" => 8,
+        "cpu" => 7,
+        "memory" | "status" | "age" => 10,
+        "user" => 12,
+        "name" => 20,
+        _ => 0,
+    }
+}
+
+fn field_header(field: &str) -> &str {
+    match field {
+        "pid" => "PID",
+        "ppid" => "PPID",
+        "user" => "User",
+        "status" => "Status",
+        "cpu" => "CPU%",
+        "memory" => "Memory",
+        "age" => "Age",
+        "name" => "Name",
+        "exe" => "Exe",
+        "cwd" => "Cwd",
+        "cmd" => "Command",
+        _ => field,
+    }
+}
+
+/// Renders the projected columns as a table. Every column but the last is
+/// padded to a fixed width.
+pub fn render_table(
+    processes: &[&Process],
+    fields: &[&str],
+    users: &Users,
+    format_bytes: fn(u64) -> String,
+    format_duration: fn(u64) -> String,
+) -> String {
+    let row = |values: Vec<String>| {
+        let last = values.len().saturating_sub(1);
+        let mut line = String::new();
+        for (i, (field, value)) in fields.iter().zip(values).enumerate() {
+            if i == last {
+                line.push_str(&value);
+            } else {
+                line.push_str(&format!("{:<width$} ", value, width = field_width(field)));
+            }
+        }
+        line.push('\n');
+        line
+    };
+
+    let mut output = row(fields.iter().map(|f| field_header(f).to_string()).collect());
+    output.push_str(&format!("{:-<60}\n", ""));
+    for p in processes {
+        output.push_str(&row(fields
+            .iter()
+            .map(|f| field_value(p, f, users, format_bytes, format_duration))
+            .collect()));
+    }
+    output
+}
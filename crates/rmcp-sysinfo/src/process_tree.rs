@@ -0,0 +1,278 @@
+//! Parent/child view over sysinfo's process table with CPU and memory
+//! aggregated per subtree.
+
+use std::collections::HashMap;
+use sysinfo::{Pid, System, ThreadKind};
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub pid: Pid,
+    pub name: String,
+    pub cpu: f32,
+    pub memory: u64,
+    pub children: Vec<Pid>,
+    /// Totals for this process and all of its descendants.
+    pub subtree_count: usize,
+    pub subtree_cpu: f32,
+    pub subtree_memory: u64,
+}
+
+#[derive(Debug)]
+pub struct ProcessTree {
+    nodes: HashMap<Pid, Node>,
+    roots: Vec<Pid>,
+    kernel_threads: usize,
+}
+
+impl ProcessTree {
+    /// Builds the tree from a refreshed `System`. Userland threads are
+    /// skipped so their shared memory is not counted once per thread.
+    /// Kernel threads (kthreadd and its children) are left out too: they
+    /// use no memory of their own and would bury user processes under
+    /// hundreds of kworkers. [`Self::kernel_threads`] says how many.
+    pub fn build(sys: &System) -> Self {
+        let kernel_threads = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind() == Some(ThreadKind::Kernel))
+            .count();
+        let mut nodes: HashMap<Pid, Node> = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none())
+            .map(|p| {
+                (
+                    p.pid(),
+                    Node {
+                        pid: p.pid(),
+                        name: p.name().to_string_lossy().to_string(),
+                        cpu: p.cpu_usage(),
+                        memory: p.memory(),
+                        children: Vec::new(),
+                        subtree_count: 0,
+                        subtree_cpu: 0.0,
+                        subtree_memory: 0,
+                    },
+                )
+            })
+            .collect();
+
+        let mut roots = Vec::new();
+        for p in sys.processes().values().filter(|p| p.thread_kind().is_none()) {
+            match p.parent().filter(|parent| *parent != p.pid() && nodes.contains_key(parent)) {
+                Some(parent) => nodes.get_mut(&parent).unwrap().children.push(p.pid()),
+                None => roots.push(p.pid()),
+            }
+        }
+        roots.sort();
+
+        let mut tree = Self {
+            nodes,
+            roots,
+            kernel_threads,
+        };
+        tree.aggregate();
+        for pid in tree.nodes.keys().copied().collect::<Vec<_>>() {
+            let mut children = std::mem::take(&mut tree.nodes.get_mut(&pid).unwrap().children);
+            children.sort_by_key(|c| std::cmp::Reverse(tree.nodes[c].subtree_memory));
+            tree.nodes.get_mut(&pid).unwrap().children = children;
+        }
+        tree
+    }
+
+    /// Fills in the subtree totals, children before parents. Walks with an
+    /// explicit stack: a fork chain can be deeper than recursion allows.
+    fn aggregate(&mut self) {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = self.roots.clone();
+        while let Some(pid) = stack.pop() {
+            order.push(pid);
+            stack.extend(self.nodes[&pid].children.iter().copied());
+        }
+        for pid in order.into_iter().rev() {
+            let node = &self.nodes[&pid];
+            let (mut count, mut cpu, mut memory) = (1, node.cpu, node.memory);
+            for child in &node.children {
+                let child = &self.nodes[child];
+                count += child.subtree_count;
+                cpu += child.subtree_cpu;
+                memory += child.subtree_memory;
+            }
+            let node = self.nodes.get_mut(&pid).unwrap();
+            node.subtree_count = count;
+            node.subtree_cpu = cpu;
+            node.subtree_memory = memory;
+        }
+    }
+
+    pub fn get(&self, pid: Pid) -> Option<&Node> {
+        self.nodes.get(&pid)
+    }
+
+    pub fn roots(&self) -> &[Pid] {
+        &self.roots
+    }
+
+    /// Parent chain of `pid`, outermost first, excluding `pid` itself.
+    pub fn ancestors(&self, sys: &System, pid: Pid) -> Vec<&Node> {
+        let mut chain = Vec::new();
+        let mut current = sys.process(pid).and_then(|p| p.parent());
+        while let Some(parent) = current {
+            let Some(node) = self.nodes.get(&parent) else { break };
+            if chain.iter().any(|n: &&Node| n.pid == parent) {
+                break;
+            }
+            chain.push(node);
+            current = sys.process(parent).and_then(|p| p.parent());
+        }
+        chain.reverse();
+        chain
+    }
+
+    pub fn process_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Kernel threads left out of the tree.
+    pub fn kernel_threads(&self) -> usize {
+        self.kernel_threads
+    }
+
+    /// Renders the subtrees under `roots` with box-drawing connectors.
+    /// Children below `max_depth` are folded into their parent's totals and
+    /// output stops after `max_lines` lines.
+    pub fn render(
+        &self,
+        roots: &[Pid],
+        max_depth: Option<usize>,
+        max_lines: usize,
+        format_bytes: fn(u64) -> String,
+    ) -> String {
+        let mut lines = Vec::new();
+        let mut hidden = 0;
+        // (pid, prefix, prefix for its children, depth), first child on top.
+        let mut stack: Vec<(Pid, String, String, usize)> =
+            roots.iter().rev().map(|root| (*root, String::new(), String::new(), 0)).collect();
+        while let Some((pid, prefix, child_prefix, depth)) = stack.pop() {
+            let node = &self.nodes[&pid];
+            if lines.len() >= max_lines {
+                // Past the limit only the count matters.
+                hidden += 1;
+                if max_depth.is_none_or(|max| depth < max) {
+                    stack.extend(node.children.iter().map(|c| (*c, String::new(), String::new(), depth + 1)));
+                }
+                continue;
+            }
+
+            let mut line = format!(
+                "{}{} {}  {:.1}% {}",
+                prefix,
+                node.pid,
+                node.name,
+                node.cpu,
+                format_bytes(node.memory)
+            );
+            if node.subtree_count > 1 {
+                line.push_str(&format!(
+                    "  [subtree: {} procs, {:.1}% CPU, {}]",
+                    node.subtree_count,
+                    node.subtree_cpu,
+                    format_bytes(node.subtree_memory)
+                ));
+            }
+            lines.push(line);
+
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for (i, child) in node.children.iter().enumerate().rev() {
+                let last = i + 1 == node.children.len();
+                let (branch, next) = if last { ("└─ ", "   ") } else { ("├─ ", "│  ") };
+                stack.push((
+                    *child,
+                    format!("{}{}", child_prefix, branch),
+                    format!("{}{}", child_prefix, next),
+                    depth + 1,
+                ));
+            }
+        }
+        if hidden > 0 {
+            lines.push(format!("... {} more processes not shown", hidden));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(pid: usize, memory: u64, children: Vec<Pid>) -> Node {
+        Node {
+            pid: Pid::from(pid),
+            name: format!("p{}", pid),
+            cpu: 1.0,
+            memory,
+            children,
+            subtree_count: 0,
+            subtree_cpu: 0.0,
+            subtree_memory: 0,
+        }
+    }
+
+    fn tree(nodes: Vec<Node>, roots: Vec<Pid>) -> ProcessTree {
+        let mut tree = ProcessTree {
+            nodes: nodes.into_iter().map(|n| (n.pid, n)).collect(),
+            roots,
+            kernel_threads: 0,
+        };
+        tree.aggregate();
+        tree
+    }
+
+    fn bytes(n: u64) -> String {
+        format!("{}B", n)
+    }
+
+    #[test]
+    fn subtree_totals() {
+        let tree = tree(
+            vec![
+                node(1, 10, vec![Pid::from(2), Pid::from(3)]),
+                node(2, 20, vec![Pid::from(4)]),
+                node(3, 30, vec![]),
+                node(4, 40, vec![]),
+            ],
+            vec![Pid::from(1)],
+        );
+        let root = tree.get(Pid::from(1)).unwrap();
+        assert_eq!((root.subtree_count, root.subtree_memory), (4, 100));
+        assert_eq!(root.subtree_cpu, 4.0);
+        assert_eq!(tree.get(Pid::from(2)).unwrap().subtree_memory, 60);
+
+        let rendered = tree.render(&[Pid::from(1)], None, 100, bytes);
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("1 p1"));
+        assert!(lines[1].starts_with("├─ 2 p2"));
+        assert!(lines[2].starts_with("│  └─ 4 p4"));
+        assert!(lines[3].starts_with("└─ 3 p3"));
+
+        let folded = tree.render(&[Pid::from(1)], Some(0), 100, bytes);
+        assert_eq!(folded.lines().count(), 1);
+    }
+
+    #[test]
+    fn deep_fork_chain_does_not_overflow() {
+        const DEPTH: usize = 200_000;
+        let nodes = (1..=DEPTH)
+            .map(|pid| node(pid, 1, if pid < DEPTH { vec![Pid::from(pid + 1)] } else { vec![] }))
+            .collect();
+        let tree = tree(nodes, vec![Pid::from(1)]);
+        assert_eq!(tree.get(Pid::from(1)).unwrap().subtree_count, DEPTH);
+
+        let rendered = tree.render(&[Pid::from(1)], None, 3, bytes);
+        assert_eq!(rendered.lines().count(), 4);
+        assert!(rendered.ends_with(&format!("... {} more processes not shown", DEPTH - 3)));
+    }
+}
@@ -0,0 +1,151 @@
+//! Thermal zones with their trip points from `/sys/class/thermal` and the
+//! per-CPU thermal throttle counters in
+//! `/sys/devices/system/cpu/cpuN/thermal_throttle` (Intel only). The counters
+//! are cumulative since boot, so callers compare two readings to tell
+//! whether throttling is happening right now. Linux-only.
+
+use std::fs;
+use std::path::Path;
+
+pub const SYSFS_THERMAL_ROOT: &str = "/sys/class/thermal";
+
+/// A sensor counts as hot this close to its critical temperature...
+pub const HOT_MARGIN_C: f64 = 10.0;
+/// ...or above this when it reports no critical temperature.
+pub const HOT_FALLBACK_C: f64 = 85.0;
+
+/// A clock below this share of the hardware maximum while the CPUs are at
+/// least `BUSY_LOAD_PERCENT` busy points at firmware or thermal throttling.
+pub const SLOW_CLOCK_RATIO: f64 = 0.6;
+pub const BUSY_LOAD_PERCENT: f64 = 50.0;
+
+/// Temperature at which a sensor with the given critical temperature is hot.
+pub fn hot_threshold(critical_c: Option<f64>) -> f64 {
+    match critical_c {
+        Some(critical) if critical > HOT_MARGIN_C => critical - HOT_MARGIN_C,
+        _ => HOT_FALLBACK_C,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trip {
+    /// "active" (fan), "passive" (throttling starts), "hot" or "critical"
+    /// (shutdown).
+    pub kind: String,
+    pub temp_c: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThermalZone {
+    /// `thermal_zoneN`.
+    pub id: String,
+    /// What the zone measures, e.g. "x86_pkg_temp", "acpitz", "cpu-thermal".
+    pub kind: String,
+    pub temp_c: Option<f64>,
+    pub trips: Vec<Trip>,
+}
+
+impl ThermalZone {
+    /// The lowest trip point that has not been crossed yet.
+    pub fn next_trip(&self) -> Option<&Trip> {
+        let temp = self.temp_c?;
+        self.trips
+            .iter()
+            .filter(|t| t.temp_c > temp)
+            .min_by(|a, b| a.temp_c.total_cmp(&b.temp_c))
+    }
+
+    /// Passive or hotter trip points that the zone is at or above.
+    pub fn crossed_trips(&self) -> Vec<&Trip> {
+        let Some(temp) = self.temp_c else { return Vec::new() };
+        self.trips.iter().filter(|t| t.kind != "active" && t.temp_c <= temp).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThrottleCounters {
+    /// Core throttle events and time, summed over all CPUs.
+    pub core_events: u64,
+    pub core_time_ms: u64,
+    /// Package counters are repeated on every CPU of the package, so these
+    /// are the largest values seen rather than a sum.
+    pub package_events: u64,
+    pub package_time_ms: u64,
+}
+
+impl ThrottleCounters {
+    /// Core and package events recorded after `earlier`.
+    pub fn events_since(&self, earlier: &ThrottleCounters) -> u64 {
+        self.core_events.saturating_sub(earlier.core_events)
+            + self.package_events.saturating_sub(earlier.package_events)
+    }
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number(path: impl AsRef<Path>) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// Sysfs reports millidegrees Celsius.
+fn read_celsius(path: impl AsRef<Path>) -> Option<f64> {
+    read_trimmed(path)?.parse::<i64>().ok().map(|m| m as f64 / 1000.0)
+}
+
+pub fn read_zones(root: &Path) -> Vec<ThermalZone> {
+    let Ok(entries) = fs::read_dir(root) else { return Vec::new() };
+    let mut zones: Vec<ThermalZone> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().into_owned();
+            id.strip_prefix("thermal_zone")?.parse::<u32>().ok()?;
+            let dir = entry.path();
+            let mut trips = Vec::new();
+            for index in 0.. {
+                let Some(temp_c) = read_celsius(dir.join(format!("trip_point_{}_temp", index))) else { break };
+                let kind = read_trimmed(dir.join(format!("trip_point_{}_type", index))).unwrap_or_default();
+                // Disabled trip points read as 0 or a huge sentinel.
+                if temp_c > 0.0 && temp_c < 200.0 {
+                    trips.push(Trip { kind, temp_c });
+                }
+            }
+            Some(ThermalZone {
+                kind: read_trimmed(dir.join("type")).unwrap_or_else(|| id.clone()),
+                temp_c: read_celsius(dir.join("temp")),
+                trips,
+                id,
+            })
+        })
+        .collect();
+    zones.sort_by_key(|z| z.id.trim_start_matches("thermal_zone").parse::<u32>().unwrap_or(u32::MAX));
+    zones
+}
+
+/// Summed throttle counters, or `None` when the CPUs have none (AMD, ARM,
+/// most VMs).
+pub fn read_throttle(cpu_root: &Path) -> Option<ThrottleCounters> {
+    let entries = fs::read_dir(cpu_root).ok()?;
+    let mut counters = ThrottleCounters::default();
+    let mut found = false;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(index) = name.to_str().and_then(|n| n.strip_prefix("cpu")) else { continue };
+        if index.parse::<u32>().is_err() {
+            continue;
+        }
+        let dir = entry.path().join("thermal_throttle");
+        let Some(core_events) = read_number(dir.join("core_throttle_count")) else { continue };
+        found = true;
+        counters.core_events += core_events;
+        counters.core_time_ms += read_number(dir.join("core_throttle_total_time_ms")).unwrap_or(0);
+        counters.package_events = counters
+            .package_events
+            .max(read_number(dir.join("package_throttle_count")).unwrap_or(0));
+        counters.package_time_ms = counters
+            .package_time_ms
+            .max(read_number(dir.join("package_throttle_total_time_ms")).unwrap_or(0));
+    }
+    found.then_some(counters)
+}
@@ -0,0 +1,320 @@
+pub mod sessions;
+pub mod systemd;
+
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, ServerHandler, wrapper::Parameters},
+    model::*,
+    ErrorData as McpError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use systemd::Bus;
+
+#[derive(Debug)]
+pub struct SystemdServer {
+    pub tool_router: ToolRouter<Self>,
+}
+
+impl Default for SystemdServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct BusParams {
+    #[schemars(description = "Ask the user's service manager (systemctl --user) instead of the system one (default false)")]
+    #[serde(default)]
+    pub user: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ServiceStatusParams {
+    #[schemars(description = "Unit name, e.g. 'postgresql' or 'nginx.service' ('.service' is added when no type is given)")]
+    pub name: String,
+    #[schemars(description = "Ask the user's service manager (systemctl --user) instead of the system one (default false)")]
+    #[serde(default)]
+    pub user: Option<bool>,
+}
+
+fn bus(user: Option<bool>) -> Bus {
+    if user.unwrap_or(false) {
+        Bus::User
+    } else {
+        Bus::System
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else if seconds < 86400 {
+        format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
+    } else {
+        format!("{}d {}h", seconds / 86400, (seconds % 86400) / 3600)
+    }
+}
+
+/// "5m ago" or "in 2h 10m" for a timestamp in microseconds since the epoch.
+fn relative_time(usec: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or_default();
+    if usec > now {
+        format!("in {}", format_duration((usec - now) / 1_000_000))
+    } else {
+        format!("{} ago", format_duration((now - usec) / 1_000_000))
+    }
+}
+
+/// Runs a blocking D-Bus query off the async runtime.
+async fn query<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, McpError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?
+        .map_err(|e| McpError::internal_error(e, None))
+}
+
+#[rmcp::tool_router]
+impl SystemdServer {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[rmcp::tool(description = "List systemd units in the failed state, like 'systemctl --failed'")]
+    pub async fn list_failed_units(
+        &self,
+        Parameters(params): Parameters<BusParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let bus = bus(params.user);
+        let units = query(move || systemd::list_units(bus)).await?;
+        let failed: Vec<_> = units.iter().filter(|u| u.active_state == "failed").collect();
+
+        let mut output = format!("Failed Units ({} manager):\n\n", bus.name());
+        if failed.is_empty() {
+            output.push_str(&format!("No failed units ({} loaded).\n", units.len()));
+        }
+        for unit in &failed {
+            output.push_str(&format!("{} - {} ({})", unit.name, unit.description, unit.sub_state));
+            if unit.load_state != "loaded" {
+                output.push_str(&format!(" [{}]", unit.load_state));
+            }
+            output.push('\n');
+        }
+        if !failed.is_empty() {
+            output.push_str(&format!("\nTotal: {} failed of {} loaded\n", failed.len(), units.len()));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get the status of a systemd service: active state and sub-state since when, main PID, \
+        memory, tasks, restart count and last result, like 'systemctl status'")]
+    pub async fn get_service_status(
+        &self,
+        Parameters(params): Parameters<ServiceStatusParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let bus = bus(params.user);
+        let name = systemd::unit_name(params.name.trim());
+        let status = query(move || systemd::service_status(bus, &name)).await?;
+
+        if status.load_state == "not-found" {
+            return Err(McpError::invalid_params(
+                format!("Unit {} not found on the {} manager", status.name, bus.name()),
+                None,
+            ));
+        }
+
+        let mut output = format!("{} - {}\n", status.name, status.description);
+        output.push_str(&format!("  Loaded: {}", status.load_state));
+        if let Some(path) = &status.fragment_path {
+            output.push_str(&format!(" ({}", path));
+            if let Some(state) = &status.unit_file_state {
+                output.push_str(&format!("; {}", state));
+            }
+            output.push(')');
+        }
+        output.push('\n');
+        output.push_str(&format!("  Active: {} ({})", status.active_state, status.sub_state));
+        if let Some(since) = status.since_usec {
+            output.push_str(&format!(" since {}", relative_time(since)));
+        }
+        output.push('\n');
+        if let Some(pid) = status.main_pid {
+            output.push_str(&format!("  Main PID: {}\n", pid));
+        }
+        if let Some(tasks) = status.tasks {
+            output.push_str(&format!("  Tasks: {}\n", tasks));
+        }
+        if let Some(memory) = status.memory {
+            output.push_str(&format!("  Memory: {}\n", format_bytes(memory)));
+        }
+        if let Some(cpu) = status.cpu_nsec {
+            output.push_str(&format!("  CPU: {:.1}s\n", cpu as f64 / 1e9));
+        }
+        if let Some(restarts) = status.restarts {
+            output.push_str(&format!("  Restarts: {}\n", restarts));
+        }
+        if let Some(result) = &status.result {
+            output.push_str(&format!("  Last Result: {}", result));
+            if let Some(code) = status.exit_status.filter(|code| *code != 0) {
+                output.push_str(&format!(" (exit status {})", code));
+            }
+            output.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List active systemd timers with when they fire next, when they last fired and the unit \
+        they start, like 'systemctl list-timers'")]
+    pub async fn list_timers(
+        &self,
+        Parameters(params): Parameters<BusParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let bus = bus(params.user);
+        let timers = query(move || systemd::list_timers(bus)).await?;
+
+        let mut output = format!("Timers ({} manager):\n\n", bus.name());
+        if timers.is_empty() {
+            output.push_str("No active timers.\n");
+        }
+        for timer in &timers {
+            output.push_str(&format!("{} -> {}\n", timer.name, timer.unit));
+            match timer.next_usec {
+                Some(next) => output.push_str(&format!("  Next: {}\n", relative_time(next))),
+                None => output.push_str("  Next: not scheduled\n"),
+            }
+            if let Some(last) = timer.last_usec {
+                output.push_str(&format!("  Last: {}\n", relative_time(last)));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get the actual login sessions (logind, or utmp without it): user, TTY, seat, \
+        session type (x11/wayland/tty), remote host for SSH and start time, plus whether this server runs \
+        over SSH or inside tmux/screen, i.e. whether the user is remote")]
+    pub async fn get_sessions(&self) -> Result<CallToolResult, McpError> {
+        let (source, sessions) = query(sessions::list_sessions).await?;
+        let context = sessions::server_context();
+
+        let mut output = format!("Login Sessions ({}):\n\n", source.name());
+        if sessions.is_empty() {
+            output.push_str("No login sessions.\n\n");
+        }
+        for session in &sessions {
+            output.push_str(&session.user);
+            if let Some(id) = &session.id {
+                output.push_str(&format!(" (session {})", id));
+            }
+            if session.current {
+                output.push_str(" [this server]");
+            }
+            output.push('\n');
+            let mut kind = session.kind.clone();
+            if let Some(class) = session.class.as_deref().filter(|c| *c != "user") {
+                kind.push_str(&format!(" ({})", class));
+            }
+            if session.remote {
+                match &session.remote_host {
+                    Some(host) => kind.push_str(&format!(", remote from {}", host)),
+                    None => kind.push_str(", remote"),
+                }
+            }
+            output.push_str(&format!("  Type: {}\n", kind));
+            if let Some(tty) = &session.tty {
+                output.push_str(&format!("  TTY: {}\n", tty));
+            }
+            if let Some(seat) = &session.seat {
+                output.push_str(&format!("  Seat: {}\n", seat));
+            }
+            if let Some(display) = &session.display {
+                output.push_str(&format!("  Display: {}\n", display));
+            }
+            if let Some(state) = &session.state {
+                output.push_str(&format!("  State: {}\n", state));
+            }
+            if let Some(started) = session.started_usec {
+                output.push_str(&format!("  Started: {}\n", relative_time(started)));
+            }
+            if let Some(idle) = session.idle_since_usec {
+                output.push_str(&format!("  Idle since: {}\n", relative_time(idle)));
+            }
+            if let Some(uid) = session.uid {
+                output.push_str(&format!("  UID: {}\n", uid));
+            }
+            if let Some(service) = &session.service {
+                output.push_str(&format!("  Service: {}\n", service));
+            }
+            if let Some(leader) = session.leader {
+                output.push_str(&format!("  Leader PID: {}\n", leader));
+            }
+            output.push('\n');
+        }
+
+        let mut server = Vec::new();
+        if context.ssh {
+            server.push(match &context.ssh_client {
+                Some(client) => format!("over SSH from {}", client),
+                None => "over SSH".to_string(),
+            });
+        }
+        if context.mosh {
+            server.push("over mosh".to_string());
+        }
+        if let Some(multiplexer) = context.multiplexer {
+            server.push(format!("inside {}", multiplexer));
+        }
+        output.push_str(&format!(
+            "Total: {} session(s), {} remote\n",
+            sessions.len(),
+            sessions.iter().filter(|s| s.remote).count()
+        ));
+        output.push_str(&format!(
+            "This server: {}\n",
+            if server.is_empty() { "local, not inside SSH, tmux or screen".to_string() } else { server.join(", ") }
+        ));
+        if let Some(id) = &context.session_id {
+            output.push_str(&format!("XDG_SESSION_ID: {}\n", id));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+}
+
+#[rmcp::tool_handler]
+impl ServerHandler for SystemdServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some("systemd server - failed units, service status, timers, login sessions".into()),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! rmcp-systemd: MCP server for systemd services and timers
+//!
+//! Run with: `rmcp-systemd` (serves on stdio)
+
+use rmcp::ServiceExt;
+use rmcp_systemd::SystemdServer;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Initialize tracing (to stderr so it doesn't interfere with stdio transport)
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    tracing::info!("Starting rmcp-systemd server");
+
+    // Create server and serve on stdio
+    let server = SystemdServer::new();
+    let service = server.serve(rmcp::transport::stdio()).await?;
+
+    // Wait for shutdown
+    service.waiting().await?;
+
+    tracing::info!("rmcp-systemd server stopped");
+    Ok(())
+}
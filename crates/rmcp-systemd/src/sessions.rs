@@ -0,0 +1,393 @@
+//! Login sessions from systemd-logind over D-Bus, falling back to the utmp
+//! file where logind is not running (containers, non-systemd distros), and
+//! how the server process itself was reached: over SSH, inside tmux or
+//! screen. utmp only knows user, terminal, remote host and start time; seat,
+//! session type and state come from logind alone. Linux-only; elsewhere
+//! listing fails with an explanation.
+
+use std::path::Path;
+
+pub const UTMP: &str = "/var/run/utmp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Logind,
+    Utmp,
+}
+
+impl Source {
+    pub fn name(self) -> &'static str {
+        match self {
+            Source::Logind => "logind",
+            Source::Utmp => "utmp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    /// logind session id; utmp has none.
+    pub id: Option<String>,
+    pub user: String,
+    pub uid: Option<u32>,
+    pub tty: Option<String>,
+    pub seat: Option<String>,
+    /// X11 display, e.g. ":0".
+    pub display: Option<String>,
+    pub remote: bool,
+    pub remote_host: Option<String>,
+    /// "x11", "wayland", "tty", "mir" or "unspecified" (SSH and other
+    /// sessions without a display or VT).
+    pub kind: String,
+    /// "user", "greeter", "lock-screen", "background" or "manager".
+    pub class: Option<String>,
+    /// PAM service that opened the session, e.g. "sshd" or "gdm-password".
+    pub service: Option<String>,
+    /// "online", "active" (in the foreground of its seat) or "closing".
+    pub state: Option<String>,
+    pub started_usec: Option<u64>,
+    pub idle_since_usec: Option<u64>,
+    /// The process that opened the session.
+    pub leader: Option<u32>,
+    /// The server runs inside this session.
+    pub current: bool,
+}
+
+/// How the server process was reached, from its environment and its
+/// ancestor processes (the environment may have been scrubbed by the MCP
+/// client).
+#[derive(Debug, Clone, Default)]
+pub struct ServerContext {
+    pub ssh: bool,
+    /// "address port" of the SSH client when known.
+    pub ssh_client: Option<String>,
+    pub mosh: bool,
+    /// "tmux", "screen" or "zellij".
+    pub multiplexer: Option<&'static str>,
+    /// `XDG_SESSION_ID` as inherited from the login.
+    pub session_id: Option<String>,
+}
+
+/// Sessions from logind, or from utmp when logind is unreachable.
+pub fn list_sessions() -> Result<(Source, Vec<Session>), String> {
+    let mut sessions = match imp::logind_sessions() {
+        Ok(sessions) => (Source::Logind, sessions),
+        Err(logind) => match read_utmp(Path::new(UTMP)) {
+            Ok(sessions) => (Source::Utmp, sessions),
+            Err(utmp) => return Err(format!("{}; {}", logind, utmp)),
+        },
+    };
+    sessions.1.sort_by(|a, b| a.started_usec.cmp(&b.started_usec).then_with(|| a.user.cmp(&b.user)));
+    Ok(sessions)
+}
+
+pub fn server_context() -> ServerContext {
+    let env = |key: &str| std::env::var(key).ok().filter(|v| !v.is_empty());
+    let ancestors = imp::ancestors();
+    let has_ancestor = |names: &[&str]| ancestors.iter().any(|(_, a)| names.iter().any(|n| a.starts_with(n)));
+
+    let ssh_client = env("SSH_CONNECTION")
+        .or_else(|| env("SSH_CLIENT"))
+        .map(|c| c.split_whitespace().take(2).collect::<Vec<_>>().join(" "));
+    let multiplexer = if env("TMUX").is_some() || has_ancestor(&["tmux"]) {
+        Some("tmux")
+    } else if env("STY").is_some() || has_ancestor(&["screen", "SCREEN"]) {
+        Some("screen")
+    } else if env("ZELLIJ").is_some() || has_ancestor(&["zellij"]) {
+        Some("zellij")
+    } else {
+        None
+    };
+    ServerContext {
+        ssh: ssh_client.is_some() || has_ancestor(&["sshd"]),
+        ssh_client,
+        mosh: has_ancestor(&["mosh-server"]),
+        multiplexer,
+        session_id: env("XDG_SESSION_ID"),
+    }
+}
+
+/// Where a glibc `struct utmp` record lays out the fields read here. Type,
+/// pid, line, user and host sit at 0, 4, 8, 44 and 76 on every target; the
+/// record size and `ut_tv` depend on whether glibc sets
+/// `__WORDSIZE_TIME64_COMPAT32`.
+#[derive(Debug, Clone, Copy)]
+struct UtmpLayout {
+    record: usize,
+    /// Offset of `ut_tv.tv_sec`.
+    tv_sec: usize,
+    /// `tv_sec` is 64 bits wide rather than 32.
+    wide_time: bool,
+}
+
+impl UtmpLayout {
+    /// 32-bit targets, and 64-bit ones that keep their 32-bit layout for
+    /// compatibility: a 32-bit `ut_session` and `ut_tv`.
+    #[cfg(any(
+        target_pointer_width = "32",
+        target_arch = "x86_64",
+        target_arch = "powerpc64",
+        target_arch = "sparc64",
+        target_arch = "mips64"
+    ))]
+    const HOST: UtmpLayout = UtmpLayout { record: 384, tv_sec: 340, wide_time: false };
+
+    /// Other 64-bit targets (aarch64, riscv64, ...): `ut_session` is a
+    /// `long` and `ut_tv` a native `struct timeval`.
+    #[cfg(not(any(
+        target_pointer_width = "32",
+        target_arch = "x86_64",
+        target_arch = "powerpc64",
+        target_arch = "sparc64",
+        target_arch = "mips64"
+    )))]
+    const HOST: UtmpLayout = UtmpLayout { record: 400, tv_sec: 344, wide_time: true };
+}
+
+/// Reads the glibc `struct utmp` records of live login processes.
+pub fn read_utmp(path: &Path) -> Result<Vec<Session>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let ancestors = imp::ancestors();
+    let mut sessions = parse_utmp(&bytes, UtmpLayout::HOST);
+    // Entries of crashed logins are never cleared.
+    sessions.retain(|s| s.leader.is_none_or(|pid| Path::new("/proc").join(pid.to_string()).exists()));
+    for session in &mut sessions {
+        session.current = ancestors.iter().any(|(ancestor, _)| Some(*ancestor) == session.leader);
+    }
+    Ok(sessions)
+}
+
+/// Sessions of the `USER_PROCESS` records in a utmp file's contents.
+fn parse_utmp(bytes: &[u8], layout: UtmpLayout) -> Vec<Session> {
+    const USER_PROCESS: i16 = 7;
+    let text = |field: &[u8]| {
+        let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+        Some(String::from_utf8_lossy(&field[..end]).into_owned()).filter(|s| !s.is_empty())
+    };
+    let mut sessions = Vec::new();
+    for record in bytes.chunks_exact(layout.record) {
+        if i16::from_ne_bytes([record[0], record[1]]) != USER_PROCESS {
+            continue;
+        }
+        let pid = u32::from_ne_bytes([record[4], record[5], record[6], record[7]]);
+        let Some(user) = text(&record[44..76]) else { continue };
+        let tty = text(&record[8..40]);
+        let host = text(&record[76..332]);
+        let time = &record[layout.tv_sec..];
+        let seconds = if layout.wide_time {
+            i64::from_ne_bytes([time[0], time[1], time[2], time[3], time[4], time[5], time[6], time[7]])
+        } else {
+            i32::from_ne_bytes([time[0], time[1], time[2], time[3]]).into()
+        };
+        // Graphical logins record the X display as the host.
+        let display = host.clone().filter(|h| h.starts_with(':'));
+        let remote_host = host.filter(|h| !h.starts_with(':'));
+        let kind = if display.is_some() {
+            "x11"
+        } else if tty.as_deref().is_some_and(|t| t.starts_with("tty") || t.starts_with("pts/")) {
+            // What logind calls text logins, SSH ones with a terminal included.
+            "tty"
+        } else {
+            "unspecified"
+        };
+        sessions.push(Session {
+            user,
+            tty,
+            display,
+            remote: remote_host.is_some(),
+            remote_host,
+            kind: kind.to_string(),
+            started_usec: u64::try_from(seconds).ok().filter(|s| *s > 0).and_then(|s| s.checked_mul(1_000_000)),
+            leader: Some(pid).filter(|p| *p != 0),
+            ..Default::default()
+        });
+    }
+    sessions
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::Session;
+    use dbus::arg::{prop_cast, PropMap};
+    use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+    use dbus::blocking::{Connection, Proxy};
+    use std::time::Duration;
+
+    const DESTINATION: &str = "org.freedesktop.login1";
+    const MANAGER_PATH: &str = "/org/freedesktop/login1";
+    const MANAGER: &str = "org.freedesktop.login1.Manager";
+    const SESSION: &str = "org.freedesktop.login1.Session";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// id, uid, user, seat, path
+    type SessionRow = (String, u32, String, String, dbus::Path<'static>);
+
+    fn describe(e: &dbus::Error) -> String {
+        e.message().unwrap_or_else(|| e.name().unwrap_or("unknown D-Bus error")).to_string()
+    }
+
+    fn proxy<'a>(conn: &'a Connection, path: dbus::Path<'a>) -> Proxy<'a, &'a Connection> {
+        conn.with_proxy(DESTINATION, path, TIMEOUT)
+    }
+
+    fn string(props: &PropMap, key: &str) -> Option<String> {
+        prop_cast::<String>(props, key).filter(|s| !s.is_empty()).cloned()
+    }
+
+    fn timestamp(props: &PropMap, key: &str) -> Option<u64> {
+        prop_cast::<u64>(props, key).copied().filter(|v| *v != 0)
+    }
+
+    pub fn logind_sessions() -> Result<Vec<Session>, String> {
+        let conn = Connection::new_system()
+            .map_err(|e| format!("Cannot connect to the system bus: {}", describe(&e)))?;
+        let manager = proxy(&conn, MANAGER_PATH.into());
+        let (rows,): (Vec<SessionRow>,) = manager
+            .method_call(MANAGER, "ListSessions", ())
+            .map_err(|e| format!("logind ListSessions failed: {}", describe(&e)))?;
+        let own: Option<dbus::Path<'static>> = manager
+            .method_call(MANAGER, "GetSessionByPID", (std::process::id(),))
+            .ok()
+            .map(|(path,): (dbus::Path<'static>,)| path);
+
+        let mut sessions = Vec::new();
+        for (id, uid, user, seat, path) in rows {
+            let current = own.as_ref() == Some(&path);
+            let props = proxy(&conn, path).get_all(SESSION).unwrap_or_default();
+            let idle = prop_cast::<bool>(&props, "IdleHint").copied().unwrap_or(false);
+            sessions.push(Session {
+                id: Some(id),
+                user,
+                uid: Some(uid),
+                tty: string(&props, "TTY"),
+                seat: Some(seat).filter(|s| !s.is_empty()),
+                display: string(&props, "Display"),
+                remote: prop_cast::<bool>(&props, "Remote").copied().unwrap_or(false),
+                remote_host: string(&props, "RemoteHost"),
+                kind: string(&props, "Type").unwrap_or_else(|| "unspecified".to_string()),
+                class: string(&props, "Class"),
+                service: string(&props, "Service"),
+                state: string(&props, "State"),
+                started_usec: timestamp(&props, "Timestamp"),
+                idle_since_usec: timestamp(&props, "IdleSinceHint").filter(|_| idle),
+                leader: prop_cast::<u32>(&props, "Leader").copied().filter(|p| *p != 0),
+                current,
+            });
+        }
+        Ok(sessions)
+    }
+
+    /// PIDs and command names of the server's ancestors, nearest first.
+    pub fn ancestors() -> Vec<(u32, String)> {
+        let mut names = Vec::new();
+        let mut pid = std::process::id();
+        // PID 1 and the kernel threads above it say nothing.
+        while pid > 1 && names.len() < 64 {
+            let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else { break };
+            // pid (comm) state ppid ...; comm may contain spaces and parentheses.
+            let Some((head, tail)) = stat.rsplit_once(')') else { break };
+            let Some(ppid) = tail.split_whitespace().nth(1).and_then(|p| p.parse().ok()) else { break };
+            if pid != std::process::id() {
+                if let Some((_, comm)) = head.split_once('(') {
+                    names.push((pid, comm.to_string()));
+                }
+            }
+            pid = ppid;
+        }
+        names
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::Session;
+
+    pub fn logind_sessions() -> Result<Vec<Session>, String> {
+        Err("logind is only available on Linux".to_string())
+    }
+
+    pub fn ancestors() -> Vec<(u32, String)> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPAT32: UtmpLayout = UtmpLayout { record: 384, tv_sec: 340, wide_time: false };
+    const NATIVE64: UtmpLayout = UtmpLayout { record: 400, tv_sec: 344, wide_time: true };
+
+    fn record(layout: UtmpLayout, kind: i16, pid: u32, line: &str, user: &str, host: &str, seconds: i64) -> Vec<u8> {
+        let mut record = vec![0u8; layout.record];
+        record[0..2].copy_from_slice(&kind.to_ne_bytes());
+        record[4..8].copy_from_slice(&pid.to_ne_bytes());
+        record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+        if layout.wide_time {
+            record[layout.tv_sec..layout.tv_sec + 8].copy_from_slice(&seconds.to_ne_bytes());
+        } else {
+            record[layout.tv_sec..layout.tv_sec + 4].copy_from_slice(&(seconds as i32).to_ne_bytes());
+        }
+        record
+    }
+
+    fn sample(layout: UtmpLayout) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(record(layout, 2, 0, "~", "reboot", "6.1.0", 1_700_000_000));
+        bytes.extend(record(layout, 7, 1234, "pts/0", "alice", "10.0.0.5", 1_700_000_100));
+        bytes.extend(record(layout, 7, 0, "seat0", "bob", ":0", 1_700_000_200));
+        bytes.extend(record(layout, 8, 99, "pts/2", "", "", 1_700_000_300));
+        bytes.extend(record(layout, 7, 77, "pts/3", "", "", 1_700_000_400));
+        // A record cut short by a concurrent write.
+        bytes.extend(&record(layout, 7, 55, "pts/4", "carol", "", 1_700_000_500)[..100]);
+        bytes
+    }
+
+    fn check(sessions: &[Session]) {
+        assert_eq!(sessions.len(), 2);
+        let alice = &sessions[0];
+        assert_eq!(alice.user, "alice");
+        assert_eq!(alice.tty.as_deref(), Some("pts/0"));
+        assert_eq!(alice.kind, "tty");
+        assert!(alice.remote);
+        assert_eq!(alice.remote_host.as_deref(), Some("10.0.0.5"));
+        assert_eq!(alice.started_usec, Some(1_700_000_100_000_000));
+        assert_eq!(alice.leader, Some(1234));
+
+        let bob = &sessions[1];
+        assert_eq!(bob.user, "bob");
+        assert_eq!(bob.kind, "x11");
+        assert_eq!(bob.display.as_deref(), Some(":0"));
+        assert!(!bob.remote);
+        assert_eq!(bob.started_usec, Some(1_700_000_200_000_000));
+        assert_eq!(bob.leader, None);
+    }
+
+    #[test]
+    fn parses_compat32_records() {
+        check(&parse_utmp(&sample(COMPAT32), COMPAT32));
+    }
+
+    #[test]
+    fn parses_native64_records() {
+        check(&parse_utmp(&sample(NATIVE64), NATIVE64));
+    }
+
+    #[test]
+    fn read_utmp_drops_dead_logins() {
+        let layout = UtmpLayout::HOST;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("utmp");
+        let mut bytes = record(layout, 7, std::process::id(), "pts/0", "alice", "", 1_700_000_000);
+        // Above the kernel's pid_max, so never a live process.
+        bytes.extend(record(layout, 7, 1 << 23, "pts/1", "ghost", "", 1_700_000_000));
+        std::fs::write(&path, bytes).unwrap();
+
+        let sessions = read_utmp(&path).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user, "alice");
+        assert!(read_utmp(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Unit, service and timer state from the systemd manager over D-Bus, on the
+//! system bus or the calling user's session bus. Calls are blocking, so async
+//! callers should run them on a blocking thread. Linux-only; elsewhere every
+//! call fails with an explanation.
+
+/// Which manager to ask: PID 1 on the system bus, or the per-user instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    System,
+    User,
+}
+
+impl Bus {
+    pub fn name(self) -> &'static str {
+        match self {
+            Bus::System => "system",
+            Bus::User => "user",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Unit {
+    pub name: String,
+    pub description: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub description: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub unit_file_state: Option<String>,
+    pub fragment_path: Option<String>,
+    /// When the unit entered its current state, in microseconds since the
+    /// epoch, picked the way `systemctl status` does for its "since".
+    pub since_usec: Option<u64>,
+    pub main_pid: Option<u32>,
+    pub memory: Option<u64>,
+    pub tasks: Option<u64>,
+    pub cpu_nsec: Option<u64>,
+    pub restarts: Option<u32>,
+    /// Outcome of the last run: "success", "exit-code", "signal", ...
+    pub result: Option<String>,
+    pub exit_status: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub name: String,
+    /// The unit the timer activates.
+    pub unit: String,
+    /// Next elapse in microseconds since the epoch.
+    pub next_usec: Option<u64>,
+    pub last_usec: Option<u64>,
+}
+
+/// Completes a bare name like "postgres" to "postgres.service".
+pub fn unit_name(name: &str) -> String {
+    const TYPES: &[&str] = &[
+        ".service", ".socket", ".target", ".timer", ".mount", ".automount", ".swap", ".path", ".slice",
+        ".scope", ".device",
+    ];
+    if TYPES.iter().any(|t| name.ends_with(t)) {
+        name.to_string()
+    } else {
+        format!("{}.service", name)
+    }
+}
+
+pub fn list_units(bus: Bus) -> Result<Vec<Unit>, String> {
+    imp::list_units(&imp::connect(bus)?)
+}
+
+pub fn service_status(bus: Bus, name: &str) -> Result<ServiceStatus, String> {
+    imp::service_status(&imp::connect(bus)?, name)
+}
+
+/// Active timers ordered by next elapse; timers with nothing scheduled last.
+pub fn list_timers(bus: Bus) -> Result<Vec<Timer>, String> {
+    let mut timers = imp::list_timers(&imp::connect(bus)?)?;
+    timers.sort_by_key(|t| t.next_usec.unwrap_or(u64::MAX));
+    Ok(timers)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{Bus, ServiceStatus, Timer, Unit};
+    use dbus::arg::{prop_cast, PropMap};
+    use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+    use dbus::blocking::{Connection, Proxy};
+    use dbus::channel::Channel;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const DESTINATION: &str = "org.freedesktop.systemd1";
+    const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+    const MANAGER: &str = "org.freedesktop.systemd1.Manager";
+    const UNIT: &str = "org.freedesktop.systemd1.Unit";
+    const SERVICE: &str = "org.freedesktop.systemd1.Service";
+    const TIMER: &str = "org.freedesktop.systemd1.Timer";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    const PROC_ROOT: &str = "/proc";
+
+    /// systemd's "unset" for timestamps and counters.
+    const INFINITY: u64 = u64::MAX;
+
+    /// name, description, load, active, sub, following, path, job id, job type, job path
+    pub type UnitRow = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        dbus::Path<'static>,
+        u32,
+        String,
+        dbus::Path<'static>,
+    );
+
+    /// Time since boot, which `CLOCK_MONOTONIC` timer deadlines count from
+    /// (exact unless the machine has been suspended).
+    fn monotonic_now_usec(proc_root: &Path) -> Option<u64> {
+        let uptime = std::fs::read_to_string(proc_root.join("uptime")).ok()?;
+        let seconds: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+        Some((seconds * 1_000_000.0) as u64)
+    }
+
+    /// The bus address libdbus would use: the environment's, else the
+    /// well-known system socket or the per-user session bus socket.
+    fn address(bus: Bus) -> Result<String, String> {
+        let var = match bus {
+            Bus::System => "DBUS_SYSTEM_BUS_ADDRESS",
+            Bus::User => "DBUS_SESSION_BUS_ADDRESS",
+        };
+        if let Some(address) = std::env::var(var).ok().filter(|a| !a.is_empty()) {
+            return Ok(address);
+        }
+        match bus {
+            Bus::System => Ok("unix:path=/var/run/dbus/system_bus_socket".to_string()),
+            Bus::User => std::env::var_os("XDG_RUNTIME_DIR")
+                .map(|dir| Path::new(&dir).join("bus"))
+                .filter(|socket| socket.exists())
+                .map(|socket| format!("unix:path={}", socket.display()))
+                .ok_or_else(|| format!("{} is not set", var)),
+        }
+    }
+
+    pub fn connect(bus: Bus) -> Result<Connection, String> {
+        address(bus)
+            .and_then(|address| open(&address))
+            .map_err(|e| format!("Cannot connect to the {} bus: {}", bus.name(), e))
+    }
+
+    /// A private connection to the bus at `address`, such as
+    /// "unix:path=/run/dbus/system_bus_socket".
+    pub fn open(address: &str) -> Result<Connection, String> {
+        let mut channel = Channel::open_private(address).map_err(|e| describe(&e))?;
+        channel.register().map_err(|e| describe(&e))?;
+        Ok(Connection::from(channel))
+    }
+
+    fn describe(e: &dbus::Error) -> String {
+        e.message().unwrap_or_else(|| e.name().unwrap_or("unknown D-Bus error")).to_string()
+    }
+
+    fn proxy<'a>(conn: &'a Connection, path: dbus::Path<'a>) -> Proxy<'a, &'a Connection> {
+        conn.with_proxy(DESTINATION, path, TIMEOUT)
+    }
+
+    fn string(props: &PropMap, key: &str) -> Option<String> {
+        prop_cast::<String>(props, key).filter(|s| !s.is_empty()).cloned()
+    }
+
+    fn set_u64(props: &PropMap, key: &str) -> Option<u64> {
+        prop_cast::<u64>(props, key).copied().filter(|v| *v != 0 && *v != INFINITY)
+    }
+
+    fn rows(conn: &Connection) -> Result<Vec<UnitRow>, String> {
+        let (rows,): (Vec<UnitRow>,) = proxy(conn, MANAGER_PATH.into())
+            .method_call(MANAGER, "ListUnits", ())
+            .map_err(|e| format!("ListUnits failed: {}", describe(&e)))?;
+        Ok(rows)
+    }
+
+    pub fn list_units(conn: &Connection) -> Result<Vec<Unit>, String> {
+        Ok(rows(conn)?
+            .into_iter()
+            .map(|row| Unit {
+                name: row.0,
+                description: row.1,
+                load_state: row.2,
+                active_state: row.3,
+                sub_state: row.4,
+            })
+            .collect())
+    }
+
+    pub fn service_status(conn: &Connection, name: &str) -> Result<ServiceStatus, String> {
+        // LoadUnit also finds units that are installed but not running.
+        let (path,): (dbus::Path<'static>,) = proxy(conn, MANAGER_PATH.into())
+            .method_call(MANAGER, "LoadUnit", (name,))
+            .map_err(|e| format!("Cannot load {}: {}", name, describe(&e)))?;
+        let unit = proxy(conn, path.clone());
+        let props = unit
+            .get_all(UNIT)
+            .map_err(|e| format!("Cannot read {}: {}", name, describe(&e)))?;
+
+        let active_state = string(&props, "ActiveState").unwrap_or_default();
+        let since_key = match active_state.as_str() {
+            "active" | "reloading" => "ActiveEnterTimestamp",
+            "inactive" | "failed" => "InactiveEnterTimestamp",
+            "activating" => "InactiveExitTimestamp",
+            _ => "ActiveExitTimestamp",
+        };
+        let mut status = ServiceStatus {
+            name: name.to_string(),
+            description: string(&props, "Description").unwrap_or_default(),
+            load_state: string(&props, "LoadState").unwrap_or_default(),
+            sub_state: string(&props, "SubState").unwrap_or_default(),
+            unit_file_state: string(&props, "UnitFileState"),
+            fragment_path: string(&props, "FragmentPath"),
+            since_usec: set_u64(&props, since_key),
+            active_state,
+            ..Default::default()
+        };
+
+        if name.ends_with(".service") && status.load_state == "loaded" {
+            if let Ok(service) = unit.get_all(SERVICE) {
+                status.main_pid = prop_cast::<u32>(&service, "MainPID").copied().filter(|pid| *pid != 0);
+                status.memory = prop_cast::<u64>(&service, "MemoryCurrent").copied().filter(|v| *v != INFINITY);
+                status.tasks = prop_cast::<u64>(&service, "TasksCurrent").copied().filter(|v| *v != INFINITY);
+                status.cpu_nsec = prop_cast::<u64>(&service, "CPUUsageNSec").copied().filter(|v| *v != INFINITY);
+                status.restarts = prop_cast::<u32>(&service, "NRestarts").copied();
+                status.result = string(&service, "Result");
+                status.exit_status = prop_cast::<i32>(&service, "ExecMainStatus").copied();
+            }
+        }
+        Ok(status)
+    }
+
+    pub fn list_timers(conn: &Connection) -> Result<Vec<Timer>, String> {
+        let now_usec = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or_default();
+        let monotonic_now = monotonic_now_usec(Path::new(PROC_ROOT));
+
+        let mut timers = Vec::new();
+        for row in rows(conn)? {
+            if !row.0.ends_with(".timer") || row.3 != "active" {
+                continue;
+            }
+            let Ok(props) = proxy(conn, row.6).get_all(TIMER) else { continue };
+            // Calendar timers have a wall-clock deadline; OnBootSec= and
+            // friends count on the monotonic clock and are converted here.
+            let realtime = set_u64(&props, "NextElapseUSecRealtime");
+            let monotonic = set_u64(&props, "NextElapseUSecMonotonic").and_then(|next| {
+                (now_usec + next).checked_sub(monotonic_now?)
+            });
+            let next_usec = match (realtime, monotonic) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            timers.push(Timer {
+                unit: string(&props, "Unit").unwrap_or_default(),
+                next_usec,
+                last_usec: set_u64(&props, "LastTriggerUSec"),
+                name: row.0,
+            });
+        }
+        Ok(timers)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::{Bus, ServiceStatus, Timer, Unit};
+
+    /// There is no bus to connect to.
+    pub enum Connection {}
+
+    pub fn connect(_bus: Bus) -> Result<Connection, String> {
+        Err("systemd is only available on Linux".to_string())
+    }
+
+    pub fn list_units(conn: &Connection) -> Result<Vec<Unit>, String> {
+        match *conn {}
+    }
+
+    pub fn service_status(conn: &Connection, _name: &str) -> Result<ServiceStatus, String> {
+        match *conn {}
+    }
+
+    pub fn list_timers(conn: &Connection) -> Result<Vec<Timer>, String> {
+        match *conn {}
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::imp;
+    use dbus::arg::{PropMap, RefArg, Variant};
+    use dbus::blocking::Connection;
+    use dbus::channel::{MatchingReceiver, Sender};
+    use dbus::message::{MatchRule, Message};
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::time::Duration;
+
+    const NGINX_PATH: &str = "/org/freedesktop/systemd1/unit/nginx_2eservice";
+
+    /// A private dbus-daemon, stopped on drop.
+    struct Bus {
+        daemon: Child,
+        address: String,
+        _dir: tempfile::TempDir,
+    }
+
+    impl Bus {
+        fn start() -> Bus {
+            let dir = tempfile::tempdir().unwrap();
+            let config = dir.path().join("bus.conf");
+            std::fs::write(
+                &config,
+                format!(
+                    "<busconfig><type>session</type><listen>unix:dir={}</listen><auth>EXTERNAL</auth>\
+                     <policy context=\"default\"><allow send_destination=\"*\"/><allow receive_sender=\"*\"/>\
+                     <allow own=\"*\"/></policy></busconfig>",
+                    dir.path().display()
+                ),
+            )
+            .unwrap();
+            let mut daemon = Command::new("dbus-daemon")
+                .arg(format!("--config-file={}", config.display()))
+                .args(["--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("dbus-daemon is needed for the systemd tests");
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+            Bus { daemon, address: address.trim().to_string(), _dir: dir }
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    fn props(entries: Vec<(&str, Box<dyn RefArg>)>) -> PropMap {
+        entries.into_iter().map(|(key, value)| (key.to_string(), Variant(value))).collect()
+    }
+
+    fn unit_row(name: &str, active: &str, sub: &str) -> imp::UnitRow {
+        let path = dbus::Path::from(format!("/org/freedesktop/systemd1/unit/{}", name.replace('.', "_2e")));
+        let description = format!("{} unit", name);
+        let (load, active, sub) = ("loaded".to_string(), active.to_string(), sub.to_string());
+        (name.to_string(), description, load, active, sub, String::new(), path, 0, String::new(), "/".into())
+    }
+
+    /// Answers the systemd manager calls the module makes.
+    fn answer(call: &Message) -> Message {
+        let member = call.member().map(|m| m.to_string()).unwrap_or_default();
+        match member.as_str() {
+            "ListUnits" => call.method_return().append1(vec![
+                unit_row("cron.service", "active", "running"),
+                unit_row("nginx.service", "failed", "failed"),
+                unit_row("backup.timer", "active", "waiting"),
+            ]),
+            "LoadUnit" => match call.read1::<&str>() {
+                Ok("nginx.service") => call.method_return().append1(dbus::Path::from(NGINX_PATH)),
+                Ok(name) => call.error(
+                    &"org.freedesktop.systemd1.NoSuchUnit".into(),
+                    &std::ffi::CString::new(format!("Unit {} not found.", name)).unwrap(),
+                ),
+                Err(e) => panic!("LoadUnit without a name: {}", e),
+            },
+            "GetAll" => call.method_return().append1(match call.read1::<&str>().unwrap() {
+                "org.freedesktop.systemd1.Unit" => props(vec![
+                    ("Description", Box::new("A high performance web server".to_string())),
+                    ("LoadState", Box::new("loaded".to_string())),
+                    ("ActiveState", Box::new("failed".to_string())),
+                    ("SubState", Box::new("failed".to_string())),
+                    ("UnitFileState", Box::new("enabled".to_string())),
+                    ("FragmentPath", Box::new("/lib/systemd/system/nginx.service".to_string())),
+                    ("InactiveEnterTimestamp", Box::new(1_700_000_000_000_000u64)),
+                    ("ActiveEnterTimestamp", Box::new(1_600_000_000_000_000u64)),
+                ]),
+                "org.freedesktop.systemd1.Service" => props(vec![
+                    ("MainPID", Box::new(0u32)),
+                    ("MemoryCurrent", Box::new(u64::MAX)),
+                    ("TasksCurrent", Box::new(0u64)),
+                    ("NRestarts", Box::new(5u32)),
+                    ("Result", Box::new("exit-code".to_string())),
+                    ("ExecMainStatus", Box::new(1i32)),
+                ]),
+                other => panic!("unexpected interface {}", other),
+            }),
+            other => panic!("unexpected call {}", other),
+        }
+    }
+
+    /// Serves a fake systemd manager on the bus until `stop` is set.
+    fn fake_systemd(address: String, stop: Arc<AtomicBool>) -> std::thread::JoinHandle<()> {
+        let (ready, started) = mpsc::channel();
+        let server = std::thread::spawn(move || {
+            let conn = imp::open(&address).unwrap();
+            conn.request_name("org.freedesktop.systemd1", false, true, true).unwrap();
+            conn.start_receive(
+                MatchRule::new_method_call(),
+                Box::new(|call, conn| {
+                    conn.send(answer(&call)).unwrap();
+                    true
+                }),
+            );
+            ready.send(()).unwrap();
+            while !stop.load(Ordering::Relaxed) {
+                conn.process(Duration::from_millis(20)).unwrap();
+            }
+        });
+        started.recv().unwrap();
+        server
+    }
+
+    #[test]
+    fn queries_a_manager_on_a_private_bus() {
+        let bus = Bus::start();
+        let stop = Arc::new(AtomicBool::new(false));
+        let server = fake_systemd(bus.address.clone(), stop.clone());
+        let conn: Connection = imp::open(&bus.address).unwrap();
+
+        let units = imp::list_units(&conn).unwrap();
+        let failed: Vec<_> = units.iter().filter(|u| u.active_state == "failed").collect();
+        assert_eq!(units.len(), 3);
+        assert_eq!(failed.len(), 1);
+        assert_eq!((failed[0].name.as_str(), failed[0].description.as_str()), ("nginx.service", "nginx.service unit"));
+
+        let status = imp::service_status(&conn, "nginx.service").unwrap();
+        assert_eq!(status.description, "A high performance web server");
+        assert_eq!((status.active_state.as_str(), status.sub_state.as_str()), ("failed", "failed"));
+        assert_eq!(status.unit_file_state.as_deref(), Some("enabled"));
+        // A failed unit has been in that state since it went inactive.
+        assert_eq!(status.since_usec, Some(1_700_000_000_000_000));
+        assert_eq!((status.main_pid, status.memory, status.tasks), (None, None, Some(0)));
+        assert_eq!((status.restarts, status.exit_status), (Some(5), Some(1)));
+        assert_eq!(status.result.as_deref(), Some("exit-code"));
+
+        assert_eq!(
+            imp::service_status(&conn, "missing.service").unwrap_err(),
+            "Cannot load missing.service: Unit missing.service not found."
+        );
+
+        stop.store(true, Ordering::Relaxed);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn reports_unreachable_buses() {
+        assert!(imp::open("unix:path=/nonexistent/bus").is_err());
+    }
+}
@@ -59,6 +59,7 @@ pub struct WttrResponse {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct CurrentCondition {
     pub temp_F: String,
     pub temp_C: String,
@@ -83,6 +84,7 @@ pub struct WeatherDesc {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct NearestArea {
     pub areaName: Vec<AreaValue>,
     pub region: Vec<AreaValue>,
@@ -95,6 +97,7 @@ pub struct AreaValue {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct WeatherDay {
     pub date: String,
     pub maxtempF: String,
@@ -105,6 +108,7 @@ pub struct WeatherDay {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct HourlyForecast {
     pub time: String,
     pub tempF: String,
@@ -0,0 +1,512 @@
+//! Sensor capability discovery.
+//!
+//! Each backend is probed once at startup (and again on request) and
+//! classified as available, degraded or unavailable with a reason, so an
+//! assistant can tell "no battery" apart from "battery backend broken".
+//! Backends behind another process are probed concurrently under a short
+//! timeout so a wedged daemon cannot hold up the MCP handshake.
+
+use btleplug::api::Manager as _;
+use btleplug::platform::Manager as BluetoothManager;
+use display_info::DisplayInfo;
+use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+use std::future::Future;
+use std::time::Duration;
+use sysinfo::Components;
+use user_idle::UserIdle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Available,
+    Degraded,
+    Unavailable,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Available => "available",
+            Status::Degraded => "degraded",
+            Status::Unavailable => "unavailable",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Capability {
+    pub sensor: &'static str,
+    pub status: Status,
+    pub reason: String,
+    pub tools: &'static [&'static str],
+}
+
+impl Capability {
+    fn new(sensor: &'static str, tools: &'static [&'static str], status: Status, reason: impl Into<String>) -> Self {
+        Self {
+            sensor,
+            status,
+            reason: reason.into(),
+            tools,
+        }
+    }
+}
+
+const DISPLAY_TOOLS: &[&str] = &["get_display_info", "get_display_at_point", "get_display_by_name"];
+const IDLE_TOOLS: &[&str] = &["get_idle_time", "is_idle_for"];
+const NETWORK_TOOLS: &[&str] = &["get_interfaces"];
+const SOCKET_TOOLS: &[&str] = &["get_listening_ports", "get_connections"];
+const USB_TOOLS: &[&str] = &["get_usb_devices"];
+const BATTERY_TOOLS: &[&str] = &["get_battery_status"];
+const BLUETOOTH_TOOLS: &[&str] = &["scan_ble_devices"];
+const GIT_TOOLS: &[&str] = &[
+    "get_status",
+    "get_log",
+    "get_current_branch",
+    "get_branches",
+    "get_remotes",
+    "get_tags",
+    "get_stash_list",
+    "get_diff_summary",
+];
+const DEV_TOOLS: &[&str] = &["get_dev_environment"];
+const SYSINFO_TOOLS: &[&str] = &[
+    "get_system_info",
+    "get_host_info",
+    "get_cpu_details",
+    "get_memory_pressure",
+    "get_disk_info",
+    "get_disk_io",
+    "find_disk_hogs",
+    "get_top_processes",
+    "find_process",
+    "get_process_details",
+    "get_process_files",
+    "get_process_tree",
+    "query_processes",
+    "list_processes",
+    "get_network_stats",
+    "get_users",
+];
+const SESSION_TOOLS: &[&str] = &["get_sessions"];
+const TEMPERATURE_TOOLS: &[&str] = &["get_component_temps", "diagnose_thermals"];
+const HWMON_TOOLS: &[&str] = &["get_hwmon"];
+const CONTAINER_TOOLS: &[&str] = &["list_containers", "get_container_stats", "get_container_logs"];
+const SYSTEMD_TOOLS: &[&str] = &["list_failed_units", "get_service_status", "list_timers"];
+const LOG_TOOLS: &[&str] = &["tail_logs", "search_logs"];
+const FILE_TOOLS: &[&str] = &["get_recent_file_activity"];
+const WEATHER_TOOLS: &[&str] = &["get_weather", "get_forecast"];
+const HISTORY_TOOLS: &[&str] = &["query_history"];
+
+/// How long a probe of Bluetooth, logind, the container engine or systemd
+/// may take before the backend is reported as degraded. Their own timeouts
+/// (D-Bus calls, engine requests) are far longer.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Every tool list, for checking them against the router.
+#[cfg(test)]
+const TOOL_LISTS: &[&[&str]] = &[
+    DISPLAY_TOOLS,
+    IDLE_TOOLS,
+    NETWORK_TOOLS,
+    SOCKET_TOOLS,
+    USB_TOOLS,
+    BATTERY_TOOLS,
+    BLUETOOTH_TOOLS,
+    GIT_TOOLS,
+    DEV_TOOLS,
+    SYSINFO_TOOLS,
+    SESSION_TOOLS,
+    TEMPERATURE_TOOLS,
+    HWMON_TOOLS,
+    CONTAINER_TOOLS,
+    SYSTEMD_TOOLS,
+    LOG_TOOLS,
+    FILE_TOOLS,
+    WEATHER_TOOLS,
+    HISTORY_TOOLS,
+];
+
+/// Whether an X11 display is advertised. user-idle's X11 backend
+/// dereferences a null display when none is reachable, so callers must
+/// check this before asking it for idle time on Linux.
+pub fn x11_display_available() -> bool {
+    cfg!(not(target_os = "linux")) || std::env::var_os("DISPLAY").is_some()
+}
+
+/// Probes every sensor backend. `history_enabled` reflects whether the
+/// history database was opened at startup, `file_watcher` whether file
+/// activity watching was configured.
+pub async fn probe(history_enabled: bool, file_watcher: Option<&crate::file_activity::FileWatcher>) -> Vec<Capability> {
+    let (bluetooth, sessions, containers, systemd) = tokio::join!(
+        bounded("bluetooth", BLUETOOTH_TOOLS, probe_bluetooth()),
+        bounded("sessions", SESSION_TOOLS, probe_sessions()),
+        bounded("containers", CONTAINER_TOOLS, probe_containers()),
+        bounded("systemd", SYSTEMD_TOOLS, probe_systemd()),
+    );
+    vec![
+        probe_display(),
+        probe_idle(),
+        probe_network(),
+        probe_sockets(),
+        probe_usb(),
+        probe_battery(),
+        bluetooth,
+        Capability::new("git", GIT_TOOLS, Status::Available, "libgit2 is built in"),
+        Capability::new(
+            "toolchains",
+            DEV_TOOLS,
+            Status::Available,
+            "manifests are parsed in-process; toolchains are looked up on PATH per call",
+        ),
+        probe_sysinfo(),
+        sessions,
+        probe_temperatures(),
+        probe_hwmon(),
+        containers,
+        systemd,
+        probe_logs(),
+        probe_files(file_watcher),
+        Capability::new(
+            "weather",
+            WEATHER_TOOLS,
+            Status::Available,
+            "not probed; requires internet access to wttr.in",
+        ),
+        if history_enabled {
+            Capability::new("history", HISTORY_TOOLS, Status::Available, "recording to database")
+        } else {
+            Capability::new("history", HISTORY_TOOLS, Status::Unavailable, "RMCP_SENSORS_HISTORY_DB is not set")
+        },
+    ]
+}
+
+/// Runs `probe`, reporting the sensor as degraded if it takes longer than
+/// `PROBE_TIMEOUT`.
+async fn bounded(
+    sensor: &'static str,
+    tools: &'static [&'static str],
+    probe: impl Future<Output = Capability>,
+) -> Capability {
+    tokio::time::timeout(PROBE_TIMEOUT, probe).await.unwrap_or_else(|_| {
+        Capability::new(
+            sensor,
+            tools,
+            Status::Degraded,
+            format!("no answer within {}s", PROBE_TIMEOUT.as_secs()),
+        )
+    })
+}
+
+fn probe_display() -> Capability {
+    match DisplayInfo::all() {
+        Ok(displays) if displays.is_empty() => {
+            Capability::new("display", DISPLAY_TOOLS, Status::Degraded, "backend works but reports no displays")
+        }
+        Ok(displays) => Capability::new(
+            "display",
+            DISPLAY_TOOLS,
+            Status::Available,
+            format!("{} display(s) detected", displays.len()),
+        ),
+        Err(e) => Capability::new("display", DISPLAY_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+fn probe_idle() -> Capability {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    if !x11_display_available() {
+        let reason = if wayland {
+            "Wayland-only session; idle detection needs X11 or XWayland"
+        } else {
+            "no X11 display (DISPLAY is not set)"
+        };
+        return Capability::new("idle", IDLE_TOOLS, Status::Unavailable, reason);
+    }
+
+    match UserIdle::get_time() {
+        Ok(_) if cfg!(target_os = "linux") && wayland => Capability::new(
+            "idle",
+            IDLE_TOOLS,
+            Status::Degraded,
+            "running under XWayland; only input to X11 windows resets the idle timer",
+        ),
+        Ok(_) => Capability::new("idle", IDLE_TOOLS, Status::Available, "idle time readable"),
+        Err(e) => Capability::new("idle", IDLE_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+fn probe_network() -> Capability {
+    match NetworkInterface::show() {
+        Ok(interfaces) if interfaces.iter().all(|i| i.addr.is_empty()) => Capability::new(
+            "network",
+            NETWORK_TOOLS,
+            Status::Degraded,
+            "no interface has an address",
+        ),
+        Ok(interfaces) => Capability::new(
+            "network",
+            NETWORK_TOOLS,
+            Status::Available,
+            format!("{} interface(s)", interfaces.len()),
+        ),
+        Err(e) => Capability::new("network", NETWORK_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+fn probe_sockets() -> Capability {
+    if std::fs::metadata("/proc/net/tcp").is_err() {
+        return Capability::new("sockets", SOCKET_TOOLS, Status::Unavailable, "/proc/net is not readable");
+    }
+    // Owners are found through /proc/<pid>/fd, which only root can read for
+    // other users' processes. PID 1 is a convenient stand-in for "not ours".
+    if std::fs::read_dir("/proc/1/fd").is_ok() {
+        Capability::new("sockets", SOCKET_TOOLS, Status::Available, "socket tables and owners readable")
+    } else {
+        Capability::new(
+            "sockets",
+            SOCKET_TOOLS,
+            Status::Degraded,
+            "owning processes only resolvable for this user's processes",
+        )
+    }
+}
+
+fn probe_usb() -> Capability {
+    match nusb::list_devices() {
+        Ok(devices) => Capability::new(
+            "usb",
+            USB_TOOLS,
+            Status::Available,
+            format!("{} device(s) connected", devices.count()),
+        ),
+        Err(e) => Capability::new("usb", USB_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+fn probe_battery() -> Capability {
+    let manager = match battery::Manager::new() {
+        Ok(manager) => manager,
+        Err(e) => return Capability::new("battery", BATTERY_TOOLS, Status::Unavailable, e.to_string()),
+    };
+
+    match manager.batteries() {
+        Ok(batteries) => {
+            let results: Vec<_> = batteries.collect();
+            let readable = results.iter().filter(|b| b.is_ok()).count();
+            if results.is_empty() {
+                Capability::new("battery", BATTERY_TOOLS, Status::Unavailable, "no batteries detected")
+            } else if readable < results.len() {
+                Capability::new(
+                    "battery",
+                    BATTERY_TOOLS,
+                    Status::Degraded,
+                    format!("{} of {} batteries readable", readable, results.len()),
+                )
+            } else {
+                Capability::new(
+                    "battery",
+                    BATTERY_TOOLS,
+                    Status::Available,
+                    format!("{} battery(ies)", readable),
+                )
+            }
+        }
+        Err(e) => Capability::new("battery", BATTERY_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+async fn probe_bluetooth() -> Capability {
+    let probe = async {
+        let manager = BluetoothManager::new().await.map_err(|e| e.to_string())?;
+        manager.adapters().await.map(|a| a.len()).map_err(|e| e.to_string())
+    };
+
+    match probe.await {
+        Ok(0) => Capability::new("bluetooth", BLUETOOTH_TOOLS, Status::Unavailable, "no Bluetooth adapters found"),
+        Ok(n) => Capability::new("bluetooth", BLUETOOTH_TOOLS, Status::Available, format!("{} adapter(s)", n)),
+        Err(e) => Capability::new("bluetooth", BLUETOOTH_TOOLS, Status::Unavailable, e),
+    }
+}
+
+fn probe_sysinfo() -> Capability {
+    if sysinfo::IS_SUPPORTED_SYSTEM {
+        Capability::new("sysinfo", SYSINFO_TOOLS, Status::Available, "supported platform")
+    } else {
+        Capability::new("sysinfo", SYSINFO_TOOLS, Status::Unavailable, "platform not supported by sysinfo")
+    }
+}
+
+async fn probe_containers() -> Capability {
+    let engine = match crate::engine::Engine::locate() {
+        Ok(engine) => engine,
+        Err(e) => return Capability::new("containers", CONTAINER_TOOLS, Status::Unavailable, e),
+    };
+    match engine.get("/_ping").await {
+        Ok(_) => Capability::new(
+            "containers",
+            CONTAINER_TOOLS,
+            Status::Available,
+            format!("engine at {}", engine.socket().display()),
+        ),
+        // The socket exists, so an engine is installed; usually we just
+        // lack permission (not in the docker group) or it is stopped.
+        Err(e) => Capability::new(
+            "containers",
+            CONTAINER_TOOLS,
+            Status::Degraded,
+            format!("{}: {}", engine.socket().display(), e),
+        ),
+    }
+}
+
+async fn probe_systemd() -> Capability {
+    match tokio::task::spawn_blocking(|| crate::systemd::list_units(crate::systemd::Bus::System)).await {
+        Ok(Ok(units)) => Capability::new(
+            "systemd",
+            SYSTEMD_TOOLS,
+            Status::Available,
+            format!("{} units loaded on the system manager", units.len()),
+        ),
+        Ok(Err(e)) => Capability::new("systemd", SYSTEMD_TOOLS, Status::Unavailable, e),
+        Err(e) => Capability::new("systemd", SYSTEMD_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+async fn probe_sessions() -> Capability {
+    use crate::sessions::Source;
+    match tokio::task::spawn_blocking(crate::sessions::list_sessions).await {
+        Ok(Ok((Source::Logind, sessions))) => Capability::new(
+            "sessions",
+            SESSION_TOOLS,
+            Status::Available,
+            format!("{} logind session(s)", sessions.len()),
+        ),
+        Ok(Ok((Source::Utmp, _))) => Capability::new(
+            "sessions",
+            SESSION_TOOLS,
+            Status::Degraded,
+            "logind unreachable; reading utmp (no seat, session type or state)",
+        ),
+        Ok(Err(e)) => Capability::new("sessions", SESSION_TOOLS, Status::Unavailable, e),
+        Err(e) => Capability::new("sessions", SESSION_TOOLS, Status::Unavailable, e.to_string()),
+    }
+}
+
+fn probe_logs() -> Capability {
+    let journal = std::process::Command::new(crate::logs::JOURNALCTL)
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    let files = crate::logs::configured_files();
+    let readable = files.iter().filter(|f| std::fs::File::open(f).is_ok()).count();
+
+    let mut sources = Vec::new();
+    if journal {
+        sources.push("journalctl".to_string());
+    }
+    if readable > 0 {
+        sources.push(format!("{} log file(s)", readable));
+    }
+    match (sources.is_empty(), readable < files.len()) {
+        (true, _) => Capability::new("logs", LOG_TOOLS, Status::Unavailable, "no journalctl and no readable log files"),
+        (false, true) => Capability::new(
+            "logs",
+            LOG_TOOLS,
+            Status::Degraded,
+            format!("{}; {} configured file(s) unreadable", sources.join(" and "), files.len() - readable),
+        ),
+        (false, false) => Capability::new("logs", LOG_TOOLS, Status::Available, sources.join(" and ")),
+    }
+}
+
+fn probe_files(watcher: Option<&crate::file_activity::FileWatcher>) -> Capability {
+    let Some(watcher) = watcher else {
+        return Capability::new("files", FILE_TOOLS, Status::Unavailable, "RMCP_SENSORS_WATCH_DIRS is not set");
+    };
+    let watched = watcher.dirs().len();
+    match (watched, watcher.failed().len()) {
+        (0, _) => Capability::new("files", FILE_TOOLS, Status::Unavailable, "no configured directory could be watched"),
+        (_, 0) => Capability::new("files", FILE_TOOLS, Status::Available, format!("watching {} directory(ies)", watched)),
+        (_, failed) => Capability::new(
+            "files",
+            FILE_TOOLS,
+            Status::Degraded,
+            format!("watching {} directory(ies); {} could not be watched", watched, failed),
+        ),
+    }
+}
+
+fn probe_hwmon() -> Capability {
+    let chips = crate::hwmon::read_chips(std::path::Path::new(crate::hwmon::SYSFS_HWMON_ROOT));
+    let rapl = crate::hwmon::read_rapl(std::path::Path::new(crate::hwmon::SYSFS_POWERCAP_ROOT));
+    let rapl_readable = rapl.iter().any(|z| z.energy_uj.is_some());
+
+    match (chips.len(), rapl.is_empty(), rapl_readable) {
+        (0, true, _) => Capability::new("hwmon", HWMON_TOOLS, Status::Unavailable, "no hwmon chips or RAPL zones"),
+        (n, false, false) => Capability::new(
+            "hwmon",
+            HWMON_TOOLS,
+            Status::Degraded,
+            format!("{} hwmon chip(s); RAPL energy counters need root", n),
+        ),
+        (n, _, _) => Capability::new("hwmon", HWMON_TOOLS, Status::Available, format!("{} hwmon chip(s)", n)),
+    }
+}
+
+fn probe_temperatures() -> Capability {
+    let components = Components::new_with_refreshed_list();
+    let readable = components.iter().filter(|c| c.temperature().is_some()).count();
+
+    if components.iter().count() == 0 {
+        Capability::new("temperatures", TEMPERATURE_TOOLS, Status::Unavailable, "no temperature sensors found")
+    } else if readable == 0 {
+        Capability::new(
+            "temperatures",
+            TEMPERATURE_TOOLS,
+            Status::Degraded,
+            "sensors found but none report a temperature",
+        )
+    } else {
+        Capability::new(
+            "temperatures",
+            TEMPERATURE_TOOLS,
+            Status::Available,
+            format!("{} sensor(s)", readable),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tools that report on the server itself rather than a sensor.
+    const SERVER_TOOLS: &[&str] = &["get_server_stats", "list_capabilities"];
+
+    #[test]
+    fn tool_lists_match_the_router() {
+        let router = crate::SensorsServer::tool_router();
+        let listed: Vec<&str> = TOOL_LISTS.iter().flat_map(|tools| tools.iter().copied()).collect();
+        for tool in &listed {
+            assert!(router.has_route(tool), "{} is listed for a sensor but is not a tool", tool);
+        }
+        for tool in router.list_all() {
+            assert!(
+                listed.contains(&tool.name.as_ref()) || SERVER_TOOLS.contains(&tool.name.as_ref()),
+                "{} belongs to no sensor",
+                tool.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_probes_are_degraded() {
+        let hung = bounded("systemd", SYSTEMD_TOOLS, std::future::pending());
+        let capability = hung.await;
+        assert_eq!(capability.status, Status::Degraded);
+        assert_eq!(capability.reason, "no answer within 1s");
+    }
+}
@@ -0,0 +1,206 @@
+//! Change tracking between tool calls.
+//!
+//! Tools that report a set of things (USB devices, processes, dirty files,
+//! ...) describe each reading as a snapshot keyed by item identity and hand
+//! out an opaque token for it. A later call with `since: <token>` gets only
+//! the items that appeared, disappeared or changed since that reading.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Number of snapshots kept across all tools before the oldest tokens expire.
+const MAX_SNAPSHOTS: usize = 256;
+
+/// Item identity mapped to a one-line description of the item. Items whose
+/// description differs between two snapshots are reported as changed; an
+/// empty description means the key describes itself.
+pub type Snapshot = BTreeMap<String, String>;
+
+#[derive(Debug, Default)]
+pub struct Diff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<(String, String)>,
+}
+
+impl Diff {
+    pub fn between(old: &Snapshot, new: &Snapshot) -> Self {
+        let mut diff = Diff::default();
+        for (key, value) in new {
+            match old.get(key) {
+                None => diff.added.push(describe(key, value)),
+                Some(previous) if previous != value => {
+                    diff.changed.push((describe(key, previous), describe(key, value)))
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, value) in old {
+            if !new.contains_key(key) {
+                diff.removed.push(describe(key, value));
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Renders the diff as `+`/`-`/`~` lines.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return "  (no changes)\n".to_string();
+        }
+        let mut output = String::new();
+        for item in &self.added {
+            output.push_str(&format!("  + {}\n", item));
+        }
+        for item in &self.removed {
+            output.push_str(&format!("  - {}\n", item));
+        }
+        for (old, new) in &self.changed {
+            output.push_str(&format!("  ~ {} -> {}\n", old, new));
+        }
+        output.push_str(&format!(
+            "\n{} added, {} removed, {} changed\n",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        ));
+        output
+    }
+}
+
+fn describe(key: &str, value: &str) -> String {
+    if value.is_empty() {
+        key.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug)]
+pub struct ChangeTracker {
+    /// Distinguishes tokens from a previous server run, whose counters
+    /// would otherwise collide with ours.
+    epoch: u64,
+    next_id: AtomicU64,
+    snapshots: Mutex<VecDeque<(String, String, Snapshot)>>,
+}
+
+impl Default for ChangeTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChangeTracker {
+    pub fn new() -> Self {
+        let epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            epoch,
+            next_id: AtomicU64::new(1),
+            snapshots: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Stores a snapshot taken by `scope` (a tool name, optionally with a
+    /// qualifier such as the repository path) and returns its token.
+    pub fn record(&self, scope: &str, snapshot: Snapshot) -> String {
+        let token = format!("{:x}-{}", self.epoch, self.next_id.fetch_add(1, Ordering::Relaxed));
+        let mut snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        if snapshots.len() == MAX_SNAPSHOTS {
+            snapshots.pop_front();
+        }
+        snapshots.push_back((token.clone(), scope.to_string(), snapshot));
+        token
+    }
+
+    /// Compares `current` against the snapshot behind `token`.
+    pub fn diff(&self, scope: &str, token: &str, current: &Snapshot) -> Result<Diff, String> {
+        let snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        let (_, recorded_scope, old) = snapshots
+            .iter()
+            .find(|(t, _, _)| t == token)
+            .ok_or_else(|| format!("Unknown or expired change token '{}'; call again without 'since'", token))?;
+        if recorded_scope != scope {
+            return Err(format!(
+                "Change token '{}' was issued for {}, not {}",
+                token, recorded_scope, scope
+            ));
+        }
+        Ok(Diff::between(old, current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(items: &[(&str, &str)]) -> Snapshot {
+        items.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn diff_detects_added_removed_and_changed() {
+        let old = snapshot(&[("sda", "sda 40%"), ("sdb", ""), ("sdc", "sdc 10%")]);
+        let new = snapshot(&[("sda", "sda 55%"), ("sdc", "sdc 10%"), ("sdd", "")]);
+        let diff = Diff::between(&old, &new);
+        assert_eq!(diff.added, vec!["sdd"]);
+        assert_eq!(diff.removed, vec!["sdb"]);
+        assert_eq!(diff.changed, vec![("sda 40%".to_string(), "sda 55%".to_string())]);
+        assert_eq!(
+            diff.render(),
+            "  + sdd\n  - sdb\n  ~ sda 40% -> sda 55%\n\n1 added, 1 removed, 1 changed\n"
+        );
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let items = snapshot(&[("a", "one"), ("b", "")]);
+        let diff = Diff::between(&items, &items);
+        assert!(diff.is_empty());
+        assert_eq!(diff.render(), "  (no changes)\n");
+    }
+
+    #[test]
+    fn tokens_are_unique_and_scoped() {
+        let tracker = ChangeTracker::new();
+        let first = tracker.record("list_processes", snapshot(&[("1", "init")]));
+        let second = tracker.record("get_usb_devices", snapshot(&[]));
+        assert_ne!(first, second);
+
+        let diff = tracker.diff("list_processes", &first, &snapshot(&[("1", "init"), ("2", "")])).unwrap();
+        assert_eq!(diff.added, vec!["2"]);
+        // A token can be used more than once.
+        assert!(tracker.diff("list_processes", &first, &snapshot(&[("1", "init")])).unwrap().is_empty());
+
+        let err = tracker.diff("get_usb_devices", &first, &snapshot(&[])).unwrap_err();
+        assert!(err.contains("issued for list_processes"), "{}", err);
+        let err = tracker.diff("list_processes", "bogus", &snapshot(&[])).unwrap_err();
+        assert!(err.contains("Unknown or expired"), "{}", err);
+    }
+
+    #[test]
+    fn oldest_tokens_expire() {
+        let tracker = ChangeTracker::new();
+        let tokens: Vec<String> = (0..=MAX_SNAPSHOTS).map(|_| tracker.record("scope", snapshot(&[]))).collect();
+        assert!(tracker.diff("scope", &tokens[0], &snapshot(&[])).is_err());
+        assert!(tracker.diff("scope", &tokens[1], &snapshot(&[])).is_ok());
+        assert!(tracker.diff("scope", &tokens[MAX_SNAPSHOTS], &snapshot(&[])).is_ok());
+    }
+
+    #[test]
+    fn tokens_from_another_run_are_rejected() {
+        let ours = ChangeTracker::new();
+        let theirs = ChangeTracker { epoch: ours.epoch + 1, ..ChangeTracker::new() };
+        let token = theirs.record("scope", snapshot(&[]));
+        ours.record("scope", snapshot(&[]));
+        assert!(ours.diff("scope", &token, &snapshot(&[])).is_err());
+    }
+}
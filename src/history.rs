@@ -0,0 +1,607 @@
+//! Persistent metrics history backed by an embedded SQLite database.
+//!
+//! When `RMCP_SENSORS_HISTORY_DB` is set, a background sampler records CPU,
+//! memory, disk, battery, idle, network and temperature readings at a fixed
+//! interval.
+//! Raw samples are kept for a short window and rolled up into hourly
+//! aggregates that are kept much longer, so long-range trends stay cheap.
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{Components, CpuRefreshKind, Disks, MemoryRefreshKind, Networks, RefreshKind, System};
+use user_idle::UserIdle;
+
+/// Metrics recorded by the sampler, with the unit used when formatting them.
+pub const METRICS: &[(&str, Unit, &str)] = &[
+    ("cpu_percent", Unit::Percent, "Average CPU usage across all cores"),
+    ("memory_used_bytes", Unit::Bytes, "Used RAM"),
+    ("memory_percent", Unit::Percent, "Used RAM as a share of total"),
+    ("swap_used_bytes", Unit::Bytes, "Used swap"),
+    ("disk_free_bytes", Unit::Bytes, "Available space (key: mount point)"),
+    ("battery_charge_percent", Unit::Percent, "State of charge (key: battery index)"),
+    ("battery_health_percent", Unit::Percent, "State of health (key: battery index)"),
+    ("idle_seconds", Unit::Seconds, "Time since last keyboard/mouse input"),
+    ("net_rx_bytes_per_sec", Unit::BytesPerSecond, "Receive rate since the previous sample (key: interface)"),
+    ("net_tx_bytes_per_sec", Unit::BytesPerSecond, "Transmit rate since the previous sample (key: interface)"),
+    ("temperature_celsius", Unit::Celsius, "Component temperature (key: sensor label)"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Percent,
+    Bytes,
+    BytesPerSecond,
+    Seconds,
+    Celsius,
+}
+
+impl Unit {
+    pub fn of(metric: &str) -> Option<Unit> {
+        METRICS.iter().find(|(name, _, _)| *name == metric).map(|(_, unit, _)| *unit)
+    }
+}
+
+/// Sampler and retention settings, read from the environment.
+#[derive(Debug, Clone)]
+pub struct HistoryConfig {
+    pub path: PathBuf,
+    pub interval: Duration,
+    pub raw_retention_days: i64,
+    pub rollup_retention_days: i64,
+}
+
+impl HistoryConfig {
+    /// Returns `None` when `RMCP_SENSORS_HISTORY_DB` is unset (history disabled).
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var_os("RMCP_SENSORS_HISTORY_DB")?;
+
+        let env_u64 = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+
+        Some(Self {
+            path: PathBuf::from(path),
+            interval: Duration::from_secs(env_u64("RMCP_SENSORS_HISTORY_INTERVAL", 60)),
+            raw_retention_days: env_u64("RMCP_SENSORS_HISTORY_RAW_DAYS", 7) as i64,
+            rollup_retention_days: env_u64("RMCP_SENSORS_HISTORY_ROLLUP_DAYS", 365) as i64,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub metric: &'static str,
+    pub key: String,
+    pub value: f64,
+}
+
+impl Sample {
+    fn new(metric: &'static str, key: impl Into<String>, value: f64) -> Self {
+        Self { metric, key: key.into(), value }
+    }
+}
+
+/// How values inside a time range (or bucket) are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Avg,
+    Min,
+    Max,
+    Count,
+    /// `max - min`, how far a reading moved within the range.
+    Delta,
+}
+
+impl Aggregation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "avg" | "average" | "mean" => Some(Self::Avg),
+            "min" | "minimum" => Some(Self::Min),
+            "max" | "maximum" => Some(Self::Max),
+            "count" => Some(Self::Count),
+            "delta" | "change" => Some(Self::Delta),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Count => "count",
+            Self::Delta => "delta",
+        }
+    }
+
+    /// SQL expression over the raw `samples` table.
+    fn raw_sql(self) -> &'static str {
+        match self {
+            Self::Avg => "AVG(value)",
+            Self::Min => "MIN(value)",
+            Self::Max => "MAX(value)",
+            Self::Count => "COUNT(*)",
+            Self::Delta => "MAX(value) - MIN(value)",
+        }
+    }
+
+    /// SQL expression over the `hourly` rollup table.
+    fn rollup_sql(self) -> &'static str {
+        match self {
+            Self::Avg => "SUM(avg * count) / SUM(count)",
+            Self::Min => "MIN(min)",
+            Self::Max => "MAX(max)",
+            Self::Count => "SUM(count)",
+            Self::Delta => "MAX(max) - MIN(min)",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    pub metric: String,
+    pub key: Option<String>,
+    pub start: i64,
+    pub end: i64,
+    pub aggregation: Aggregation,
+    pub bucket_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryRow {
+    pub key: String,
+    pub bucket: Option<i64>,
+    pub value: f64,
+    pub samples: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryResult {
+    pub rows: Vec<HistoryRow>,
+    /// True when the range reached past raw retention and hourly rollups were used.
+    pub from_rollups: bool,
+}
+
+/// SQLite-backed sample store. The connection is guarded by a mutex so the
+/// sampler thread and tool calls can share it.
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+    config: HistoryConfig,
+}
+
+impl std::fmt::Debug for HistoryStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistoryStore").field("config", &self.config).finish()
+    }
+}
+
+impl HistoryStore {
+    pub fn open(config: HistoryConfig) -> rusqlite::Result<Self> {
+        let conn = Self::open_connection(&config.path)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            config,
+        })
+    }
+
+    fn open_connection(path: &Path) -> rusqlite::Result<Connection> {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS samples (
+                 ts     INTEGER NOT NULL,
+                 metric TEXT    NOT NULL,
+                 key    TEXT    NOT NULL,
+                 value  REAL    NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS samples_metric_ts ON samples (metric, ts);
+             CREATE TABLE IF NOT EXISTS hourly (
+                 hour   INTEGER NOT NULL,
+                 metric TEXT    NOT NULL,
+                 key    TEXT    NOT NULL,
+                 avg    REAL    NOT NULL,
+                 min    REAL    NOT NULL,
+                 max    REAL    NOT NULL,
+                 count  INTEGER NOT NULL,
+                 PRIMARY KEY (metric, key, hour)
+             );",
+        )?;
+        Ok(conn)
+    }
+
+    pub fn config(&self) -> &HistoryConfig {
+        &self.config
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn record(&self, ts: i64, samples: &[Sample]) -> rusqlite::Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt =
+                tx.prepare_cached("INSERT INTO samples (ts, metric, key, value) VALUES (?1, ?2, ?3, ?4)")?;
+            for s in samples {
+                stmt.execute(params![ts, s.metric, s.key, s.value])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Rolls completed hours up into `hourly`, then prunes both tables.
+    pub fn apply_retention(&self, now: i64) -> rusqlite::Result<()> {
+        let current_hour = now - now.rem_euclid(3600);
+        let raw_cutoff = now - self.config.raw_retention_days * 86_400;
+        let rollup_cutoff = now - self.config.rollup_retention_days * 86_400;
+
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO hourly (hour, metric, key, avg, min, max, count)
+             SELECT ts - (ts % 3600), metric, key, AVG(value), MIN(value), MAX(value), COUNT(*)
+             FROM samples
+             WHERE ts >= (SELECT COALESCE(MAX(hour), 0) FROM hourly) AND ts < ?1
+             GROUP BY ts - (ts % 3600), metric, key",
+            params![current_hour],
+        )?;
+        tx.execute("DELETE FROM samples WHERE ts < ?1", params![raw_cutoff])?;
+        tx.execute("DELETE FROM hourly WHERE hour < ?1", params![rollup_cutoff])?;
+        tx.commit()
+    }
+
+    pub fn query(&self, q: &HistoryQuery) -> rusqlite::Result<HistoryResult> {
+        let raw_cutoff = Local::now().timestamp() - self.config.raw_retention_days * 86_400;
+        let from_rollups = q.start < raw_cutoff;
+
+        // Long ranges read the rollups, topped up with raw samples newer than
+        // the last completed hour so recent readings are not missing. An hour
+        // that starts before the range but overlaps it is included.
+        let (table, agg, count, starts_in_range) = if from_rollups {
+            (
+                "(SELECT hour AS ts, 3600 AS span, metric, key, avg, min, max, count FROM hourly
+                  UNION ALL
+                  SELECT ts, 1, metric, key, value, value, value, 1 FROM samples
+                  WHERE ts >= (SELECT COALESCE(MAX(hour) + 3600, 0) FROM hourly))",
+                q.aggregation.rollup_sql(),
+                "SUM(count)",
+                "ts + span > ?2",
+            )
+        } else {
+            ("samples", q.aggregation.raw_sql(), "COUNT(*)", "ts >= ?2")
+        };
+
+        // Buckets are aligned to local time so "1d" means calendar days.
+        let offset = Local::now().offset().local_minus_utc() as i64;
+        let bucket_expr = match q.bucket_secs {
+            Some(b) => format!("(((ts + {off}) / {b}) * {b} - {off})", off = offset, b = b),
+            None => "NULL".to_string(),
+        };
+
+        let sql = format!(
+            "SELECT key, {bucket} AS bucket, {agg}, {count}
+             FROM {table}
+             WHERE metric = ?1 AND {starts_in_range} AND ts <= ?3 AND (?4 IS NULL OR key = ?4)
+             GROUP BY key, bucket
+             ORDER BY key, bucket",
+            starts_in_range = starts_in_range,
+            bucket = bucket_expr,
+            agg = agg,
+            count = count,
+            table = table,
+        );
+
+        let conn = self.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params![q.metric, q.start, q.end, q.key], |row| {
+                Ok(HistoryRow {
+                    key: row.get(0)?,
+                    bucket: row.get(1)?,
+                    value: row.get::<_, Option<f64>>(2)?.unwrap_or(0.0),
+                    samples: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(HistoryResult { rows, from_rollups })
+    }
+
+    /// Start of the current unbroken run of raw samples at or above
+    /// `threshold`, or `None` when the latest sample is below it.
+    pub fn above_since(&self, metric: &str, key: &str, threshold: f64) -> rusqlite::Result<Option<i64>> {
+        let conn = self.lock();
+        conn.query_row(
+            "SELECT MIN(ts) FROM samples
+             WHERE metric = ?1 AND key = ?2 AND value >= ?3
+               AND ts > (SELECT COALESCE(MAX(ts), 0) FROM samples WHERE metric = ?1 AND key = ?2 AND value < ?3)",
+            params![metric, key, threshold],
+            |row| row.get(0),
+        )
+    }
+
+    /// Oldest and newest sample timestamps across both tables.
+    pub fn coverage(&self) -> rusqlite::Result<(Option<i64>, Option<i64>)> {
+        let conn = self.lock();
+        conn.query_row(
+            "SELECT MIN(t), MAX(t) FROM (SELECT hour AS t FROM hourly UNION ALL SELECT ts FROM samples)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+}
+
+// ============================================================================
+// Sampler
+// ============================================================================
+
+struct Sampler {
+    sys: System,
+    networks: Networks,
+    networks_refreshed: Instant,
+    components: Components,
+}
+
+impl Sampler {
+    fn new() -> Self {
+        Self {
+            sys: System::new_with_specifics(
+                RefreshKind::nothing()
+                    .with_cpu(CpuRefreshKind::everything())
+                    .with_memory(MemoryRefreshKind::everything()),
+            ),
+            networks: Networks::new_with_refreshed_list(),
+            networks_refreshed: Instant::now(),
+            components: Components::new_with_refreshed_list(),
+        }
+    }
+
+    fn collect(&mut self) -> Vec<Sample> {
+        let mut samples = Vec::new();
+
+        self.sys.refresh_cpu_all();
+        self.sys.refresh_memory();
+
+        let cpus = self.sys.cpus();
+        if !cpus.is_empty() {
+            let usage = cpus.iter().map(|c| c.cpu_usage()).sum::<f32>() / cpus.len() as f32;
+            samples.push(Sample::new("cpu_percent", "", usage as f64));
+        }
+
+        let total_mem = self.sys.total_memory();
+        let used_mem = self.sys.used_memory();
+        samples.push(Sample::new("memory_used_bytes", "", used_mem as f64));
+        if total_mem > 0 {
+            samples.push(Sample::new("memory_percent", "", used_mem as f64 / total_mem as f64 * 100.0));
+        }
+        samples.push(Sample::new("swap_used_bytes", "", self.sys.used_swap() as f64));
+
+        let disks = Disks::new_with_refreshed_list();
+        for disk in disks.iter() {
+            samples.push(Sample::new(
+                "disk_free_bytes",
+                disk.mount_point().display().to_string(),
+                disk.available_space() as f64,
+            ));
+        }
+
+        if let Ok(manager) = battery::Manager::new() {
+            if let Ok(batteries) = manager.batteries() {
+                for (i, battery) in batteries.filter_map(|b| b.ok()).enumerate() {
+                    let charge = battery.state_of_charge().get::<battery::units::ratio::percent>();
+                    let health = battery.state_of_health().get::<battery::units::ratio::percent>();
+                    samples.push(Sample::new("battery_charge_percent", i.to_string(), charge as f64));
+                    samples.push(Sample::new("battery_health_percent", i.to_string(), health as f64));
+                }
+            }
+        }
+
+        if crate::capabilities::x11_display_available() {
+            if let Ok(idle) = UserIdle::get_time() {
+                samples.push(Sample::new("idle_seconds", "", idle.as_seconds() as f64));
+            }
+        }
+
+        // Rates rather than the raw counters, which restart from zero when an
+        // interface is re-created and mean nothing when averaged. sysinfo
+        // clamps the per-refresh difference at zero across such a reset.
+        self.networks.refresh(true);
+        let elapsed = self.networks_refreshed.elapsed().as_secs_f64().max(1e-3);
+        self.networks_refreshed = Instant::now();
+        for (name, data) in self.networks.iter() {
+            samples.push(Sample::new("net_rx_bytes_per_sec", name.clone(), data.received() as f64 / elapsed));
+            samples.push(Sample::new("net_tx_bytes_per_sec", name.clone(), data.transmitted() as f64 / elapsed));
+        }
+
+        self.components.refresh(true);
+        for component in self.components.iter() {
+            if let Some(temp) = component.temperature() {
+                samples.push(Sample::new("temperature_celsius", component.label(), temp as f64));
+            }
+        }
+
+        samples
+    }
+}
+
+/// Starts the background sampler on its own thread. Sensor backends are
+/// blocking, so this keeps them off the async runtime.
+pub fn spawn_sampler(store: Arc<HistoryStore>) -> std::thread::JoinHandle<()> {
+    std::thread::Builder::new()
+        .name("history-sampler".into())
+        .spawn(move || {
+            let interval = store.config().interval;
+            let mut sampler = Sampler::new();
+            let mut last_retention = 0;
+
+            // Prime CPU usage so the first recorded value is meaningful.
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+
+            loop {
+                let samples = sampler.collect();
+                let now = Local::now().timestamp();
+
+                if let Err(e) = store.record(now, &samples) {
+                    tracing::warn!("Failed to record history samples: {}", e);
+                }
+
+                if now - last_retention >= 3600 {
+                    match store.apply_retention(now) {
+                        Ok(()) => last_retention = now,
+                        Err(e) => tracing::warn!("Failed to apply history retention: {}", e),
+                    }
+                }
+
+                std::thread::sleep(interval);
+            }
+        })
+        .expect("failed to spawn history sampler thread")
+}
+
+// ============================================================================
+// Time parsing and formatting
+// ============================================================================
+
+/// Parses a point in time relative to `now` (unix seconds).
+///
+/// Accepts `now`, `today`, `yesterday`, relative offsets like `-2h`, `90d` or
+/// `30m ago`, RFC 3339 timestamps, and local `YYYY-MM-DD[ HH:MM[:SS]]`.
+pub fn parse_time(input: &str, now: i64) -> Result<i64, String> {
+    let s = input.trim();
+    let lower = s.to_lowercase();
+
+    let local_midnight = |days_back: i64| -> Result<i64, String> {
+        let date = Local::now().date_naive() - chrono::Duration::days(days_back);
+        local_timestamp(date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .ok_or_else(|| format!("Invalid local time: {}", input))
+    };
+
+    match lower.as_str() {
+        "now" => return Ok(now),
+        "today" => return local_midnight(0),
+        "yesterday" => return local_midnight(1),
+        _ => {}
+    }
+
+    let relative = lower.trim_end_matches("ago").trim().trim_start_matches('-');
+    if let Some(secs) = parse_duration(relative) {
+        return Ok(now - secs);
+    }
+
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.timestamp());
+    }
+
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return local_timestamp(naive).ok_or_else(|| format!("Invalid local time: {}", input));
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return local_timestamp(date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .ok_or_else(|| format!("Invalid local time: {}", input));
+    }
+
+    Err(format!(
+        "Could not parse time '{}' (try 'now', 'yesterday', '-6h', '90d', or '2024-05-01 14:00')",
+        input
+    ))
+}
+
+fn local_timestamp(naive: NaiveDateTime) -> Option<i64> {
+    Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+}
+
+/// Parses durations like `90s`, `15m`, `6h`, `7d`, `2w` into seconds.
+pub fn parse_duration(input: &str) -> Option<i64> {
+    let s = input.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (num, unit) = s.split_at(split);
+    let n: i64 = num.parse().ok()?;
+    let mult = match unit.trim() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+        "d" | "day" | "days" => 86_400,
+        "w" | "week" | "weeks" => 604_800,
+        _ => return None,
+    };
+    n.checked_mul(mult)
+}
+
+pub fn format_timestamp(ts: i64) -> String {
+    Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(raw_retention_days: i64) -> HistoryStore {
+        HistoryStore::open(HistoryConfig {
+            path: PathBuf::from(":memory:"),
+            interval: Duration::from_secs(60),
+            raw_retention_days,
+            rollup_retention_days: 365,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("90s"), Some(90));
+        assert_eq!(parse_duration("15m"), Some(900));
+        assert_eq!(parse_duration("2 weeks"), Some(1_209_600));
+        assert_eq!(parse_duration("7"), None);
+        assert_eq!(parse_duration("3y"), None);
+    }
+
+    #[test]
+    fn parse_duration_overflow_is_rejected() {
+        assert_eq!(parse_duration("99999999999999999w"), None);
+        assert!(parse_time("99999999999999999w", 1_700_000_000).is_err());
+    }
+
+    #[test]
+    fn rollup_query_includes_hour_overlapping_range_start() {
+        let store = store(1);
+        let now = Local::now().timestamp();
+        let hour = now - 3 * 86_400 - (now - 3 * 86_400).rem_euclid(3600);
+        let sample = |value| [Sample::new("cpu_percent", "", value)];
+        store.record(hour + 600, &sample(10.0)).unwrap();
+        store.record(hour + 2400, &sample(30.0)).unwrap();
+        store.apply_retention(now).unwrap();
+
+        let result = store
+            .query(&HistoryQuery {
+                metric: "cpu_percent".to_string(),
+                key: None,
+                start: hour + 1800,
+                end: now,
+                aggregation: Aggregation::Avg,
+                bucket_secs: None,
+            })
+            .unwrap();
+        assert!(result.from_rollups);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].value, 20.0);
+        assert_eq!(result.rows[0].samples, 2);
+    }
+}
@@ -2,40 +2,138 @@
 //!
 //! A unified MCP server that exposes all sensor tools in one binary.
 
+mod capabilities;
+mod changes;
+mod history;
+mod telemetry;
+
+// Sensor modules shared with the standalone servers.
+use rmcp_containers::engine;
+use rmcp_files::file_activity;
+use rmcp_git::dev_env;
+use rmcp_logs::logs;
+use rmcp_network::sockets;
+use rmcp_sysinfo::{
+    cgroup, cpu, disk_io, disk_usage, host, hwmon, mounts, net_io, pressure, proc_files, process_query, process_tree,
+    thermal,
+};
+use rmcp_systemd::{sessions, systemd};
+
 use btleplug::api::{Central, Manager as BtManager, Peripheral as _, ScanFilter};
 use btleplug::platform::Manager as BluetoothManager;
+use capabilities::{Capability, Status};
+use changes::{ChangeTracker, Snapshot};
 use display_info::DisplayInfo;
+use engine::{ContainerSummary, Engine, Stats};
+use file_activity::{Change, FileWatcher};
 use git2::{BranchType, Repository, StatusOptions};
+use history::{Aggregation, HistoryConfig, HistoryQuery, HistoryStore, Unit};
+use logs::{LogEntry, LogFilter, Source};
 use network_interface::{Addr, NetworkInterface, NetworkInterfaceConfig};
 use nusb::list_devices;
+use proc_files::FdKind;
+use process_query::{ProcessQuery, SortKey};
+use process_tree::ProcessTree;
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters, ServerHandler},
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters, ServerHandler},
     model::*,
+    service::RequestContext,
     transport::stdio,
     ErrorData as McpError,
+    Peer,
+    RoleServer,
     ServiceExt,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sockets::{Protocol, Socket};
+use systemd::Bus;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
-use sysinfo::{Components, CpuRefreshKind, Disks, MemoryRefreshKind, Networks, RefreshKind, System, Users};
+use sysinfo::{
+    Components, CpuRefreshKind, Disks, MemoryRefreshKind, Networks, ProcessRefreshKind, ProcessesToUpdate,
+    RefreshKind, System, Users,
+};
+use telemetry::Telemetry;
 use user_idle::UserIdle;
 
 // ============================================================================
 // Parameter structs
 // ============================================================================
 
+/// How much output a tool produces. Agents can start with `summary` and
+/// drill down with `full` only when they need it. Only the unified server
+/// takes it; the standalone crates keep their fixed reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Detail {
+    Summary,
+    #[default]
+    Normal,
+    Full,
+}
+
+const DETAIL_DESCRIPTION: &str = "Output detail: 'summary' (one line), 'normal' (default) or 'full' \
+    (everything known)";
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DetailParams {
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ChangeParams {
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report what changed since that reading")]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct IdleThresholdParams {
     #[schemars(description = "Threshold in seconds to check against (default: 300)")]
     pub threshold_seconds: u64,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct RepoPathParams {
     #[schemars(description = "Path to the git repository (defaults to current directory)")]
     pub path: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RepoChangeParams {
+    #[schemars(description = "Path to the git repository (defaults to current directory)")]
+    pub path: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report what changed since that reading")]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DevEnvParams {
+    #[schemars(description = "Project directory; parent directories up to the git root are searched when it has \
+        no manifest (defaults to current directory)")]
+    #[serde(default)]
+    pub path: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -46,24 +144,375 @@ pub struct TopProcessesParams {
     #[schemars(description = "Sort by: 'cpu' or 'memory' (default 'cpu')")]
     #[serde(default)]
     pub sort_by: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct LocationParams {
     #[schemars(description = "Location to get weather for (city name, zip code, or 'lat,lon')")]
     pub location: String,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct FindProcessParams {
     #[schemars(description = "Process name to search for (case-insensitive, partial match)")]
     pub name: String,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report matching processes started or exited since")]
+    #[serde(default)]
+    pub since: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessIdParams {
     #[schemars(description = "Process ID (PID) to get details for")]
     pub pid: u32,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ListContainersParams {
+    #[schemars(description = "Include stopped containers (default false)")]
+    #[serde(default)]
+    pub all: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report what changed since that reading")]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerStatsParams {
+    #[schemars(description = "Container name or ID (default: all running containers)")]
+    #[serde(default)]
+    pub container: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerLogsParams {
+    #[schemars(description = "Container name or ID")]
+    pub container: String,
+    #[schemars(description = "Number of most recent log lines (default 100, max 2000)")]
+    #[serde(default)]
+    pub tail: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FailedUnitsParams {
+    #[schemars(description = "Ask the user's service manager (systemctl --user) instead of the system one (default false)")]
+    #[serde(default)]
+    pub user: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report what changed since that reading")]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ServiceStatusParams {
+    #[schemars(description = "Unit name, e.g. 'postgresql' or 'nginx.service' ('.service' is added when no type is given)")]
+    pub name: String,
+    #[schemars(description = "Ask the user's service manager (systemctl --user) instead of the system one (default false)")]
+    #[serde(default)]
+    pub user: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TimersParams {
+    #[schemars(description = "Ask the user's service manager (systemctl --user) instead of the system one (default false)")]
+    #[serde(default)]
+    pub user: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TailLogsParams {
+    #[schemars(description = "'journal' or one of the configured log file paths (default: all sources)")]
+    #[serde(default)]
+    pub source: Option<String>,
+    #[schemars(description = "Only this systemd unit, e.g. 'nginx' or 'backup.service' (journal only)")]
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[schemars(description = "Only this priority or more severe: emerg, alert, crit, err, warning, notice, info, debug \
+        or 0-7 (journal only)")]
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[schemars(description = "Only entries from the last N minutes (max 43200, 30 days)")]
+    #[serde(default)]
+    pub minutes: Option<u64>,
+    #[schemars(description = "Number of most recent lines (default 50, max 1000)")]
+    #[serde(default)]
+    pub lines: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchLogsParams {
+    #[schemars(description = "Regex matched against the message and its source, e.g. '(?i)out of memory|oom'")]
+    pub pattern: String,
+    #[schemars(description = "'journal' or one of the configured log file paths (default: all sources)")]
+    #[serde(default)]
+    pub source: Option<String>,
+    #[schemars(description = "Only this systemd unit, e.g. 'nginx' or 'backup.service' (journal only)")]
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[schemars(description = "Only this priority or more severe: emerg, alert, crit, err, warning, notice, info, debug \
+        or 0-7 (journal only)")]
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[schemars(description = "Search the last N minutes (default 1440, one day; max 43200, 30 days)")]
+    #[serde(default)]
+    pub minutes: Option<u64>,
+    #[schemars(description = "Maximum number of matches, newest kept (default 50, max 500)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FileActivityParams {
+    #[schemars(description = "Only files below this path, which must be inside a watched directory \
+        (default: all watched directories)")]
+    #[serde(default)]
+    pub path: Option<String>,
+    #[schemars(description = "How far back to look: '10m' (default), '2h' or '1d'; at most 7 days")]
+    #[serde(default)]
+    pub within: Option<String>,
+    #[schemars(description = "Maximum number of files to list, newest first (default 50, max 500)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SystemInfoParams {
+    #[schemars(description = "Compute CPU and memory percentages against this process's cgroup limits \
+        (container or systemd slice) instead of host totals (default false)")]
+    #[serde(default)]
+    pub relative_to_cgroup: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HwmonParams {
+    #[schemars(description = "Window for measuring CPU package power in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ThermalParams {
+    #[schemars(description = "Window for watching throttle counters and CPU load in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DiskInfoParams {
+    #[schemars(description = "Also list pseudo filesystems such as tmpfs, overlay and squashfs (default false)")]
+    #[serde(default)]
+    pub include_pseudo: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report what changed since that reading")]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiskHogsParams {
+    #[schemars(description = "Directory to investigate, e.g. '/' or '/home/me'")]
+    pub path: String,
+    #[schemars(description = "How many levels below the path to report directory sizes for (default 2, max 6)")]
+    #[serde(default)]
+    pub depth: Option<usize>,
+    #[schemars(description = "Number of directories, files and reclaimable entries to show (default 10, max 100)")]
+    #[serde(default)]
+    pub top_n: Option<usize>,
+    #[schemars(description = "Time budget for the walk in seconds (default 10, max 120)")]
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DiskIoParams {
+    #[schemars(description = "Sampling window for rates in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = "Number of top processes by disk I/O to show (default 5)")]
+    #[serde(default)]
+    pub top: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkStatsParams {
+    #[schemars(description = "Sampling window for rates in milliseconds (default 1000, max 10000)")]
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = "Number of processes to show in the per-process estimate (default 5)")]
+    #[serde(default)]
+    pub top: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessFilesParams {
+    #[schemars(description = "Process ID (PID) to list open files for")]
+    pub pid: u32,
+    #[schemars(description = "Only show descriptors and mappings whose target contains this text, e.g. a file path")]
+    #[serde(default)]
+    pub path: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct QueryProcessesParams {
+    #[schemars(description = "Only processes owned by this user name")]
+    #[serde(default)]
+    pub user: Option<String>,
+    #[schemars(description = "Regex matched against the process name")]
+    #[serde(default)]
+    pub name: Option<String>,
+    #[schemars(description = "Regex matched against the full command line")]
+    #[serde(default)]
+    pub cmdline: Option<String>,
+    #[schemars(description = "Substring of the executable path, e.g. '/usr/lib/firefox'")]
+    #[serde(default)]
+    pub exe: Option<String>,
+    #[schemars(description = "Only processes whose working directory is inside this path")]
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[schemars(description = "Status: running, sleeping, zombie, disk-sleep, stopped or dead")]
+    #[serde(default)]
+    pub status: Option<String>,
+    #[schemars(description = "Minimum CPU usage in percent")]
+    #[serde(default)]
+    pub min_cpu: Option<f32>,
+    #[schemars(description = "Minimum resident memory in MB")]
+    #[serde(default)]
+    pub min_memory_mb: Option<u64>,
+    #[schemars(description = "Only processes running for at least this many seconds")]
+    #[serde(default)]
+    pub min_age_seconds: Option<u64>,
+    #[schemars(description = "Only processes running for at most this many seconds")]
+    #[serde(default)]
+    pub max_age_seconds: Option<u64>,
+    #[schemars(description = "Sort by: cpu, memory, age, pid, name or user (default 'cpu')")]
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    #[schemars(description = "Sort ascending instead of descending (default false)")]
+    #[serde(default)]
+    pub ascending: Option<bool>,
+    #[schemars(description = "Columns to show: pid, ppid, user, status, cpu, memory, age, name, exe, cwd, cmd \
+        (default pid, cpu, memory, name)")]
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[schemars(description = "Maximum number of rows (default 50)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessTreeParams {
+    #[schemars(description = "Root the tree at this PID (default: the whole system)")]
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[schemars(description = "Maximum depth below the root to expand (default: unlimited)")]
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ListeningPortsParams {
+    #[schemars(description = "Protocol: tcp, udp, tcp4, tcp6, udp4, udp6, unix, inet or all (default 'inet')")]
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[schemars(description = "Only show this port number")]
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[schemars(description = "Change token from a previous call; only report listeners opened or closed since")]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectionsParams {
+    #[schemars(description = "Protocol: tcp, udp, tcp4, tcp6, udp4, udp6, unix, inet or all (default 'inet')")]
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[schemars(description = "Only sockets in this state, e.g. ESTABLISHED, TIME_WAIT, LISTEN (case-insensitive)")]
+    #[serde(default)]
+    pub state: Option<String>,
+    #[schemars(description = "Only sockets whose local or remote port is this")]
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[schemars(description = "Only sockets owned by this PID")]
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[schemars(description = "Maximum number of sockets to list (default 100)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -73,6 +522,9 @@ pub struct ForecastParams {
     #[schemars(description = "Number of days (1-3, default 3)")]
     #[serde(default)]
     pub days: Option<u8>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -81,12 +533,55 @@ pub struct DisplayPointParams {
     pub x: i32,
     #[schemars(description = "Y coordinate on screen")]
     pub y: i32,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct DisplayNameParams {
     #[schemars(description = "Display name to search for")]
     pub name: String,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CapabilitiesParams {
+    #[schemars(description = "Re-probe every sensor instead of returning the startup results (default false); hidden \
+        tools follow the new results")]
+    #[serde(default)]
+    pub refresh: Option<bool>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryQueryParams {
+    #[schemars(description = "Metric to query: cpu_percent, memory_used_bytes, memory_percent, swap_used_bytes, \
+        disk_free_bytes, battery_charge_percent, battery_health_percent, idle_seconds, net_rx_bytes_per_sec, \
+        net_tx_bytes_per_sec, temperature_celsius")]
+    pub metric: String,
+    #[schemars(description = "Restrict to one key: mount point for disk, interface for network, battery index for battery")]
+    #[serde(default)]
+    pub key: Option<String>,
+    #[schemars(description = "Range start: 'yesterday', '-6h', '90d', RFC 3339, or local 'YYYY-MM-DD HH:MM' (default '-24h')")]
+    #[serde(default)]
+    pub start: Option<String>,
+    #[schemars(description = "Range end, same formats as start (default 'now')")]
+    #[serde(default)]
+    pub end: Option<String>,
+    #[schemars(description = "Aggregation: 'avg', 'min', 'max', 'count' or 'delta' (default 'avg')")]
+    #[serde(default)]
+    pub aggregation: Option<String>,
+    #[schemars(description = "Split the range into buckets, e.g. '1h' or '1d' (default: one value for the whole range)")]
+    #[serde(default)]
+    pub bucket: Option<String>,
+    #[schemars(description = DETAIL_DESCRIPTION)]
+    #[serde(default)]
+    pub detail: Option<Detail>,
 }
 
 // ============================================================================
@@ -101,6 +596,7 @@ pub struct WttrResponse {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct CurrentCondition {
     pub temp_F: String,
     pub temp_C: String,
@@ -125,6 +621,7 @@ pub struct WeatherDesc {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct NearestArea {
     pub areaName: Vec<AreaValue>,
     pub region: Vec<AreaValue>,
@@ -137,6 +634,7 @@ pub struct AreaValue {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct WeatherDay {
     pub date: String,
     pub maxtempF: String,
@@ -147,6 +645,7 @@ pub struct WeatherDay {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
 pub struct HourlyForecast {
     pub time: String,
     pub tempF: String,
@@ -163,6 +662,14 @@ pub struct HourlyForecast {
 pub struct SensorsServer {
     pub tool_router: ToolRouter<Self>,
     http_client: reqwest::Client,
+    history: Option<Arc<HistoryStore>>,
+    file_watcher: Option<Arc<FileWatcher>>,
+    telemetry: Telemetry,
+    capabilities: RwLock<Vec<Capability>>,
+    /// Whether tools of unavailable sensors are left out of `tools/list`.
+    hide_unavailable: bool,
+    hidden_tools: RwLock<BTreeSet<&'static str>>,
+    changes: ChangeTracker,
 }
 
 impl Default for SensorsServer {
@@ -176,7 +683,114 @@ impl SensorsServer {
         Self {
             tool_router: Self::tool_router(),
             http_client: reqwest::Client::new(),
+            history: None,
+            file_watcher: None,
+            telemetry: Telemetry::new(),
+            capabilities: RwLock::new(Vec::new()),
+            hide_unavailable: false,
+            hidden_tools: RwLock::new(BTreeSet::new()),
+            changes: ChangeTracker::new(),
+        }
+    }
+
+    pub fn with_history(mut self, history: Arc<HistoryStore>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    pub fn with_file_watcher(mut self, watcher: Arc<FileWatcher>) -> Self {
+        self.file_watcher = Some(watcher);
+        self
+    }
+
+    pub fn with_capabilities(self, capabilities: Vec<Capability>) -> Self {
+        self.set_capabilities(capabilities);
+        self
+    }
+
+    /// Leaves tools whose sensor is unavailable out of `tools/list`, now and
+    /// whenever the sensors are probed again.
+    pub fn hide_unavailable_tools(mut self) -> Self {
+        self.hide_unavailable = true;
+        self.update_hidden_tools();
+        self
+    }
+
+    /// Stores fresh probe results. Every tool they name must exist.
+    fn set_capabilities(&self, capabilities: Vec<Capability>) {
+        for tool in capabilities.iter().flat_map(|c| c.tools) {
+            assert!(self.tool_router.has_route(tool), "capability lists unknown tool {}", tool);
         }
+        *self.capabilities.write().unwrap_or_else(|e| e.into_inner()) = capabilities;
+    }
+
+    /// Recomputes which tools are hidden from the current capabilities and
+    /// returns whether that changed the tool list.
+    fn update_hidden_tools(&self) -> bool {
+        let hidden: BTreeSet<&'static str> = if self.hide_unavailable {
+            self.capabilities()
+                .iter()
+                .filter(|c| c.status == Status::Unavailable)
+                .flat_map(|c| c.tools.iter().copied())
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+
+        let mut current = self.hidden_tools.write().unwrap_or_else(|e| e.into_inner());
+        for tool in hidden.difference(&current) {
+            tracing::info!("Hiding {} (sensor unavailable)", tool);
+        }
+        for tool in current.difference(&hidden) {
+            tracing::info!("Showing {} (sensor available again)", tool);
+        }
+        let changed = *current != hidden;
+        *current = hidden;
+        changed
+    }
+
+    fn is_hidden(&self, tool: &str) -> bool {
+        self.hidden_tools.read().unwrap_or_else(|e| e.into_inner()).contains(tool)
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.capabilities.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn ensure_x11_display() -> Result<(), McpError> {
+        if capabilities::x11_display_available() {
+            Ok(())
+        } else {
+            Err(McpError::internal_error(
+                "Failed to get idle time: no X11 display available (DISPLAY is not set)",
+                None,
+            ))
+        }
+    }
+
+    /// Finishes a tool that supports `since`. With a token the report is
+    /// replaced by the differences from that reading; either way a fresh
+    /// token for this reading is appended.
+    fn report_changes(
+        &self,
+        scope: &str,
+        title: &str,
+        since: Option<&str>,
+        snapshot: Snapshot,
+        output: String,
+    ) -> Result<CallToolResult, McpError> {
+        let output = match since {
+            Some(token) => {
+                let diff = self.changes.diff(scope, token, &snapshot);
+                self.telemetry.record_cache("change tokens", diff.is_ok());
+                let diff = diff.map_err(|e| McpError::invalid_params(e, None))?;
+                format!("{} changes since {}:\n\n{}", title, token, diff.render())
+            }
+            None => output,
+        };
+        let token = self.changes.record(scope, snapshot);
+        let output = format!("{}\n\nChange token: {}", output.trim_end(), token);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     // Helper functions
@@ -218,14 +832,87 @@ impl SensorsServer {
         }
     }
 
-    fn format_single_display(d: &DisplayInfo) -> String {
+    fn socket_protocols(filter: Option<&str>) -> Result<Vec<Protocol>, McpError> {
+        match filter {
+            Some(filter) => Protocol::parse_filter(filter)
+                .ok_or_else(|| McpError::invalid_params(format!("Unknown protocol '{}'", filter), None)),
+            None => Ok(Protocol::INET.to_vec()),
+        }
+    }
+
+    fn socket_owner(socket: &Socket) -> String {
+        match (socket.pid, &socket.process) {
+            (Some(pid), Some(name)) => format!("{:<8} {}", pid, name),
+            _ => format!("{:<8} {}", "-", "-"),
+        }
+    }
+
+    fn unresolved_owners_note(sockets: &[&Socket]) -> String {
+        let unresolved = sockets.iter().filter(|s| s.pid.is_none() && s.inode != 0).count();
+        if unresolved == 0 {
+            String::new()
+        } else {
+            format!(
+                "\n{} socket(s) could not be matched to a process (other users' processes need root)\n",
+                unresolved
+            )
+        }
+    }
+
+    /// Extra lines appended under a process table row at `detail: full`.
+    fn process_extras(proc: &sysinfo::Process, users: &Users) -> String {
+        let mut extras = String::new();
+        if let Some(user) = proc.user_id().and_then(|uid| users.get_user_by_id(uid)) {
+            extras.push_str(&format!("         User: {}\n", user.name()));
+        }
+        if let Some(exe) = proc.exe() {
+            extras.push_str(&format!("         Exe: {}\n", exe.display()));
+        }
+        if let Some(cwd) = proc.cwd() {
+            extras.push_str(&format!("         Cwd: {}\n", cwd.display()));
+        }
+        let cmd: Vec<_> = proc.cmd().iter().map(|s| s.to_string_lossy()).collect();
+        if !cmd.is_empty() {
+            extras.push_str(&format!("         Cmd: {}\n", cmd.join(" ")));
+        }
+        extras
+    }
+
+    fn format_metric(value: f64, unit: Option<Unit>) -> String {
+        match unit {
+            Some(Unit::Percent) => format!("{:.1}%", value),
+            Some(Unit::Bytes) => Self::format_bytes(value.max(0.0) as u64),
+            Some(Unit::BytesPerSecond) => format!("{}/s", Self::format_bytes(value.max(0.0) as u64)),
+            Some(Unit::Seconds) => Self::format_duration(value.max(0.0) as u64),
+            Some(Unit::Celsius) => format!("{:.1}°C", value),
+            None => format!("{:.2}", value),
+        }
+    }
+
+    fn display_name(d: &DisplayInfo) -> &str {
+        if d.friendly_name.is_empty() { &d.name } else { &d.friendly_name }
+    }
+
+    fn display_summary(d: &DisplayInfo) -> String {
+        let mut summary = format!("{} {}x{}", Self::display_name(d), d.width, d.height);
+        if d.frequency > 0.0 {
+            summary.push_str(&format!("@{:.0}Hz", d.frequency));
+        }
+        if d.is_primary {
+            summary.push_str(" primary");
+        }
+        summary
+    }
+
+    fn format_single_display(d: &DisplayInfo, detail: Detail) -> String {
+        if detail == Detail::Summary {
+            return format!("{}\n", Self::display_summary(d));
+        }
+
+        let full = detail == Detail::Full;
         let mut result = String::new();
         let primary = if d.is_primary { " (primary)" } else { "" };
-        result.push_str(&format!(
-            "{}{}\n",
-            if d.friendly_name.is_empty() { &d.name } else { &d.friendly_name },
-            primary
-        ));
+        result.push_str(&format!("{}{}\n", Self::display_name(d), primary));
         result.push_str(&format!("  Resolution: {}x{}\n", d.width, d.height));
         result.push_str(&format!("  Position: ({}, {})\n", d.x, d.y));
 
@@ -242,14 +929,21 @@ impl SensorsServer {
             result.push_str(&format!("  Refresh: {:.0}Hz\n", d.frequency));
         }
 
-        if d.scale_factor != 1.0 {
+        if d.scale_factor != 1.0 || full {
             result.push_str(&format!("  Scale: {:.0}%\n", d.scale_factor * 100.0));
         }
 
-        if d.rotation != 0.0 {
+        if d.rotation != 0.0 || full {
             result.push_str(&format!("  Rotation: {}°\n", d.rotation as i32));
         }
 
+        if full {
+            result.push_str(&format!("  ID: {}\n", d.id));
+            if !d.friendly_name.is_empty() && d.friendly_name != d.name {
+                result.push_str(&format!("  Output: {}\n", d.name));
+            }
+        }
+
         result
     }
 
@@ -262,12 +956,270 @@ impl SensorsServer {
             .map_err(|e| McpError::internal_error(format!("Not a git repository: {}", e), None))
     }
 
-    fn battery_state_to_string(state: battery::State) -> &'static str {
-        match state {
-            battery::State::Charging => "Charging",
-            battery::State::Discharging => "Discharging",
-            battery::State::Empty => "Empty",
-            battery::State::Full => "Full",
+    fn container_engine() -> Result<Engine, McpError> {
+        Engine::locate().map_err(|e| McpError::internal_error(e, None))
+    }
+
+    fn check_container_reference(container: &str) -> Result<(), McpError> {
+        if engine::is_valid_reference(container) {
+            Ok(())
+        } else {
+            Err(McpError::invalid_params(
+                format!("Invalid container name or ID '{}'", container),
+                None,
+            ))
+        }
+    }
+
+    /// One line of `docker stats`-like numbers.
+    fn format_container_stats(stats: &Stats) -> String {
+        let mut parts = Vec::new();
+        if let Some(cpu) = stats.cpu_percent() {
+            parts.push(format!("CPU {:.1}%", cpu));
+        }
+        if let Some(used) = stats.memory_used() {
+            match stats.memory_stats.limit.filter(|limit| *limit > 0) {
+                Some(limit) => parts.push(format!(
+                    "memory {} / {} ({:.1}%)",
+                    Self::format_bytes(used),
+                    Self::format_bytes(limit),
+                    used as f64 / limit as f64 * 100.0
+                )),
+                None => parts.push(format!("memory {}", Self::format_bytes(used))),
+            }
+        }
+        let (rx, tx) = stats.network_totals();
+        if stats.networks.is_some() {
+            parts.push(format!("net {} in / {} out", Self::format_bytes(rx), Self::format_bytes(tx)));
+        }
+        let (read, written) = stats.block_totals();
+        if read > 0 || written > 0 {
+            parts.push(format!(
+                "block {} read / {} written",
+                Self::format_bytes(read),
+                Self::format_bytes(written)
+            ));
+        }
+        if let Some(pids) = stats.pids_stats.current {
+            parts.push(format!("{} pids", pids));
+        }
+        parts.join(", ")
+    }
+
+    fn systemd_bus(user: Option<bool>) -> Bus {
+        if user.unwrap_or(false) {
+            Bus::User
+        } else {
+            Bus::System
+        }
+    }
+
+    /// Runs a blocking D-Bus query off the async runtime.
+    async fn systemd_query<T: Send + 'static>(
+        f: impl FnOnce() -> Result<T, String> + Send + 'static,
+    ) -> Result<T, McpError> {
+        tokio::task::spawn_blocking(f)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?
+            .map_err(|e| McpError::internal_error(e, None))
+    }
+
+    /// "5m ago" or "in 2h 10m" for a timestamp in microseconds since the
+    /// epoch; `absolute` adds the local date and time.
+    fn relative_time(usec: u64, absolute: bool) -> String {
+        let now = chrono::Utc::now().timestamp_micros().max(0) as u64;
+        let relative = if usec > now {
+            format!("in {}", Self::format_duration((usec - now) / 1_000_000))
+        } else {
+            format!("{} ago", Self::format_duration((now - usec) / 1_000_000))
+        };
+        match chrono::DateTime::from_timestamp_micros(usec as i64).filter(|_| absolute) {
+            Some(time) => format!(
+                "{} ({})",
+                time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                relative
+            ),
+            None => relative,
+        }
+    }
+
+    /// Resolves the requested log sources and the filter shared by
+    /// `tail_logs` and `search_logs`.
+    fn log_query(
+        source: Option<&str>,
+        unit: Option<&str>,
+        priority: Option<&str>,
+        minutes: Option<u64>,
+        pattern: Option<&str>,
+        limit: usize,
+    ) -> Result<(Vec<Source>, LogFilter), McpError> {
+        let files = logs::configured_files();
+        let journal_only = unit.is_some() || priority.is_some();
+        let sources = match source {
+            None | Some("") if journal_only => vec![Source::Journal],
+            None | Some("") => std::iter::once(Source::Journal).chain(files.into_iter().map(Source::File)).collect(),
+            Some("journal") => vec![Source::Journal],
+            Some(path) if journal_only => {
+                return Err(McpError::invalid_params(
+                    format!("'{}' is a plain log file; unit and priority filters only apply to the journal", path),
+                    None,
+                ))
+            }
+            Some(path) => {
+                let path = PathBuf::from(path);
+                if !files.contains(&path) {
+                    return Err(McpError::invalid_params(
+                        format!(
+                            "'{}' is not a configured log file (configured: {}; set RMCP_SENSORS_LOG_FILES to change)",
+                            path.display(),
+                            if files.is_empty() {
+                                "none".to_string()
+                            } else {
+                                files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ")
+                            }
+                        ),
+                        None,
+                    ));
+                }
+                vec![Source::File(path)]
+            }
+        };
+
+        let priority = match priority {
+            Some(value) => Some(logs::parse_priority(value).ok_or_else(|| {
+                McpError::invalid_params(format!("Unknown priority '{}'", value), None)
+            })?),
+            None => None,
+        };
+        let pattern = pattern
+            .map(regex::Regex::new)
+            .transpose()
+            .map_err(|e| McpError::invalid_params(format!("Invalid regex: {}", e), None))?;
+        let since_usec = minutes.map(|m| {
+            let window_usec = m.min(logs::MAX_WINDOW_MINUTES) as i64 * 60_000_000;
+            chrono::Utc::now().timestamp_micros().saturating_sub(window_usec)
+        });
+
+        let filter = LogFilter {
+            unit: unit.map(str::to_string),
+            priority,
+            since_usec,
+            pattern,
+            limit,
+        };
+        Ok((sources, filter))
+    }
+
+    /// One log line; `full` keeps long messages whole and labels every
+    /// priority, not just warnings and worse.
+    fn format_log_entry(entry: &LogEntry, full: bool) -> String {
+        const MAX_MESSAGE: usize = 500;
+
+        let mut line = match entry.timestamp_usec.and_then(chrono::DateTime::from_timestamp_micros) {
+            Some(time) => format!("{} ", time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")),
+            None => String::new(),
+        };
+        line.push_str(&entry.source);
+        if let Some(pid) = entry.pid {
+            line.push_str(&format!("[{}]", pid));
+        }
+        if let Some(priority) = entry.priority.filter(|p| full || *p <= 4) {
+            line.push_str(&format!(" <{}>", logs::priority_name(priority)));
+        }
+        line.push_str(": ");
+        match entry.message.char_indices().nth(MAX_MESSAGE).filter(|_| !full) {
+            Some((cut, _)) => {
+                line.push_str(&entry.message[..cut]);
+                line.push_str("...");
+            }
+            None => line.push_str(&entry.message),
+        }
+        line
+    }
+
+    /// Reads the log sources on a blocking thread and renders the entries.
+    async fn run_log_query(
+        title: String,
+        sources: Vec<Source>,
+        filter: LogFilter,
+        detail: Detail,
+    ) -> Result<String, McpError> {
+        let labels: Vec<String> = sources.iter().map(Source::label).collect();
+        let (entries, errors) = tokio::task::spawn_blocking(move || logs::collect(&sources, &filter))
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        if entries.is_empty() && errors.len() == labels.len() {
+            return Err(McpError::internal_error(
+                format!("No log source readable: {}", errors.join("; ")),
+                None,
+            ));
+        }
+
+        if detail == Detail::Summary {
+            let severe = entries.iter().filter(|e| e.priority.is_some_and(|p| p <= 3)).count();
+            let mut output = format!("{} line(s)", entries.len());
+            if severe > 0 {
+                output.push_str(&format!(", {} at err or worse", severe));
+            }
+            if let Some(last) = entries.last() {
+                output.push_str(&format!(", newest: {}", Self::format_log_entry(last, false)));
+            }
+            return Ok(output);
+        }
+
+        let mut output = format!("{} ({}):\n\n", title, labels.join(", "));
+        if entries.is_empty() {
+            output.push_str("No matching log lines.\n");
+        }
+        for entry in &entries {
+            output.push_str(&Self::format_log_entry(entry, detail == Detail::Full));
+            output.push('\n');
+        }
+        if !errors.is_empty() {
+            output.push_str(&format!("\nUnreadable: {}\n", errors.join("; ")));
+        }
+        Ok(output)
+    }
+
+    /// "Vcore: 1.032 V", with "(min 0.800 V, max 1.500 V)" when `thresholds`
+    /// is set, at the precision each kind of sensor warrants.
+    fn format_hwmon_reading(reading: &hwmon::Reading, thresholds: bool) -> String {
+        let decimals = match reading.kind {
+            hwmon::SensorKind::Fan => 0,
+            hwmon::SensorKind::Voltage | hwmon::SensorKind::Current => 3,
+            hwmon::SensorKind::Power => 2,
+            _ => 1,
+        };
+        let unit = reading.kind.unit();
+        let value = |v: f64| {
+            if unit.starts_with('°') || unit == "%" {
+                format!("{:.*}{}", decimals, v, unit)
+            } else {
+                format!("{:.*} {}", decimals, v, unit)
+            }
+        };
+        let mut line = format!("{}: {}", reading.label, value(reading.value));
+        if thresholds {
+            let limits: Vec<String> = [("min", reading.min), ("max", reading.max), ("crit", reading.crit)]
+                .into_iter()
+                .filter_map(|(name, limit)| Some(format!("{} {}", name, value(limit.filter(|l| *l != 0.0)?))))
+                .collect();
+            if !limits.is_empty() {
+                line.push_str(&format!(" ({})", limits.join(", ")));
+            }
+        }
+        if reading.alarm {
+            line.push_str(" ALARM");
+        }
+        line
+    }
+
+    fn battery_state_to_string(state: battery::State) -> &'static str {
+        match state {
+            battery::State::Charging => "Charging",
+            battery::State::Discharging => "Discharging",
+            battery::State::Empty => "Empty",
+            battery::State::Full => "Full",
             battery::State::Unknown => "Unknown",
             _ => "Unknown",
         }
@@ -308,9 +1260,24 @@ impl SensorsServer {
     // ========================================================================
 
     #[rmcp::tool(description = "Get display/monitor information (connected displays, resolutions, physical sizes)")]
-    pub async fn get_display_info(&self) -> Result<CallToolResult, McpError> {
+    pub async fn get_display_info(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
         let displays = DisplayInfo::all()
             .map_err(|e| McpError::internal_error(format!("Failed to get display info: {}", e), None))?;
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = displays
+            .iter()
+            .map(|d| (d.id.to_string(), Self::display_summary(d)))
+            .collect();
+
+        if detail == Detail::Summary {
+            let names: Vec<_> = displays.iter().map(Self::display_summary).collect();
+            let result = format!("Displays: {} ({})", displays.len(), names.join(", "));
+            return self.report_changes("get_display_info", "Display", since, snapshot, result);
+        }
 
         let mut result = String::from("Display Information:\n\n");
 
@@ -318,39 +1285,14 @@ impl SensorsServer {
             result.push_str("No displays detected.\n");
         } else {
             for (i, d) in displays.iter().enumerate() {
-                let primary = if d.is_primary { " (primary)" } else { "" };
-                result.push_str(&format!(
-                    "Display {}: {}{}\n",
-                    i + 1,
-                    if d.friendly_name.is_empty() { &d.name } else { &d.friendly_name },
-                    primary
-                ));
-                result.push_str(&format!("  Resolution: {}x{}\n", d.width, d.height));
-                result.push_str(&format!("  Position: ({}, {})\n", d.x, d.y));
-
-                if d.width_mm > 0 && d.height_mm > 0 {
-                    let diag_mm = ((d.width_mm.pow(2) + d.height_mm.pow(2)) as f32).sqrt();
-                    let diag_inches = diag_mm / 25.4;
-                    result.push_str(&format!(
-                        "  Physical: {}mm x {}mm (~{:.1}\")\n",
-                        d.width_mm, d.height_mm, diag_inches
-                    ));
-                }
-
-                if d.frequency > 0.0 {
-                    result.push_str(&format!("  Refresh: {:.0}Hz\n", d.frequency));
-                }
-
-                if d.scale_factor != 1.0 {
-                    result.push_str(&format!("  Scale: {:.0}%\n", d.scale_factor * 100.0));
-                }
-
+                result.push_str(&format!("Display {}: ", i + 1));
+                result.push_str(&Self::format_single_display(d, detail));
                 result.push('\n');
             }
             result.push_str(&format!("Total displays: {}\n", displays.len()));
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        self.report_changes("get_display_info", "Display", since, snapshot, result)
     }
 
     #[rmcp::tool(description = "Get display info at specific screen coordinates (useful for determining which monitor contains a point)")]
@@ -361,7 +1303,7 @@ impl SensorsServer {
         let display = DisplayInfo::from_point(params.x, params.y)
             .map_err(|e| McpError::internal_error(format!("Failed to get display at ({}, {}): {}", params.x, params.y, e), None))?;
 
-        let formatted = Self::format_single_display(&display);
+        let formatted = Self::format_single_display(&display, params.detail.unwrap_or_default());
         let result = format!("Display at ({}, {}):\n{}", params.x, params.y, formatted);
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
@@ -375,7 +1317,7 @@ impl SensorsServer {
         let display = DisplayInfo::from_name(&params.name)
             .map_err(|e| McpError::internal_error(format!("Failed to get display '{}': {}", params.name, e), None))?;
 
-        let formatted = Self::format_single_display(&display);
+        let formatted = Self::format_single_display(&display, params.detail.unwrap_or_default());
 
         Ok(CallToolResult::success(vec![Content::text(formatted)]))
     }
@@ -385,17 +1327,31 @@ impl SensorsServer {
     // ========================================================================
 
     #[rmcp::tool(description = "Get user idle time (how long since last keyboard/mouse input)")]
-    pub async fn get_idle_time(&self) -> Result<CallToolResult, McpError> {
+    pub async fn get_idle_time(
+        &self,
+        Parameters(params): Parameters<DetailParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Self::ensure_x11_display()?;
         let idle = UserIdle::get_time()
             .map_err(|e| McpError::internal_error(format!("Failed to get idle time: {}", e), None))?;
 
         let seconds = idle.as_seconds();
         let formatted = Self::format_duration(seconds);
 
-        let result = format!(
-            "User Idle Time:\n\n  Raw: {} seconds\n  Formatted: {}\n",
-            seconds, formatted
-        );
+        let result = match params.detail.unwrap_or_default() {
+            Detail::Summary => format!("Idle for {}", formatted),
+            Detail::Normal => format!(
+                "User Idle Time:\n\n  Raw: {} seconds\n  Formatted: {}\n",
+                seconds, formatted
+            ),
+            Detail::Full => format!(
+                "User Idle Time:\n\n  Raw: {} seconds ({} ms)\n  Formatted: {}\n  Display: {}\n",
+                seconds,
+                idle.as_milliseconds(),
+                formatted,
+                std::env::var("DISPLAY").unwrap_or_else(|_| "(default)".to_string())
+            ),
+        };
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
@@ -405,6 +1361,7 @@ impl SensorsServer {
         &self,
         Parameters(params): Parameters<IdleThresholdParams>,
     ) -> Result<CallToolResult, McpError> {
+        Self::ensure_x11_display()?;
         let idle = UserIdle::get_time()
             .map_err(|e| McpError::internal_error(format!("Failed to get idle time: {}", e), None))?;
 
@@ -412,14 +1369,23 @@ impl SensorsServer {
         let threshold = params.threshold_seconds;
         let is_idle = seconds >= threshold;
 
-        let result = format!(
-            "Idle Check:\n\n  Current idle: {} ({})\n  Threshold: {} ({})\n  Is idle: {}\n",
-            seconds,
-            Self::format_duration(seconds),
-            threshold,
-            Self::format_duration(threshold),
-            if is_idle { "YES" } else { "NO" }
-        );
+        let result = if params.detail == Some(Detail::Summary) {
+            format!(
+                "Idle: {} ({} vs threshold {})",
+                if is_idle { "YES" } else { "NO" },
+                Self::format_duration(seconds),
+                Self::format_duration(threshold)
+            )
+        } else {
+            format!(
+                "Idle Check:\n\n  Current idle: {} ({})\n  Threshold: {} ({})\n  Is idle: {}\n",
+                seconds,
+                Self::format_duration(seconds),
+                threshold,
+                Self::format_duration(threshold),
+                if is_idle { "YES" } else { "NO" }
+            )
+        };
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
@@ -429,9 +1395,46 @@ impl SensorsServer {
     // ========================================================================
 
     #[rmcp::tool(description = "List all network interfaces with their IP addresses and MAC addresses")]
-    pub async fn get_interfaces(&self) -> Result<CallToolResult, McpError> {
+    pub async fn get_interfaces(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
         let interfaces = NetworkInterface::show()
             .map_err(|e| McpError::internal_error(format!("Failed to get network interfaces: {}", e), None))?;
+        let detail = params.detail.unwrap_or_default();
+        let full = detail == Detail::Full;
+        let since = params.since.as_deref();
+
+        let mut snapshot = Snapshot::new();
+        for iface in &interfaces {
+            snapshot.insert(iface.name.clone(), String::new());
+            for addr in &iface.addr {
+                let ip = match addr {
+                    Addr::V4(v4) => v4.ip.to_string(),
+                    Addr::V6(v6) => v6.ip.to_string(),
+                };
+                snapshot.insert(format!("{} {}", iface.name, ip), String::new());
+            }
+        }
+
+        if detail == Detail::Summary {
+            let addressed: Vec<_> = interfaces
+                .iter()
+                .filter_map(|i| {
+                    i.addr.iter().find_map(|a| match a {
+                        Addr::V4(v4) => Some(format!("{} {}", i.name, v4.ip)),
+                        Addr::V6(_) => None,
+                    })
+                })
+                .collect();
+            let result = format!(
+                "{} interfaces, {} with addresses: {}",
+                interfaces.len(),
+                interfaces.iter().filter(|i| !i.addr.is_empty()).count(),
+                addressed.join(", ")
+            );
+            return self.report_changes("get_interfaces", "Network interface", since, snapshot, result);
+        }
 
         let mut result = String::from("Network Interfaces:\n\n");
 
@@ -444,14 +1447,18 @@ impl SensorsServer {
                     Addr::V6(v6) => v6.ip.is_loopback(),
                 });
 
-                result.push_str(&format!("{}", iface.name));
+                result.push_str(&iface.name);
                 if is_loopback {
                     result.push_str(" (loopback)");
                 }
                 result.push('\n');
 
+                if full {
+                    result.push_str(&format!("  Index: {}\n", iface.index));
+                }
+
                 if let Some(ref mac) = iface.mac_addr {
-                    if !mac.is_empty() && mac != "00:00:00:00:00:00" {
+                    if full || (!mac.is_empty() && mac != "00:00:00:00:00:00") {
                         result.push_str(&format!("  MAC: {}\n", mac));
                     }
                 }
@@ -463,10 +1470,19 @@ impl SensorsServer {
                             if let Some(netmask) = &v4.netmask {
                                 result.push_str(&format!(" / {}", netmask));
                             }
+                            if let (true, Some(broadcast)) = (full, &v4.broadcast) {
+                                result.push_str(&format!(" (broadcast {})", broadcast));
+                            }
                             result.push('\n');
                         }
                         Addr::V6(v6) => {
-                            if !v6.ip.to_string().starts_with("fe80") {
+                            if full {
+                                result.push_str(&format!("  IPv6: {}", v6.ip));
+                                if let Some(netmask) = &v6.netmask {
+                                    result.push_str(&format!(" / {}", netmask));
+                                }
+                                result.push('\n');
+                            } else if !v6.ip.to_string().starts_with("fe80") {
                                 result.push_str(&format!("  IPv6: {}\n", v6.ip));
                             }
                         }
@@ -483,7 +1499,143 @@ impl SensorsServer {
             ));
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        self.report_changes("get_interfaces", "Network interface", since, snapshot, result)
+    }
+
+    #[rmcp::tool(description = "List listening TCP/UDP ports (and optionally unix sockets) with the owning \
+        process, e.g. to answer \"what's on port 8080?\"")]
+    pub async fn get_listening_ports(
+        &self,
+        Parameters(params): Parameters<ListeningPortsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let protocols = Self::socket_protocols(params.protocol.as_deref())?;
+        let mut sockets: Vec<_> = sockets::read_sockets(std::path::Path::new(sockets::PROC_ROOT), &protocols)
+            .into_iter()
+            .filter(|s| s.listening && (params.port.is_none() || s.local_port == params.port))
+            .collect();
+        sockets.sort_by(|a, b| (a.local_port, a.protocol.name(), &a.local).cmp(&(b.local_port, b.protocol.name(), &b.local)));
+
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = sockets
+            .iter()
+            .map(|s| {
+                let owner = s.process.as_deref().map(|p| format!(" ({})", p)).unwrap_or_default();
+                (
+                    format!("{} {}", s.protocol.name(), s.local),
+                    format!("{} {}{}", s.protocol.name(), s.local, owner),
+                )
+            })
+            .collect();
+
+        if detail == Detail::Summary {
+            let ports: Vec<_> = sockets
+                .iter()
+                .filter_map(|s| {
+                    let port = s.local_port?;
+                    Some(match &s.process {
+                        Some(name) => format!("{} {} ({})", s.protocol.name(), port, name),
+                        None => format!("{} {}", s.protocol.name(), port),
+                    })
+                })
+                .collect();
+            let output = format!("{} listening: {}", sockets.len(), ports.join(", "));
+            return self.report_changes("get_listening_ports", "Listening socket", since, snapshot, output);
+        }
+
+        let mut output = String::from("Listening Ports:\n\n");
+        if sockets.is_empty() {
+            output.push_str("No listening sockets found.\n");
+        } else {
+            output.push_str(&format!("{:<6} {:<40} {:<8} {}\n", "Proto", "Local Address", "PID", "Process"));
+            output.push_str(&format!("{:-<70}\n", ""));
+            for s in &sockets {
+                output.push_str(&format!("{:<6} {:<40} {}", s.protocol.name(), s.local, Self::socket_owner(s)));
+                if detail == Detail::Full {
+                    output.push_str(&format!(
+                        "  (uid {}, inode {})",
+                        s.uid.map(|u| u.to_string()).unwrap_or_else(|| "-".to_string()),
+                        s.inode
+                    ));
+                }
+                output.push('\n');
+            }
+            output.push_str(&format!("\nTotal: {} listening sockets\n", sockets.len()));
+            output.push_str(&Self::unresolved_owners_note(&sockets.iter().collect::<Vec<_>>()));
+        }
+
+        self.report_changes("get_listening_ports", "Listening socket", since, snapshot, output)
+    }
+
+    #[rmcp::tool(description = "List open sockets (TCP/UDP/unix) with local and remote address, state and \
+        owning process; filter by protocol, state, port or PID")]
+    pub async fn get_connections(
+        &self,
+        Parameters(params): Parameters<ConnectionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let protocols = Self::socket_protocols(params.protocol.as_deref())?;
+        let state = params.state.as_deref().map(str::to_uppercase);
+        let sockets: Vec<_> = sockets::read_sockets(std::path::Path::new(sockets::PROC_ROOT), &protocols)
+            .into_iter()
+            .filter(|s| state.as_deref().is_none_or(|state| s.state == state))
+            .filter(|s| params.port.is_none() || s.local_port == params.port || s.remote_port == params.port)
+            .filter(|s| params.pid.is_none() || s.pid == params.pid)
+            .collect();
+        let detail = params.detail.unwrap_or_default();
+
+        let mut by_state: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+        for s in &sockets {
+            *by_state.entry(s.state).or_default() += 1;
+        }
+        let states: Vec<_> = by_state.iter().map(|(state, n)| format!("{} {}", n, state)).collect();
+
+        if detail == Detail::Summary {
+            let output = format!("{} sockets: {}", sockets.len(), states.join(", "));
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let limit = match (params.limit, detail) {
+            (Some(limit), _) => limit,
+            (None, Detail::Full) => usize::MAX,
+            (None, _) => 100,
+        };
+
+        let mut output = String::from("Connections:\n\n");
+        if sockets.is_empty() {
+            output.push_str("No matching sockets.\n");
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        output.push_str(&format!(
+            "{:<6} {:<40} {:<40} {:<12} {:<8} {}\n",
+            "Proto", "Local Address", "Remote Address", "State", "PID", "Process"
+        ));
+        output.push_str(&format!("{:-<120}\n", ""));
+        for s in sockets.iter().take(limit) {
+            output.push_str(&format!(
+                "{:<6} {:<40} {:<40} {:<12} {}",
+                s.protocol.name(),
+                s.local,
+                s.remote.as_deref().unwrap_or("-"),
+                s.state,
+                Self::socket_owner(s)
+            ));
+            if detail == Detail::Full {
+                output.push_str(&format!(
+                    "  (uid {}, inode {})",
+                    s.uid.map(|u| u.to_string()).unwrap_or_else(|| "-".to_string()),
+                    s.inode
+                ));
+            }
+            output.push('\n');
+        }
+        if sockets.len() > limit {
+            output.push_str(&format!("\n... and {} more sockets\n", sockets.len() - limit));
+        }
+        output.push_str(&format!("\nTotal: {} ({})\n", sockets.len(), states.join(", ")));
+        output.push_str(&Self::unresolved_owners_note(&sockets.iter().collect::<Vec<_>>()));
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     // ========================================================================
@@ -491,14 +1643,41 @@ impl SensorsServer {
     // ========================================================================
 
     #[rmcp::tool(description = "List all connected USB devices with vendor/product info")]
-    pub async fn get_usb_devices(&self) -> Result<CallToolResult, McpError> {
-        let devices = list_devices()
-            .map_err(|e| McpError::internal_error(format!("Failed to list USB devices: {}", e), None))?;
+    pub async fn get_usb_devices(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let devices: Vec<_> = list_devices()
+            .map_err(|e| McpError::internal_error(format!("Failed to list USB devices: {}", e), None))?
+            .collect();
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = devices
+            .iter()
+            .map(|d| {
+                let ids = format!("{:04x}:{:04x}", d.vendor_id(), d.product_id());
+                let name = match d.product_string() {
+                    Some(product) if !product.is_empty() => format!("{} ({})", product, ids),
+                    _ => format!("Device {}", ids),
+                };
+                (format!("{}-{} {}", d.bus_number(), d.device_address(), ids), name)
+            })
+            .collect();
+
+        if detail == Detail::Summary {
+            let names: Vec<_> = devices
+                .iter()
+                .filter_map(|d| d.product_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            let result = format!("{} USB devices: {}", devices.len(), names.join(", "));
+            return self.report_changes("get_usb_devices", "USB device", since, snapshot, result);
+        }
 
         let mut result = String::from("USB Devices:\n\n");
         let mut count = 0;
 
-        for device in devices {
+        for device in &devices {
             count += 1;
 
             let manufacturer = device.manufacturer_string().unwrap_or_default();
@@ -528,10 +1707,39 @@ impl SensorsServer {
             }
 
             result.push_str(&format!(
-                "   Bus: {}, Device: {}\n\n",
+                "   Bus: {}, Device: {}\n",
                 device.bus_number(),
                 device.device_address()
             ));
+
+            if detail == Detail::Full {
+                result.push_str(&format!(
+                    "   Class: {:02x}/{:02x}/{:02x}, Version: {:x}.{:02x}\n",
+                    device.class(),
+                    device.subclass(),
+                    device.protocol(),
+                    device.device_version() >> 8,
+                    device.device_version() & 0xff
+                ));
+                if let Some(speed) = device.speed() {
+                    result.push_str(&format!("   Speed: {:?}\n", speed));
+                }
+                for iface in device.interfaces() {
+                    result.push_str(&format!(
+                        "   Interface {}: class {:02x}/{:02x}/{:02x}",
+                        iface.interface_number(),
+                        iface.class(),
+                        iface.subclass(),
+                        iface.protocol()
+                    ));
+                    if let Some(name) = iface.interface_string() {
+                        result.push_str(&format!(" ({})", name));
+                    }
+                    result.push('\n');
+                }
+            }
+
+            result.push('\n');
         }
 
         if count == 0 {
@@ -540,7 +1748,7 @@ impl SensorsServer {
             result.push_str(&format!("Total: {} USB devices\n", count));
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        self.report_changes("get_usb_devices", "USB device", since, snapshot, result)
     }
 
     // ========================================================================
@@ -548,7 +1756,10 @@ impl SensorsServer {
     // ========================================================================
 
     #[rmcp::tool(description = "Get battery/power status (charge level, charging state, time remaining)")]
-    pub async fn get_battery_status(&self) -> Result<CallToolResult, McpError> {
+    pub async fn get_battery_status(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
         let manager = battery::Manager::new()
             .map_err(|e| McpError::internal_error(format!("Failed to create battery manager: {}", e), None))?;
 
@@ -557,6 +1768,37 @@ impl SensorsServer {
             .map_err(|e| McpError::internal_error(format!("Failed to get batteries: {}", e), None))?
             .filter_map(|b| b.ok())
             .collect();
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = batteries
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                (
+                    format!("Battery {}", i + 1),
+                    format!("Battery {}: {}", i + 1, Self::battery_state_to_string(b.state())),
+                )
+            })
+            .collect();
+
+        if detail == Detail::Summary {
+            let result = if batteries.is_empty() {
+                "No batteries detected".to_string()
+            } else {
+                batteries
+                    .iter()
+                    .map(|b| {
+                        format!(
+                            "{:.0}% {}",
+                            b.state_of_charge().get::<battery::units::ratio::percent>(),
+                            Self::battery_state_to_string(b.state())
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            return self.report_changes("get_battery_status", "Battery", since, snapshot, result);
+        }
 
         let mut result = String::from("Battery Status:\n\n");
 
@@ -602,12 +1844,42 @@ impl SensorsServer {
                     result.push_str(&format!("  Temperature: {:.1}°C\n", celsius));
                 }
 
+                if detail == Detail::Full {
+                    if let Some(vendor) = battery.vendor() {
+                        result.push_str(&format!("  Vendor: {}\n", vendor.trim()));
+                    }
+                    if let Some(model) = battery.model() {
+                        result.push_str(&format!("  Model: {}\n", model.trim()));
+                    }
+                    if let Some(serial) = battery.serial_number() {
+                        result.push_str(&format!("  Serial: {}\n", serial.trim()));
+                    }
+                    result.push_str(&format!("  Technology: {}\n", battery.technology()));
+                    if let Some(cycles) = battery.cycle_count() {
+                        result.push_str(&format!("  Cycle count: {}\n", cycles));
+                    }
+                    result.push_str(&format!(
+                        "  Voltage: {:.2} V\n",
+                        battery.voltage().get::<battery::units::electric_potential::volt>()
+                    ));
+                    result.push_str(&format!(
+                        "  Power draw: {:.1} W\n",
+                        battery.energy_rate().get::<battery::units::power::watt>()
+                    ));
+                    result.push_str(&format!(
+                        "  Design capacity: {:.1} Wh\n",
+                        battery
+                            .energy_full_design()
+                            .get::<battery::units::energy::watt_hour>()
+                    ));
+                }
+
                 result.push('\n');
             }
             result.push_str(&format!("Total batteries: {}\n", batteries.len()));
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        self.report_changes("get_battery_status", "Battery", since, snapshot, result)
     }
 
     // ========================================================================
@@ -615,7 +1887,13 @@ impl SensorsServer {
     // ========================================================================
 
     #[rmcp::tool(description = "Scan for nearby Bluetooth Low Energy (BLE) devices")]
-    pub async fn scan_ble_devices(&self) -> Result<CallToolResult, McpError> {
+    pub async fn scan_ble_devices(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let mut snapshot = Snapshot::new();
         let manager = BluetoothManager::new()
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to create BT manager: {}", e), None))?;
@@ -632,6 +1910,7 @@ impl SensorsServer {
         }
 
         let mut result = String::from("Bluetooth Devices:\n\n");
+        let mut seen = Vec::new();
 
         for adapter in adapters {
             let adapter_info = adapter
@@ -680,12 +1959,42 @@ impl SensorsServer {
 
                     result.push_str(&format!("  {}. {}{}\n", count, name, rssi));
                     result.push_str(&format!("     Address: {}\n", address));
+
+                    if let (Detail::Full, Some(props)) = (detail, &properties) {
+                        if let Some(address_type) = props.address_type {
+                            result.push_str(&format!("     Address type: {:?}\n", address_type));
+                        }
+                        if let Some(tx_power) = props.tx_power_level {
+                            result.push_str(&format!("     TX power: {}dBm\n", tx_power));
+                        }
+                        if let Some(class) = props.class {
+                            result.push_str(&format!("     Class: 0x{:06x}\n", class));
+                        }
+                        for (company, data) in &props.manufacturer_data {
+                            result.push_str(&format!(
+                                "     Manufacturer data: 0x{:04x} ({} bytes)\n",
+                                company,
+                                data.len()
+                            ));
+                        }
+                        for service in &props.services {
+                            result.push_str(&format!("     Service: {}\n", service));
+                        }
+                    }
+
+                    snapshot.insert(address.clone(), format!("{} ({})", name, address));
+                    seen.push(name);
                 }
                 result.push_str(&format!("\n  Total: {} BLE devices\n", count));
             }
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        if detail == Detail::Summary {
+            let named: Vec<_> = seen.iter().filter(|n| *n != "Unknown").map(String::as_str).collect();
+            result = format!("{} BLE devices nearby: {}", seen.len(), named.join(", "));
+        }
+
+        self.report_changes("scan_ble_devices", "Bluetooth device", since, snapshot, result)
     }
 
     // ========================================================================
@@ -695,9 +2004,14 @@ impl SensorsServer {
     #[rmcp::tool(description = "Get git repository status (branch, uncommitted changes, last commit)")]
     pub async fn get_status(
         &self,
-        Parameters(params): Parameters<RepoPathParams>,
+        Parameters(params): Parameters<RepoChangeParams>,
     ) -> Result<CallToolResult, McpError> {
         let repo = Self::get_repo(params.path)?;
+        let detail = params.detail.unwrap_or_default();
+        let list_limit = if detail == Detail::Full { usize::MAX } else { 5 };
+        let since = params.since.as_deref();
+        let scope = format!("get_status:{}", repo.path().display());
+        let mut snapshot = Snapshot::new();
         let mut result = String::from("Git Repository Status:\n\n");
 
         if let Some(workdir) = repo.workdir() {
@@ -726,6 +2040,12 @@ impl SensorsServer {
                         commit.author().name().unwrap_or("unknown")
                     ));
                     result.push_str(&format!("  Date: {}\n", timestamp));
+                    if detail == Detail::Full {
+                        result.push_str(&format!("  Commit: {}\n", id));
+                        if let Some(email) = commit.author().email() {
+                            result.push_str(&format!("  Email: {}\n", email));
+                        }
+                    }
                 }
             }
             Err(_) => {
@@ -757,6 +2077,40 @@ impl SensorsServer {
                     if status.is_wt_new() {
                         untracked.push(path.to_string());
                     }
+
+                    let mut states = Vec::new();
+                    if status.is_index_new() || status.is_index_modified() || status.is_index_deleted() {
+                        states.push("staged");
+                    }
+                    if status.is_wt_modified() || status.is_wt_deleted() {
+                        states.push("modified");
+                    }
+                    if status.is_wt_new() {
+                        states.push("untracked");
+                    }
+                    if !states.is_empty() {
+                        snapshot.insert(path.to_string(), format!("{} ({})", path, states.join(", ")));
+                    }
+                }
+
+                if detail == Detail::Summary {
+                    let branch = repo
+                        .head()
+                        .ok()
+                        .and_then(|h| h.shorthand().map(String::from))
+                        .unwrap_or_else(|| "(no commits yet)".to_string());
+                    let summary = if staged.is_empty() && modified.is_empty() && untracked.is_empty() {
+                        format!("{}: clean", branch)
+                    } else {
+                        format!(
+                            "{}: {} staged, {} modified, {} untracked",
+                            branch,
+                            staged.len(),
+                            modified.len(),
+                            untracked.len()
+                        )
+                    };
+                    return self.report_changes(&scope, "Working tree", since, snapshot, summary);
                 }
 
                 result.push_str("\nWorking Tree:\n");
@@ -766,29 +2120,29 @@ impl SensorsServer {
                 } else {
                     if !staged.is_empty() {
                         result.push_str(&format!("  Staged: {} file(s)\n", staged.len()));
-                        for f in staged.iter().take(5) {
+                        for f in staged.iter().take(list_limit) {
                             result.push_str(&format!("    + {}\n", f));
                         }
-                        if staged.len() > 5 {
-                            result.push_str(&format!("    ... and {} more\n", staged.len() - 5));
+                        if staged.len() > list_limit {
+                            result.push_str(&format!("    ... and {} more\n", staged.len() - list_limit));
                         }
                     }
                     if !modified.is_empty() {
                         result.push_str(&format!("  Modified: {} file(s)\n", modified.len()));
-                        for f in modified.iter().take(5) {
+                        for f in modified.iter().take(list_limit) {
                             result.push_str(&format!("    M {}\n", f));
                         }
-                        if modified.len() > 5 {
-                            result.push_str(&format!("    ... and {} more\n", modified.len() - 5));
+                        if modified.len() > list_limit {
+                            result.push_str(&format!("    ... and {} more\n", modified.len() - list_limit));
                         }
                     }
                     if !untracked.is_empty() {
                         result.push_str(&format!("  Untracked: {} file(s)\n", untracked.len()));
-                        for f in untracked.iter().take(5) {
+                        for f in untracked.iter().take(list_limit) {
                             result.push_str(&format!("    ? {}\n", f));
                         }
-                        if untracked.len() > 5 {
-                            result.push_str(&format!("    ... and {} more\n", untracked.len() - 5));
+                        if untracked.len() > list_limit {
+                            result.push_str(&format!("    ... and {} more\n", untracked.len() - list_limit));
                         }
                     }
                 }
@@ -798,7 +2152,7 @@ impl SensorsServer {
             }
         }
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        self.report_changes(&scope, "Working tree", since, snapshot, result)
     }
 
     #[rmcp::tool(description = "Get recent git commits (last 10)")]
@@ -807,6 +2161,7 @@ impl SensorsServer {
         Parameters(params): Parameters<RepoPathParams>,
     ) -> Result<CallToolResult, McpError> {
         let repo = Self::get_repo(params.path)?;
+        let detail = params.detail.unwrap_or_default();
         let mut result = String::from("Recent Commits:\n\n");
 
         let head = repo
@@ -826,23 +2181,44 @@ impl SensorsServer {
             .map_err(|e| McpError::internal_error(format!("Failed to push HEAD: {}", e), None))?;
 
         let mut count = 0;
-        for oid in revwalk.take(10) {
-            if let Ok(oid) = oid {
-                if let Ok(commit) = repo.find_commit(oid) {
-                    count += 1;
-                    let id_str = oid.to_string();
-                    let short_id = &id_str[..7];
-                    let summary = commit.summary().unwrap_or("(no message)").to_string();
-                    let author = commit.author();
-                    let author_name = author.name().unwrap_or("unknown");
+        for oid in revwalk.take(10).flatten() {
+            if let Ok(commit) = repo.find_commit(oid) {
+                count += 1;
+                let id_str = oid.to_string();
+                let short_id = &id_str[..7];
+                let summary = commit.summary().unwrap_or("(no message)").to_string();
+                let author = commit.author();
+                let author_name = author.name().unwrap_or("unknown");
+
+                if detail == Detail::Summary {
+                    if count == 1 {
+                        result = format!("Latest: {} {} - {}", short_id, author_name, summary);
+                    }
+                    continue;
+                }
+
+                result.push_str(&format!("{} {} - {}\n", short_id, author_name, summary));
 
-                    result.push_str(&format!("{} {} - {}\n", short_id, author_name, summary));
+                if detail == Detail::Full {
+                    let timestamp = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    result.push_str(&format!("  Date: {}\n", timestamp));
+                    if let Some(email) = author.email() {
+                        result.push_str(&format!("  Email: {}\n", email));
+                    }
+                    if let Some(body) = commit.body() {
+                        for line in body.lines() {
+                            result.push_str(&format!("  {}\n", line));
+                        }
+                    }
+                    result.push('\n');
                 }
             }
         }
 
         if count == 0 {
-            result.push_str("No commits found.\n");
+            result = String::from("Recent Commits:\n\nNo commits found.\n");
         }
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
@@ -861,21 +2237,41 @@ impl SensorsServer {
         let branch_name = head.shorthand().unwrap_or("(detached)");
         let is_detached = repo.head_detached().unwrap_or(false);
 
-        let result = if is_detached {
+        let mut result = if is_detached {
             format!("Current branch: {} (detached HEAD)", branch_name)
         } else {
             format!("Current branch: {}", branch_name)
         };
 
+        if params.detail == Some(Detail::Full) && !is_detached {
+            let upstream = repo
+                .find_branch(branch_name, BranchType::Local)
+                .and_then(|b| b.upstream());
+            if let Ok(upstream) = upstream {
+                if let Ok(Some(name)) = upstream.name() {
+                    result.push_str(&format!("\nUpstream: {}", name));
+                }
+                if let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) {
+                    if let Ok((ahead, behind)) = repo.graph_ahead_behind(local, remote) {
+                        result.push_str(&format!("\nAhead: {}, Behind: {}", ahead, behind));
+                    }
+                }
+            }
+        }
+
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
     #[rmcp::tool(description = "List all branches (local and remote)")]
     pub async fn get_branches(
         &self,
-        Parameters(params): Parameters<RepoPathParams>,
+        Parameters(params): Parameters<RepoChangeParams>,
     ) -> Result<CallToolResult, McpError> {
         let repo = Self::get_repo(params.path)?;
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let scope = format!("get_branches:{}", repo.path().display());
+        let mut snapshot = Snapshot::new();
         let mut result = String::from("Branches:\n\n");
 
         let current = repo.head().ok().and_then(|h| h.shorthand().map(String::from));
@@ -885,13 +2281,24 @@ impl SensorsServer {
             .map_err(|e| McpError::internal_error(format!("Failed to list branches: {}", e), None))?;
 
         let mut local_count = 0;
-        for branch in local_branches {
-            if let Ok((branch, _)) = branch {
-                if let Ok(Some(name)) = branch.name() {
-                    local_count += 1;
-                    let marker = if Some(name.to_string()) == current { "* " } else { "  " };
-                    result.push_str(&format!("{}{}\n", marker, name));
+        for (branch, _) in local_branches.flatten() {
+            if let Ok(Some(name)) = branch.name() {
+                local_count += 1;
+                if let Some(target) = branch.get().target() {
+                    snapshot.insert(name.to_string(), format!("{} at {}", name, &target.to_string()[..7]));
+                }
+                let marker = if Some(name.to_string()) == current { "* " } else { "  " };
+                result.push_str(&format!("{}{}", marker, name));
+                if detail == Detail::Full {
+                    if let Ok(commit) = branch.get().peel_to_commit() {
+                        result.push_str(&format!(
+                            " {} {}",
+                            &commit.id().to_string()[..7],
+                            commit.summary().unwrap_or("(no message)")
+                        ));
+                    }
                 }
+                result.push('\n');
             }
         }
         if local_count == 0 {
@@ -903,21 +2310,32 @@ impl SensorsServer {
             .map_err(|e| McpError::internal_error(format!("Failed to list remote branches: {}", e), None))?;
 
         let mut remote_count = 0;
-        for branch in remote_branches {
-            if let Ok((branch, _)) = branch {
-                if let Ok(Some(name)) = branch.name() {
-                    remote_count += 1;
-                    result.push_str(&format!("  {}\n", name));
+        for (branch, _) in remote_branches.flatten() {
+            if let Ok(Some(name)) = branch.name() {
+                remote_count += 1;
+                if let Some(target) = branch.get().target() {
+                    snapshot.insert(name.to_string(), format!("{} at {}", name, &target.to_string()[..7]));
                 }
+                result.push_str(&format!("  {}\n", name));
             }
         }
         if remote_count == 0 {
             result.push_str("  (none)\n");
         }
 
+        if detail == Detail::Summary {
+            result = format!(
+                "{} local, {} remote branches (current: {})",
+                local_count,
+                remote_count,
+                current.as_deref().unwrap_or("none")
+            );
+            return self.report_changes(&scope, "Branch", since, snapshot, result);
+        }
+
         result.push_str(&format!("\nTotal: {} local, {} remote\n", local_count, remote_count));
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        self.report_changes(&scope, "Branch", since, snapshot, result)
     }
 
     #[rmcp::tool(description = "List all remotes with their URLs")]
@@ -931,7 +2349,10 @@ impl SensorsServer {
         let remotes = repo.remotes()
             .map_err(|e| McpError::internal_error(format!("Failed to list remotes: {}", e), None))?;
 
-        if remotes.is_empty() {
+        if params.detail == Some(Detail::Summary) {
+            let names: Vec<_> = remotes.iter().flatten().collect();
+            result = format!("{} remotes: {}", names.len(), names.join(", "));
+        } else if remotes.is_empty() {
             result.push_str("No remotes configured.\n");
         } else {
             for name in remotes.iter().flatten() {
@@ -943,6 +2364,13 @@ impl SensorsServer {
                     if let Some(url) = remote.pushurl().or(remote.url()) {
                         result.push_str(&format!("  Push:  {}\n", url));
                     }
+                    if params.detail == Some(Detail::Full) {
+                        if let Ok(refspecs) = remote.fetch_refspecs() {
+                            for refspec in refspecs.iter().flatten() {
+                                result.push_str(&format!("  Refspec: {}\n", refspec));
+                            }
+                        }
+                    }
                 }
                 result.push('\n');
             }
@@ -962,13 +2390,29 @@ impl SensorsServer {
         let tags = repo.tag_names(None)
             .map_err(|e| McpError::internal_error(format!("Failed to list tags: {}", e), None))?;
 
-        if tags.is_empty() {
+        if params.detail == Some(Detail::Summary) {
+            let latest = tags.iter().flatten().last().unwrap_or("none");
+            result = format!("{} tags (last: {})", tags.len(), latest);
+        } else if tags.is_empty() {
             result.push_str("No tags found.\n");
         } else {
             for tag in tags.iter().flatten() {
-                result.push_str(&format!("  {}\n", tag));
-            }
-            result.push_str(&format!("\nTotal: {} tags\n", tags.len()));
+                result.push_str(&format!("  {}", tag));
+                if params.detail == Some(Detail::Full) {
+                    let target = repo
+                        .revparse_single(&format!("refs/tags/{}", tag))
+                        .and_then(|obj| obj.peel_to_commit());
+                    if let Ok(commit) = target {
+                        result.push_str(&format!(
+                            " -> {} {}",
+                            &commit.id().to_string()[..7],
+                            commit.summary().unwrap_or("(no message)")
+                        ));
+                    }
+                }
+                result.push('\n');
+            }
+            result.push_str(&format!("\nTotal: {} tags\n", tags.len()));
         }
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
@@ -983,16 +2427,26 @@ impl SensorsServer {
         let mut result = String::from("Stash List:\n\n");
 
         let mut stashes = Vec::new();
-        repo.stash_foreach(|index, message, _oid| {
-            stashes.push((index, message.to_string()));
+        repo.stash_foreach(|index, message, oid| {
+            stashes.push((index, message.to_string(), *oid));
             true
         }).map_err(|e| McpError::internal_error(format!("Failed to list stashes: {}", e), None))?;
 
-        if stashes.is_empty() {
+        if params.detail == Some(Detail::Summary) {
+            result = format!("{} stash entries", stashes.len());
+        } else if stashes.is_empty() {
             result.push_str("No stashed changes.\n");
         } else {
-            for (index, message) in &stashes {
+            for (index, message, oid) in &stashes {
                 result.push_str(&format!("stash@{{{}}}: {}\n", index, message));
+                if params.detail == Some(Detail::Full) {
+                    if let Ok(commit) = repo.find_commit(*oid) {
+                        let timestamp = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_else(|| "unknown".to_string());
+                        result.push_str(&format!("  {} {}\n", &oid.to_string()[..7], timestamp));
+                    }
+                }
             }
             result.push_str(&format!("\nTotal: {} stash entries\n", stashes.len()));
         }
@@ -1034,6 +2488,12 @@ impl SensorsServer {
 
         let staged_total = staged_new + staged_modified + staged_deleted;
         let unstaged_total = unstaged_modified + unstaged_deleted;
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            result = format!("{} staged, {} unstaged, {} untracked", staged_total, unstaged_total, untracked);
+            return Ok(CallToolResult::success(vec![Content::text(result)]));
+        }
 
         result.push_str("Staged for commit:\n");
         if staged_total == 0 {
@@ -1059,36 +2519,218 @@ impl SensorsServer {
             result.push_str(&format!("  {} file(s)\n", untracked));
         }
 
+        if detail == Detail::Full {
+            let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            let staged_stats = repo
+                .diff_tree_to_index(head_tree.as_ref(), None, None)
+                .and_then(|d| d.stats());
+            let unstaged_stats = repo.diff_index_to_workdir(None, None).and_then(|d| d.stats());
+
+            result.push_str("\nLine changes:\n");
+            if let Ok(stats) = staged_stats {
+                result.push_str(&format!(
+                    "  Staged: +{} -{} in {} file(s)\n",
+                    stats.insertions(),
+                    stats.deletions(),
+                    stats.files_changed()
+                ));
+            }
+            if let Ok(stats) = unstaged_stats {
+                result.push_str(&format!(
+                    "  Not staged: +{} -{} in {} file(s)\n",
+                    stats.insertions(),
+                    stats.deletions(),
+                    stats.files_changed()
+                ));
+            }
+        }
+
         result.push_str(&format!("\nSummary: {} staged, {} unstaged, {} untracked\n",
             staged_total, unstaged_total, untracked));
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    #[rmcp::tool(description = "Detect the project type in a directory (Cargo.toml, package.json, \
+        pyproject.toml, go.mod...), its workspace members, pinned toolchain versions and build commands, \
+        plus the versions of the matching toolchains on PATH (rustc, cargo, node, python, go) and the \
+        active rustup toolchain")]
+    pub async fn get_dev_environment(
+        &self,
+        Parameters(params): Parameters<DevEnvParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let path = params
+            .path
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+        let path = path
+            .canonicalize()
+            .map_err(|e| McpError::invalid_params(format!("Cannot open '{}': {}", path.display(), e), None))?;
+        if !path.is_dir() {
+            return Err(McpError::invalid_params(format!("'{}' is not a directory", path.display()), None));
+        }
+        let detail = params.detail.unwrap_or_default();
+
+        let (project, versions) = tokio::task::spawn_blocking(move || {
+            let project = dev_env::detect(&path);
+            let versions = dev_env::tool_versions(&project.tools(), &project.root);
+            (project, versions)
+        })
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let versions: Vec<_> = versions.into_iter().filter(|v| !v.optional || v.path.is_some()).collect();
+
+        if detail == Detail::Summary {
+            let kinds: Vec<String> = project
+                .manifests
+                .iter()
+                .map(|m| match (&m.name, m.members.len()) {
+                    (Some(name), 0) => format!("{} ({})", m.kind, name),
+                    (Some(name), n) => format!("{} ({}, {} members)", m.kind, name, n),
+                    (None, 0) => m.kind.to_string(),
+                    (None, n) => format!("{} ({} members)", m.kind, n),
+                })
+                .collect();
+            let tools: Vec<String> = versions
+                .iter()
+                .filter(|v| !v.optional)
+                .map(|v| match &v.version {
+                    Ok(version) if version.to_lowercase().starts_with(v.name) => version.clone(),
+                    Ok(version) => format!("{} {}", v.name, version),
+                    Err(_) => format!("{} missing", v.name),
+                })
+                .collect();
+            let result = format!(
+                "{}: {}; {}",
+                project.root.display(),
+                if kinds.is_empty() { "no project manifest".to_string() } else { kinds.join(", ") },
+                tools.join(", ")
+            );
+            return Ok(CallToolResult::success(vec![Content::text(result)]));
+        }
+
+        let mut result = format!("Development Environment: {}\n\n", project.root.display());
+        if project.manifests.is_empty() {
+            result.push_str("No project manifest found here or in parent directories up to the git root.\n\n");
+        }
+        let member_limit = if detail == Detail::Full { usize::MAX } else { 20 };
+        for manifest in &project.manifests {
+            let file = manifest.file.strip_prefix(&project.root).unwrap_or(&manifest.file);
+            result.push_str(&format!("{} ({})", manifest.kind, file.display()));
+            match (&manifest.name, &manifest.version) {
+                (Some(name), Some(version)) => result.push_str(&format!(": {} {}\n", name, version)),
+                (Some(name), None) => result.push_str(&format!(": {}\n", name)),
+                _ => result.push('\n'),
+            }
+            for (label, value) in &manifest.facts {
+                result.push_str(&format!("  {}: {}\n", label, value));
+            }
+            if let Some(dependencies) = manifest.dependencies {
+                result.push_str(&format!("  dependencies: {}\n", dependencies));
+            }
+            if !manifest.members.is_empty() {
+                result.push_str(&format!("  Workspace members ({}):\n", manifest.members.len()));
+                for member in manifest.members.iter().take(member_limit) {
+                    match &member.name {
+                        Some(name) if !member.path.ends_with(name.as_str()) => {
+                            result.push_str(&format!("    {} ({})\n", member.path, name))
+                        }
+                        _ => result.push_str(&format!("    {}\n", member.path)),
+                    }
+                }
+                if manifest.members.len() > member_limit {
+                    result.push_str(&format!("    ... and {} more\n", manifest.members.len() - member_limit));
+                }
+            }
+            result.push_str(&format!("  Build: {}\n\n", manifest.build.join(" && ")));
+        }
+
+        if !project.pins.is_empty() {
+            result.push_str("Pinned versions:\n");
+            for (file, version) in &project.pins {
+                result.push_str(&format!("  {}: {}\n", file, version));
+            }
+            result.push('\n');
+        }
+
+        result.push_str("Toolchains on PATH:\n");
+        for tool in &versions {
+            match &tool.version {
+                Ok(version) => result.push_str(&format!("  {:<8} {}", tool.name, version)),
+                Err(e) => result.push_str(&format!("  {:<8} ({})", tool.name, e)),
+            }
+            if let (Detail::Full, Some(path)) = (detail, &tool.path) {
+                result.push_str(&format!("  [{}]", path.display()));
+            }
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
     // ========================================================================
     // SYSINFO
     // ========================================================================
 
-    #[rmcp::tool(description = "Get system overview: CPU usage, memory, disk space, uptime")]
-    pub async fn get_system_info(&self) -> Result<CallToolResult, McpError> {
+    #[rmcp::tool(description = "Get system overview: CPU usage, memory, disk space, uptime. Inside a container \
+        or systemd slice also reports the cgroup CPU quota and memory limit")]
+    pub async fn get_system_info(
+        &self,
+        Parameters(params): Parameters<SystemInfoParams>,
+    ) -> Result<CallToolResult, McpError> {
         let mut sys = System::new_with_specifics(
             RefreshKind::nothing()
                 .with_cpu(CpuRefreshKind::everything())
                 .with_memory(MemoryRefreshKind::everything()),
         );
 
+        let root = std::path::Path::new(cgroup::ROOT);
+        let cgroup_before = cgroup::read_cgroup(root);
+        let started = std::time::Instant::now();
         std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
         sys.refresh_cpu_all();
+        let cgroup_info = cgroup::read_cgroup(root);
+        let runtime = cgroup::detect_runtime(root);
+
+        // Cores the cgroup used over the sampling interval.
+        let cgroup_cores = match (
+            cgroup_before.as_ref().and_then(|c| c.cpu_usage_us),
+            cgroup_info.as_ref().and_then(|c| c.cpu_usage_us),
+        ) {
+            (Some(before), Some(after)) => {
+                Some(after.saturating_sub(before) as f64 / started.elapsed().as_micros().max(1) as f64)
+            }
+            _ => None,
+        };
 
         let disks = Disks::new_with_refreshed_list();
 
         let cpu_count = sys.cpus().len();
-        let cpu_usage: f32 =
+        let mut cpu_usage: f32 =
             sys.cpus().iter().map(|c| c.cpu_usage()).sum::<f32>() / cpu_count as f32;
         let cpu_name = sys.cpus().first().map(|c| c.brand()).unwrap_or("Unknown");
 
-        let total_mem = sys.total_memory();
-        let used_mem = sys.used_memory();
+        let mut total_mem = sys.total_memory();
+        let mut used_mem = sys.used_memory();
+
+        let relative = params.relative_to_cgroup.unwrap_or(false);
+        let mut cpu_scope = "";
+        let mut mem_scope = "";
+        if relative {
+            if let (Some(quota), Some(cores)) =
+                (cgroup_info.as_ref().and_then(|c| c.cpu_quota.as_ref()).map(|q| q.value), cgroup_cores)
+            {
+                cpu_usage = (cores / quota * 100.0) as f32;
+                cpu_scope = " of cgroup quota";
+            }
+            if let Some((max, current)) =
+                cgroup_info.as_ref().and_then(|c| Some((c.memory_max.as_ref()?.value, c.memory_current?)))
+            {
+                total_mem = max;
+                used_mem = current;
+                mem_scope = " of cgroup limit";
+            }
+        }
         let mem_percent = (used_mem as f64 / total_mem as f64 * 100.0) as u64;
 
         let total_swap = sys.total_swap();
@@ -1106,14 +2748,33 @@ impl SensorsServer {
         let uptime_mins = (uptime_secs % 3600) / 60;
 
         let load = System::load_average();
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let mut output = format!(
+                "CPU {:.0}%{}, memory {:.0}%{}, {} disk free, load {:.2}, up {}h {}m",
+                cpu_usage,
+                cpu_scope,
+                mem_percent,
+                mem_scope,
+                Self::format_bytes(free_disk),
+                load.one,
+                uptime_hours,
+                uptime_mins
+            );
+            if let Some(runtime) = runtime {
+                output.push_str(&format!(", in a {} container", runtime));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
 
-        let output = format!(
+        let mut output = format!(
             "System Information:\n\
              \n\
              CPU: {} ({} cores)\n\
-             CPU Usage: {:.1}%\n\
+             CPU Usage: {:.1}%{}\n\
              \n\
-             Memory: {} / {} ({:.0}%)\n\
+             Memory: {} / {} ({:.0}%{})\n\
              Swap: {} / {}\n\
              \n\
              Disk: {} / {} free\n\
@@ -1123,9 +2784,11 @@ impl SensorsServer {
             cpu_name,
             cpu_count,
             cpu_usage,
+            cpu_scope,
             Self::format_bytes(used_mem),
             Self::format_bytes(total_mem),
             mem_percent,
+            mem_scope,
             Self::format_bytes(used_swap),
             Self::format_bytes(total_swap),
             Self::format_bytes(free_disk),
@@ -1137,303 +2800,3161 @@ impl SensorsServer {
             load.fifteen
         );
 
-        Ok(CallToolResult::success(vec![Content::text(output)]))
-    }
-
-    #[rmcp::tool(description = "Get detailed disk usage for all mounted filesystems")]
-    pub async fn get_disk_info(&self) -> Result<CallToolResult, McpError> {
-        let disks = Disks::new_with_refreshed_list();
-
-        let mut output = String::from("Disk Usage:\n\n");
-
-        for disk in disks.iter() {
-            let total = disk.total_space();
-            let free = disk.available_space();
-            let used = total - free;
-            let percent = if total > 0 {
-                (used as f64 / total as f64 * 100.0) as u64
-            } else {
-                0
+        let limits = cgroup_info.as_ref().filter(|c| c.has_limits() || detail == Detail::Full);
+        if runtime.is_some() || limits.is_some() {
+            output.push_str(&format!("\n\nContainer: {}", runtime.unwrap_or("none detected")));
+        }
+        if let Some(c) = limits {
+            output.push_str(&format!("\n  Cgroup: v{} {}", c.version, c.path));
+            // Limits inherited from an ancestor name it.
+            let set_on = |set_on: &str| {
+                if set_on == c.path { String::new() } else { format!(" [set on {}]", set_on) }
             };
+            match (&c.cpu_quota, cgroup_cores) {
+                (Some(quota), Some(cores)) => output.push_str(&format!(
+                    "\n  CPU Quota: {:.2} cores (using {:.2}){}",
+                    quota.value,
+                    cores,
+                    set_on(&quota.set_on)
+                )),
+                (Some(quota), None) => {
+                    output.push_str(&format!("\n  CPU Quota: {:.2} cores{}", quota.value, set_on(&quota.set_on)))
+                }
+                (None, _) => output.push_str("\n  CPU Quota: unlimited"),
+            }
+            match (&c.memory_max, c.memory_current) {
+                (Some(max), Some(current)) => output.push_str(&format!(
+                    "\n  Memory Limit: {} (using {}, {:.0}%){}",
+                    Self::format_bytes(max.value),
+                    Self::format_bytes(current),
+                    current as f64 / max.value as f64 * 100.0,
+                    set_on(&max.set_on)
+                )),
+                (Some(max), None) => output.push_str(&format!(
+                    "\n  Memory Limit: {}{}",
+                    Self::format_bytes(max.value),
+                    set_on(&max.set_on)
+                )),
+                (None, Some(current)) => output.push_str(&format!(
+                    "\n  Memory Limit: unlimited (using {})",
+                    Self::format_bytes(current)
+                )),
+                (None, None) => output.push_str("\n  Memory Limit: unlimited"),
+            }
+            if let Some(high) = &c.memory_high {
+                output.push_str(&format!(
+                    "\n  Memory Throttle (memory.high): {}{}",
+                    Self::format_bytes(high.value),
+                    set_on(&high.set_on)
+                ));
+            }
+            if let Some(max) = &c.pids_max {
+                output.push_str(&format!(
+                    "\n  Processes: {} / {}{}",
+                    c.pids_current.unwrap_or(0),
+                    max.value,
+                    set_on(&max.set_on)
+                ));
+            }
+        }
+        if relative && cpu_scope.is_empty() && mem_scope.is_empty() {
+            output.push_str("\n\nNo cgroup CPU quota or memory limit applies; percentages are against host totals.");
+        }
 
+        if detail == Detail::Full {
             output.push_str(&format!(
-                "{} ({})\n  {} / {} ({:.0}% used)\n  Mount: {}\n\n",
-                disk.name().to_string_lossy(),
-                disk.file_system().to_string_lossy(),
-                Self::format_bytes(used),
-                Self::format_bytes(total),
-                percent,
-                disk.mount_point().display()
+                "\n\nHost: {}\nOS: {}\nKernel: {}\nArchitecture: {}\nAvailable Memory: {}\nFree Memory: {}\n\nPer-core usage:\n",
+                System::host_name().unwrap_or_else(|| "unknown".to_string()),
+                System::long_os_version().unwrap_or_else(|| "unknown".to_string()),
+                System::kernel_version().unwrap_or_else(|| "unknown".to_string()),
+                System::cpu_arch(),
+                Self::format_bytes(sys.available_memory()),
+                Self::format_bytes(sys.free_memory()),
             ));
+            for cpu in sys.cpus() {
+                output.push_str(&format!("  {}: {:.1}% @ {} MHz\n", cpu.name(), cpu.cpu_usage(), cpu.frequency()));
+            }
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get top processes by CPU or memory usage")]
-    pub async fn get_top_processes(
+    #[rmcp::tool(description = "Get host identity: hostname, OS and distribution, kernel, architecture, boot time, \
+        timezone, virtualization or container, and chassis (laptop, desktop, server) with the hardware model")]
+    pub async fn get_host_info(
         &self,
-        Parameters(params): Parameters<TopProcessesParams>,
+        Parameters(params): Parameters<DetailParams>,
     ) -> Result<CallToolResult, McpError> {
-        let mut sys = System::new_all();
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-        sys.refresh_all();
-
-        let count = params.count.unwrap_or(10);
-        let sort_by = params.sort_by.unwrap_or_else(|| "cpu".to_string());
-
-        let mut processes: Vec<_> = sys.processes().values().collect();
+        let root = std::path::Path::new(host::ROOT);
+        let dmi = host::read_dmi(root);
+        let hypervisor = host::detect_hypervisor(root, dmi.as_ref());
+        let runtime = cgroup::detect_runtime(std::path::Path::new(cgroup::ROOT));
+        let detail = params.detail.unwrap_or_default();
+
+        let hostname = System::host_name().unwrap_or_else(|| "unknown".to_string());
+        let os = match (System::name(), System::os_version()) {
+            (Some(name), Some(version)) => format!("{} {}", name, version),
+            (Some(name), None) => name,
+            _ => System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        };
+        let kernel = System::kernel_version().unwrap_or_else(|| "unknown".to_string());
+        let chassis = dmi.as_ref().and_then(|d| d.chassis_type).map(host::chassis);
 
-        match sort_by.as_str() {
-            "memory" | "mem" => {
-                processes.sort_by(|a, b| b.memory().cmp(&a.memory()));
+        if detail == Detail::Summary {
+            let mut output = format!("{}: {}, kernel {}, {}", hostname, os, kernel, System::cpu_arch());
+            if let Some(hypervisor) = hypervisor {
+                output.push_str(&format!(", VM ({})", hypervisor));
             }
-            _ => {
-                processes.sort_by(|a, b| {
-                    b.cpu_usage()
-                        .partial_cmp(&a.cpu_usage())
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
+            if let Some(runtime) = runtime {
+                output.push_str(&format!(", in a {} container", runtime));
             }
+            if let Some(class) = chassis.and_then(|(_, class)| class) {
+                output.push_str(&format!(", {}", class));
+            }
+            output.push_str(&format!(", up {}", Self::format_duration(System::uptime())));
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
         }
 
-        let mut output = format!("Top {} processes by {}:\n\n", count, sort_by);
-        output.push_str(&format!(
-            "{:<8} {:<10} {:<10} {}\n",
-            "PID", "CPU%", "Memory", "Name"
-        ));
-        output.push_str(&format!("{:-<50}\n", ""));
-
-        for proc in processes.iter().take(count) {
+        let mut output = String::from("Host Information:\n\n");
+        output.push_str(&format!("Hostname: {}\n", hostname));
+        output.push_str(&format!("OS: {}\n", os));
+        let like = System::distribution_id_like();
+        if like.is_empty() {
+            output.push_str(&format!("Distribution ID: {}\n", System::distribution_id()));
+        } else {
+            output.push_str(&format!("Distribution ID: {} (like {})\n", System::distribution_id(), like.join(", ")));
+        }
+        if detail == Detail::Full {
+            output.push_str(&format!("Kernel: {}\n", System::kernel_long_version()));
+        } else {
+            output.push_str(&format!("Kernel: {}\n", kernel));
+        }
+        output.push_str(&format!("Architecture: {}\n", System::cpu_arch()));
+        if let Some(boot) = chrono::DateTime::from_timestamp(System::boot_time() as i64, 0) {
             output.push_str(&format!(
-                "{:<8} {:<10.1} {:<10} {}\n",
-                proc.pid(),
-                proc.cpu_usage(),
-                Self::format_bytes(proc.memory()),
-                proc.name().to_string_lossy()
+                "Boot Time: {} (up {})\n",
+                boot.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                Self::format_duration(System::uptime())
             ));
         }
+        let offset = chrono::Local::now().format("%:z");
+        match host::timezone(root) {
+            Some(zone) => output.push_str(&format!("Timezone: {} (UTC{})\n", zone, offset)),
+            None => output.push_str(&format!("Timezone: UTC{}\n", offset)),
+        }
+        output.push_str(&format!("Virtualization: {}\n", hypervisor.unwrap_or("none detected")));
+        if let Some(runtime) = runtime {
+            output.push_str(&format!("Container: {}\n", runtime));
+        }
+
+        match chassis {
+            Some((name, Some(class))) => output.push_str(&format!("Chassis: {} ({})\n", class, name)),
+            Some((name, None)) => output.push_str(&format!("Chassis: {}\n", name)),
+            None => output.push_str("Chassis: unknown (no DMI data)\n"),
+        }
+        if let Some(dmi) = &dmi {
+            if let Some(product) = dmi.product() {
+                output.push_str(&format!("Hardware: {}\n", product));
+            }
+            if detail == Detail::Full {
+                if let Some(version) = &dmi.product_version {
+                    output.push_str(&format!("  Version: {}\n", version));
+                }
+                let board: Vec<&str> = [&dmi.board_vendor, &dmi.board_name].into_iter().flatten().map(String::as_str).collect();
+                if !board.is_empty() {
+                    output.push_str(&format!("  Board: {}\n", board.join(" ")));
+                }
+                let bios: Vec<&str> = [&dmi.bios_vendor, &dmi.bios_version, &dmi.bios_date]
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect();
+                if !bios.is_empty() {
+                    output.push_str(&format!("  Firmware: {}\n", bios.join(" ")));
+                }
+            }
+        }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Find processes by name (case-insensitive, partial match)")]
-    pub async fn find_process(
+    #[rmcp::tool(description = "Get Linux pressure stall information (how much time tasks waited on CPU, memory \
+        and I/O) and a memory breakdown (available, cache, buffers, dirty, shmem, slab, swap, zswap, huge pages). \
+        Use to tell real memory pressure from memory that is only used as cache")]
+    pub async fn get_memory_pressure(
         &self,
-        Parameters(params): Parameters<FindProcessParams>,
+        Parameters(params): Parameters<DetailParams>,
     ) -> Result<CallToolResult, McpError> {
-        let mut sys = System::new_all();
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-        sys.refresh_all();
-
-        let search = params.name.to_lowercase();
-        let mut matches: Vec<_> = sys
-            .processes()
-            .values()
-            .filter(|p| p.name().to_string_lossy().to_lowercase().contains(&search))
+        let detail = params.detail.unwrap_or_default();
+        let proc_root = std::path::Path::new(pressure::PROC_ROOT);
+        let resources: Vec<_> = [("cpu", "CPU"), ("memory", "Memory"), ("io", "I/O")]
+            .into_iter()
+            .filter_map(|(resource, label)| Some((resource, label, pressure::read_pressure(proc_root, resource)?)))
             .collect();
+        let meminfo = pressure::read_meminfo(proc_root);
+        if resources.is_empty() && meminfo.is_empty() {
+            return Err(McpError::internal_error(
+                "Neither /proc/pressure nor /proc/meminfo is readable (Linux only)".to_string(),
+                None,
+            ));
+        }
+        let mem = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+        let total = mem("MemTotal");
+        let available = mem("MemAvailable");
+        let available_percent = if total > 0 { available as f64 / total as f64 * 100.0 } else { 0.0 };
+        let cache = mem("Cached") + mem("Buffers") + mem("SReclaimable");
+        let memory_psi = resources.iter().find(|(r, _, _)| *r == "memory").map(|(_, _, p)| *p);
+
+        // Stalls are what users feel; low MemAvailable alone can be a cold cache.
+        let verdict = match memory_psi {
+            Some(p) if p.full.is_some_and(|f| f.avg10 >= 5.0) || p.some.avg10 >= 20.0 => {
+                "high memory pressure: tasks are stalling on memory"
+            }
+            Some(p) if p.some.avg10 >= 5.0 || p.some.avg60 >= 5.0 => "some memory pressure: occasional stalls on memory",
+            _ if total > 0 && available_percent < 10.0 => "low on available memory, but no stalls yet",
+            _ if total > 0 && cache > total - available.min(total) => "no memory pressure; most used memory is cache",
+            _ => "no memory pressure",
+        };
 
-        matches.sort_by(|a, b| {
-            b.cpu_usage()
-                .partial_cmp(&a.cpu_usage())
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        if detail == Detail::Summary {
+            let psi: Vec<_> = resources
+                .iter()
+                .map(|(_, label, p)| format!("{} {:.1}%", label, p.some.avg10))
+                .collect();
+            let mut output = format!("{:.0}% memory available; {}", available_percent, verdict);
+            if !psi.is_empty() {
+                output.push_str(&format!(" (stalled avg10: {})", psi.join(", ")));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
 
-        let mut output = format!("Processes matching '{}':\n\n", params.name);
+        let mut output = String::from("Memory Pressure:\n\n");
+        output.push_str(&format!("Assessment: {}\n", verdict));
 
-        if matches.is_empty() {
-            output.push_str("No matching processes found.\n");
+        if resources.is_empty() {
+            output.push_str("\nPressure stall information: not available (needs Linux 4.20+ with CONFIG_PSI)\n");
         } else {
-            output.push_str(&format!(
-                "{:<8} {:<10} {:<10} {}\n",
-                "PID", "CPU%", "Memory", "Name"
-            ));
-            output.push_str(&format!("{:-<50}\n", ""));
+            output.push_str("\nPressure stall information (share of time stalled, avg10 / avg60 / avg300):\n");
+            let averages = |a: &pressure::Averages| format!("{:5.1}% / {:5.1}% / {:5.1}%", a.avg10, a.avg60, a.avg300);
+            for (resource, label, p) in &resources {
+                output.push_str(&format!("  {:<7} some {}", label, averages(&p.some)));
+                if detail == Detail::Full {
+                    output.push_str(&format!("  total {}", Self::format_duration(p.some.total_us / 1_000_000)));
+                }
+                output.push('\n');
+                // System-wide CPU "full" is always zero and only confuses.
+                if let Some(full) = p.full.filter(|_| *resource != "cpu") {
+                    output.push_str(&format!("  {:<7} full {}", "", averages(&full)));
+                    if detail == Detail::Full {
+                        output.push_str(&format!("  total {}", Self::format_duration(full.total_us / 1_000_000)));
+                    }
+                    output.push('\n');
+                }
+            }
+        }
 
-            for proc in matches.iter().take(20) {
+        if !meminfo.is_empty() {
+            let line = |label: &str, bytes: u64, note: &str| {
+                if note.is_empty() {
+                    format!("  {:<20} {:>10}\n", label, Self::format_bytes(bytes))
+                } else {
+                    format!("  {:<20} {:>10}  {}\n", label, Self::format_bytes(bytes), note)
+                }
+            };
+            output.push_str("\nMemory breakdown:\n");
+            output.push_str(&line("Total", total, ""));
+            output.push_str(&line("Available", available, &format!("{:.0}% (free + reclaimable cache)", available_percent)));
+            output.push_str(&line("Free", mem("MemFree"), ""));
+            output.push_str(&line("Applications", mem("AnonPages"), "anonymous memory, not reclaimable without swap"));
+            output.push_str(&line("Page cache", mem("Cached"), "file cache, reclaimable"));
+            output.push_str(&line("Buffers", mem("Buffers"), ""));
+            output.push_str(&line("Shared (tmpfs/shm)", mem("Shmem"), "counted in page cache but not reclaimable"));
+            output.push_str(&line("Slab reclaimable", mem("SReclaimable"), ""));
+            output.push_str(&line("Slab unreclaimable", mem("SUnreclaim"), ""));
+            output.push_str(&line("Dirty", mem("Dirty"), "waiting to be written to disk"));
+            if mem("Writeback") > 0 || detail == Detail::Full {
+                output.push_str(&line("Writeback", mem("Writeback"), ""));
+            }
+            if mem("SwapTotal") > 0 {
+                output.push_str(&line(
+                    "Swap used",
+                    mem("SwapTotal").saturating_sub(mem("SwapFree")),
+                    &format!("of {}", Self::format_bytes(mem("SwapTotal"))),
+                ));
+                output.push_str(&line("Swap cached", mem("SwapCached"), ""));
+            } else {
+                output.push_str("  Swap                 none\n");
+            }
+            if mem("Zswapped") > 0 || detail == Detail::Full {
+                output.push_str(&line(
+                    "Zswap",
+                    mem("Zswap"),
+                    &format!("compressed, holding {}", Self::format_bytes(mem("Zswapped"))),
+                ));
+            }
+            if mem("HugePages_Total") > 0 || detail == Detail::Full {
                 output.push_str(&format!(
-                    "{:<8} {:<10.1} {:<10} {}\n",
-                    proc.pid(),
-                    proc.cpu_usage(),
-                    Self::format_bytes(proc.memory()),
-                    proc.name().to_string_lossy()
+                    "  {:<20} {:>10}  {} free, {} each\n",
+                    "Huge pages",
+                    mem("HugePages_Total"),
+                    mem("HugePages_Free"),
+                    Self::format_bytes(mem("Hugepagesize"))
                 ));
             }
-
-            if matches.len() > 20 {
-                output.push_str(&format!("\n... and {} more matches\n", matches.len() - 20));
+            if mem("AnonHugePages") > 0 || detail == Detail::Full {
+                output.push_str(&line("Transparent huge", mem("AnonHugePages"), ""));
             }
 
-            output.push_str(&format!("\nTotal matches: {}\n", matches.len()));
+            if detail == Detail::Full {
+                let mut keys: Vec<_> = meminfo.keys().collect();
+                keys.sort();
+                output.push_str("\nAll /proc/meminfo fields:\n");
+                for key in keys {
+                    if key.starts_with("HugePages_") {
+                        output.push_str(&format!("  {}: {}\n", key, meminfo[key]));
+                    } else {
+                        output.push_str(&format!("  {}: {}\n", key, Self::format_bytes(meminfo[key])));
+                    }
+                }
+            }
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get detailed information about a specific process by PID")]
-    pub async fn get_process_details(
+    #[rmcp::tool(description = "Get per-core CPU usage and frequency, physical vs logical cores, architecture, \
+        cache sizes and frequency governor (spot a single pegged core or a throttled clock)")]
+    pub async fn get_cpu_details(
         &self,
-        Parameters(params): Parameters<ProcessIdParams>,
+        Parameters(params): Parameters<DetailParams>,
     ) -> Result<CallToolResult, McpError> {
-        let mut sys = System::new_all();
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-        sys.refresh_all();
-
-        let pid = sysinfo::Pid::from_u32(params.pid);
-
-        let proc = sys.process(pid).ok_or_else(|| {
-            McpError::internal_error(format!("Process {} not found", params.pid), None)
-        })?;
-
-        let mut output = format!("Process Details (PID {}):\n\n", params.pid);
+        let mut sys = System::new_with_specifics(
+            RefreshKind::nothing().with_cpu(CpuRefreshKind::everything()),
+        );
+        tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+        sys.refresh_cpu_all();
 
-        output.push_str(&format!("Name: {}\n", proc.name().to_string_lossy()));
-        output.push_str(&format!("Status: {:?}\n", proc.status()));
-        output.push_str(&format!("CPU Usage: {:.1}%\n", proc.cpu_usage()));
-        output.push_str(&format!("Memory: {}\n", Self::format_bytes(proc.memory())));
-        output.push_str(&format!("Virtual Memory: {}\n", Self::format_bytes(proc.virtual_memory())));
+        let root = std::path::Path::new(cpu::SYSFS_CPU_ROOT);
+        let cpus = sys.cpus();
+        let logical = cpus.len();
+        let physical = System::physical_core_count();
+        let average = cpus.iter().map(|c| c.cpu_usage()).sum::<f32>() / logical.max(1) as f32;
+        let pegged: Vec<_> = cpus.iter().filter(|c| c.cpu_usage() >= 90.0).map(|c| c.name()).collect();
+        let scaling = cpu::read_scaling(root);
+        let capped = scaling.values().filter(|s| s.is_capped()).count();
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let busiest = cpus
+                .iter()
+                .max_by(|a, b| a.cpu_usage().partial_cmp(&b.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|c| format!(", busiest {} {:.0}%", c.name(), c.cpu_usage()))
+                .unwrap_or_default();
+            let mut output = format!(
+                "{} logical / {} physical cores, {:.0}% average{}",
+                logical,
+                physical.map(|p| p.to_string()).unwrap_or_else(|| "?".to_string()),
+                average,
+                busiest
+            );
+            if !pegged.is_empty() {
+                output.push_str(&format!(", {} pegged", pegged.len()));
+            }
+            if capped > 0 {
+                output.push_str(&format!(", {} capped below max frequency", capped));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
 
-        if let Some(parent) = proc.parent() {
-            output.push_str(&format!("Parent PID: {}\n", parent));
+        let mut output = String::from("CPU Details:\n\n");
+        if let Some(first) = cpus.first() {
+            output.push_str(&format!("Model: {}\n", first.brand()));
+            if !first.vendor_id().is_empty() {
+                output.push_str(&format!("Vendor: {}\n", first.vendor_id()));
+            }
         }
+        output.push_str(&format!("Architecture: {}\n", System::cpu_arch()));
 
-        let run_time = proc.run_time();
-        output.push_str(&format!("Running for: {}\n", Self::format_duration(run_time)));
+        let topology = cpu::read_topology(root);
+        let packages: std::collections::BTreeSet<_> = topology.iter().filter_map(|t| t.package).collect();
+        output.push_str(&format!(
+            "Cores: {} physical, {} logical",
+            physical.map(|p| p.to_string()).unwrap_or_else(|| "unknown".to_string()),
+            logical
+        ));
+        if !packages.is_empty() {
+            output.push_str(&format!(" ({} package(s))", packages.len()));
+        }
+        output.push('\n');
+        output.push_str(&format!("Average usage: {:.1}%\n", average));
+
+        output.push_str("\nPer-core:\n");
+        for c in cpus {
+            let id = cpu::cpu_id(c.name());
+            output.push_str(&format!("  {:<6} {:>5.1}%  {:>5} MHz", c.name(), c.cpu_usage(), c.frequency()));
+            if let Some(s) = id.and_then(|id| scaling.get(&id)) {
+                if let (Some(min), Some(max)) = (s.min_khz, s.max_khz) {
+                    output.push_str(&format!("  ({}-{} MHz)", min / 1000, max / 1000));
+                }
+                if s.is_capped() {
+                    output.push_str(" [capped]");
+                }
+            }
+            if c.cpu_usage() >= 90.0 {
+                output.push_str(" [pegged]");
+            }
+            if detail == Detail::Full {
+                if let Some(t) = topology.iter().find(|t| Some(t.cpu) == id) {
+                    if let (Some(package), Some(core)) = (t.package, t.core) {
+                        output.push_str(&format!("  package {} core {}", package, core));
+                    }
+                }
+            }
+            output.push('\n');
+        }
 
-        if let Some(exe) = proc.exe() {
-            output.push_str(&format!("Executable: {}\n", exe.display()));
+        let caches = cpu::read_caches(root);
+        if !caches.is_empty() {
+            output.push_str("\nCaches:\n");
+            for cache in &caches {
+                output.push_str(&format!("  {}: {}", cache.name(), cache.size));
+                if cache.instances > 1 {
+                    output.push_str(&format!(" x {}", cache.instances));
+                }
+                output.push('\n');
+            }
         }
 
-        if let Some(cwd) = proc.cwd() {
-            output.push_str(&format!("Working Dir: {}\n", cwd.display()));
+        let mut governors: Vec<_> = scaling.values().filter_map(|s| s.governor.clone()).collect();
+        governors.sort();
+        governors.dedup();
+        if let Some(s) = scaling.values().next() {
+            output.push_str("\nFrequency scaling:\n");
+            if let Some(driver) = &s.driver {
+                output.push_str(&format!("  Driver: {}\n", driver));
+            }
+            if !governors.is_empty() {
+                output.push_str(&format!("  Governor: {}\n", governors.join(", ")));
+            }
+            if let Some(hw) = s.hardware_max_khz {
+                output.push_str(&format!("  Hardware max: {} MHz\n", hw / 1000));
+            }
+            if detail == Detail::Full {
+                if let Some(available) = &s.available_governors {
+                    output.push_str(&format!("  Available governors: {}\n", available));
+                }
+            }
+        } else {
+            output.push_str("\nFrequency scaling: not exposed on this system\n");
         }
 
-        let cmd = proc.cmd();
-        if !cmd.is_empty() {
-            let cmd_str: Vec<_> = cmd.iter().map(|s| s.to_string_lossy()).collect();
-            let cmd_display = cmd_str.join(" ");
-            if cmd_display.len() > 200 {
-                output.push_str(&format!("Command: {}...\n", &cmd_display[..200]));
-            } else {
-                output.push_str(&format!("Command: {}\n", cmd_display));
+        if !pegged.is_empty() || capped > 0 {
+            output.push_str("\nNotes:\n");
+            if !pegged.is_empty() {
+                output.push_str(&format!("  Pegged (>=90%): {}\n", pegged.join(", ")));
+            }
+            if capped > 0 {
+                output.push_str(&format!(
+                    "  {} core(s) have a policy max below the hardware max (power profile or thermal limit)\n",
+                    capped
+                ));
             }
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "List all running processes (sorted by CPU usage)")]
-    pub async fn list_processes(&self) -> Result<CallToolResult, McpError> {
-        let mut sys = System::new_all();
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-        sys.refresh_all();
-
-        let mut processes: Vec<_> = sys.processes().values().collect();
-        processes.sort_by(|a, b| {
+    #[rmcp::tool(description = "Get disk activity: read/write bytes per second, IOPS and busy time per block device, \
+        plus the processes doing the most disk I/O. Use when the machine is sluggish but CPU is idle")]
+    pub async fn get_disk_io(
+        &self,
+        Parameters(params): Parameters<DiskIoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let detail = params.detail.unwrap_or_default();
+        let interval = Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000));
+        let proc_root = std::path::Path::new(disk_io::PROC_ROOT);
+        let sysfs_block = std::path::Path::new(disk_io::SYSFS_BLOCK_ROOT);
+        let refresh = ProcessRefreshKind::nothing().with_disk_usage();
+
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        let before = disk_io::read_diskstats(proc_root, sysfs_block);
+        let started = std::time::Instant::now();
+        tokio::time::sleep(interval).await;
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        let after = disk_io::read_diskstats(proc_root, sysfs_block);
+        let seconds = started.elapsed().as_secs_f64();
+
+        let devices: Vec<_> = disk_io::rates(&before, &after, seconds)
+            .into_iter()
+            .filter(|d| detail == Detail::Full || (d.is_disk && !d.is_idle()))
+            .collect();
+
+        let mut processes: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none())
+            .map(|p| {
+                let io = p.disk_usage();
+                (p, io.read_bytes as f64 / seconds, io.written_bytes as f64 / seconds)
+            })
+            .filter(|(_, read, written)| *read > 0.0 || *written > 0.0)
+            .collect();
+        processes.sort_by(|a, b| (b.1 + b.2).partial_cmp(&(a.1 + a.2)).unwrap_or(std::cmp::Ordering::Equal));
+
+        let rate = |bytes: f64| format!("{}/s", Self::format_bytes(bytes as u64));
+
+        if detail == Detail::Summary {
+            let busiest = devices
+                .iter()
+                .filter(|d| d.is_disk)
+                .max_by(|a, b| a.utilization.partial_cmp(&b.utilization).unwrap_or(std::cmp::Ordering::Equal));
+            let mut output = match busiest {
+                Some(d) => format!(
+                    "{}: {} read, {} written, {:.0} IOPS, {:.0}% busy",
+                    d.name,
+                    rate(d.read_per_sec),
+                    rate(d.written_per_sec),
+                    d.read_iops + d.write_iops,
+                    d.utilization
+                ),
+                None => "No disk activity".to_string(),
+            };
+            if let Some((p, read, written)) = processes.first() {
+                output.push_str(&format!(
+                    "; top process: {} ({})",
+                    p.name().to_string_lossy(),
+                    rate(read + written)
+                ));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("Disk I/O (rates over {:.1}s):\n\n", seconds);
+
+        if devices.is_empty() {
+            output.push_str("No disk activity.\n");
+        } else {
+            output.push_str(&format!(
+                "{:<12} {:>12} {:>12} {:>8} {:>8} {:>6}\n",
+                "Device", "Read", "Write", "R IOPS", "W IOPS", "Busy"
+            ));
+            for d in &devices {
+                output.push_str(&format!(
+                    "{:<12} {:>12} {:>12} {:>8.0} {:>8.0} {:>5.0}%",
+                    d.name,
+                    rate(d.read_per_sec),
+                    rate(d.written_per_sec),
+                    d.read_iops,
+                    d.write_iops,
+                    d.utilization
+                ));
+                if d.utilization >= 90.0 {
+                    output.push_str(" [saturated]");
+                }
+                if d.in_flight > 0 && detail == Detail::Full {
+                    output.push_str(&format!(" ({} in flight)", d.in_flight));
+                }
+                output.push('\n');
+            }
+        }
+
+        let top = params.top.unwrap_or(if detail == Detail::Full { 20 } else { 5 });
+        output.push_str("\nTop processes by disk I/O:\n");
+        if processes.is_empty() {
+            output.push_str("  (none; other users' processes need root)\n");
+        }
+        for (p, read, written) in processes.iter().take(top) {
+            output.push_str(&format!(
+                "  {:>7}  {:<20} read {}, write {}\n",
+                p.pid(),
+                p.name().to_string_lossy(),
+                rate(*read),
+                rate(*written)
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get disk usage for mounted filesystems: used/total with reserved blocks, inode usage, \
+        mount flags (read-only, noexec), SSD vs HDD, removable and network mounts and the underlying block device. \
+        Pseudo filesystems (tmpfs, overlay, squashfs, ...) are skipped unless include_pseudo is set")]
+    pub async fn get_disk_info(
+        &self,
+        Parameters(params): Parameters<DiskInfoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let all_disks = Disks::new_with_refreshed_list();
+        let include_pseudo = params.include_pseudo.unwrap_or(false);
+        let disks: Vec<_> = all_disks
+            .iter()
+            .filter(|d| include_pseudo || !mounts::is_pseudo(&d.file_system().to_string_lossy()))
+            .collect();
+        let mount_table = mounts::read_mounts(std::path::Path::new(mounts::MOUNTINFO));
+        // statvfs separates root-reserved blocks from used ones; sysinfo only
+        // knows total and available.
+        let spaces: Vec<mounts::Space> = disks
+            .iter()
+            .map(|d| {
+                mounts::space(d.mount_point()).unwrap_or(mounts::Space {
+                    total_bytes: d.total_space(),
+                    free_bytes: d.available_space(),
+                    available_bytes: d.available_space(),
+                    inodes_total: 0,
+                    inodes_free: 0,
+                })
+            })
+            .collect();
+        let inode_percent = |s: &mounts::Space| {
+            let used = s.inodes_total.saturating_sub(s.inodes_free);
+            (s.inodes_total > 0).then(|| used as f64 / s.inodes_total as f64 * 100.0)
+        };
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = disks
+            .iter()
+            .map(|d| {
+                (
+                    d.mount_point().display().to_string(),
+                    format!(
+                        "{} ({}, {}) on {}",
+                        d.name().to_string_lossy(),
+                        d.file_system().to_string_lossy(),
+                        Self::format_bytes(d.total_space()),
+                        d.mount_point().display()
+                    ),
+                )
+            })
+            .collect();
+
+        if detail == Detail::Summary {
+            let fullest = disks
+                .iter()
+                .zip(&spaces)
+                .filter(|(_, s)| s.total_bytes > 0)
+                .max_by(|a, b| a.1.used_percent().total_cmp(&b.1.used_percent()));
+            let mut output = match fullest {
+                Some((disk, space)) => format!(
+                    "{} filesystems; fullest {} at {:.0}% ({} free)",
+                    disks.len(),
+                    disk.mount_point().display(),
+                    space.used_percent(),
+                    Self::format_bytes(space.available_bytes)
+                ),
+                None => "No filesystems found".to_string(),
+            };
+            let inode_fullest = disks
+                .iter()
+                .zip(&spaces)
+                .filter_map(|(d, s)| Some((d, inode_percent(s)?)))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((disk, percent)) = inode_fullest.filter(|(_, p)| *p >= 90.0) {
+                output.push_str(&format!("; inodes {:.0}% used on {}", percent, disk.mount_point().display()));
+            }
+            return self.report_changes("get_disk_info", "Filesystem", since, snapshot, output);
+        }
+
+        let mut output = String::from("Disk Usage:\n\n");
+        if disks.is_empty() {
+            output.push_str("No filesystems found.\n");
+        }
+
+        for (disk, space) in disks.iter().zip(&spaces) {
+            let fs_type = disk.file_system().to_string_lossy();
+            let mount = mounts::find(&mount_table, disk.mount_point());
+            let mut traits = vec![fs_type.to_string()];
+            match disk.kind() {
+                sysinfo::DiskKind::SSD => traits.push("SSD".to_string()),
+                sysinfo::DiskKind::HDD => traits.push("HDD".to_string()),
+                sysinfo::DiskKind::Unknown(_) => {}
+            }
+            if disk.is_removable() {
+                traits.push("removable".to_string());
+            }
+            if mounts::is_network(&fs_type) {
+                traits.push("network".to_string());
+            }
+
+            output.push_str(&format!(
+                "{} ({})\n  {} / {} ({:.0}% used)",
+                disk.name().to_string_lossy(),
+                traits.join(", "),
+                Self::format_bytes(space.used_bytes()),
+                Self::format_bytes(space.total_bytes),
+                space.used_percent()
+            ));
+            if space.reserved_bytes() > 0 {
+                output.push_str(&format!(", {} reserved for root", Self::format_bytes(space.reserved_bytes())));
+            }
+            output.push('\n');
+            if let Some(percent) = inode_percent(space) {
+                output.push_str(&format!(
+                    "  Inodes: {} / {} ({:.0}% used)\n",
+                    space.inodes_total - space.inodes_free,
+                    space.inodes_total,
+                    percent
+                ));
+            }
+
+            output.push_str(&format!("  Mount: {}", disk.mount_point().display()));
+            let mut flags: Vec<&str> = mounts::NOTABLE_OPTIONS
+                .iter()
+                .copied()
+                .filter(|o| mount.is_some_and(|m| m.has_option(o)))
+                .collect();
+            if disk.is_read_only() && !flags.contains(&"ro") {
+                flags.insert(0, "ro");
+            }
+            if !flags.is_empty() {
+                output.push_str(&format!(" [{}]", flags.join(", ")));
+            }
+            output.push('\n');
+
+            if let Some(mount) = mount {
+                let device = mounts::block_device(std::path::Path::new(mounts::SYSFS_BLOCK_ROOT), &mount.source);
+                // Skip a device line that would only repeat the name above.
+                let device = device.filter(|d| {
+                    d.mapper_name.is_some()
+                        || d.disk.is_some()
+                        || !d.slaves.is_empty()
+                        || d.model.is_some()
+                        || !disk.name().to_string_lossy().ends_with(&d.name)
+                        || detail == Detail::Full
+                });
+                match device {
+                    Some(device) => {
+                        output.push_str(&format!("  Device: {}", device.name));
+                        if let Some(mapper) = &device.mapper_name {
+                            output.push_str(&format!(" ({})", mapper));
+                        }
+                        if let Some(parent) = &device.disk {
+                            output.push_str(&format!(" on {}", parent));
+                        }
+                        if !device.slaves.is_empty() {
+                            output.push_str(&format!(" on {}", device.slaves.join(" + ")));
+                        }
+                        if let Some(model) = &device.model {
+                            output.push_str(&format!(", {}", model));
+                        }
+                        output.push('\n');
+                    }
+                    None if mounts::is_network(&fs_type) || detail == Detail::Full => {
+                        output.push_str(&format!("  Source: {}\n", mount.source));
+                    }
+                    None => {}
+                }
+                if detail == Detail::Full {
+                    output.push_str(&format!("  Options: {}\n", mount.options.join(",")));
+                }
+            }
+            if detail == Detail::Full {
+                output.push_str(&format!(
+                    "  Free: {} ({} available to users)\n  Kind: {}\n  Removable: {}\n  Read-only: {}\n",
+                    Self::format_bytes(space.free_bytes),
+                    Self::format_bytes(space.available_bytes),
+                    disk.kind(),
+                    if disk.is_removable() { "yes" } else { "no" },
+                    if disk.is_read_only() { "yes" } else { "no" }
+                ));
+            }
+            output.push('\n');
+        }
+
+        let hidden = all_disks.iter().count() - disks.len();
+        if hidden > 0 {
+            output.push_str(&format!(
+                "{} pseudo filesystem(s) hidden (tmpfs, overlay, ...); set include_pseudo to show them.\n",
+                hidden
+            ));
+        }
+
+        self.report_changes("get_disk_info", "Filesystem", since, snapshot, output)
+    }
+
+    #[rmcp::tool(description = "Find what is filling a disk: walks a directory tree in parallel (one filesystem, \
+        within a time budget) and reports the largest directories and files, calling out cache and build \
+        directories (target/, node_modules, ~/.cache, container images) as reclaimable")]
+    pub async fn find_disk_hogs(
+        &self,
+        Parameters(params): Parameters<DiskHogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let root = PathBuf::from(&params.path);
+        if !root.is_dir() {
+            return Err(McpError::invalid_params(format!("'{}' is not a directory", root.display()), None));
+        }
+        let depth = params.depth.unwrap_or(2).clamp(1, 6);
+        let top_n = params.top_n.unwrap_or(10).clamp(1, 100);
+        let budget = Duration::from_secs(params.timeout_secs.unwrap_or(10).clamp(1, 120));
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get()).min(8);
+
+        let started = std::time::Instant::now();
+        let scan_root = root.clone();
+        let usage = tokio::task::spawn_blocking(move || disk_usage::scan(&scan_root, depth, top_n, budget, threads))
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?
+            .map_err(|e| McpError::internal_error(format!("Cannot scan {}: {}", root.display(), e), None))?;
+        let elapsed = started.elapsed().as_secs_f64();
+        let reclaimable_bytes: u64 = usage.reclaimable.iter().map(|r| r.bytes).sum();
+        let share = |bytes: u64| bytes as f64 / usage.total_bytes.max(1) as f64 * 100.0;
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let mut output = format!(
+                "{}: {} in {} files",
+                root.display(),
+                Self::format_bytes(usage.total_bytes),
+                usage.files
+            );
+            if let Some(dir) = usage.largest_dirs.first() {
+                output.push_str(&format!("; largest {} ({})", dir.path.display(), Self::format_bytes(dir.bytes)));
+            }
+            if reclaimable_bytes > 0 {
+                output.push_str(&format!("; {} reclaimable", Self::format_bytes(reclaimable_bytes)));
+            }
+            if usage.incomplete {
+                output.push_str(" (incomplete, time budget hit)");
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!(
+            "Disk usage under {}: {} in {} files, {} directories ({:.1}s)\n",
+            root.display(),
+            Self::format_bytes(usage.total_bytes),
+            usage.files,
+            usage.dirs,
+            elapsed
+        );
+        if usage.incomplete {
+            output.push_str(&format!(
+                "Stopped after the {}s time budget; sizes are lower bounds.\n",
+                budget.as_secs()
+            ));
+        }
+
+        output.push_str(&format!("\nLargest directories (depth {}):\n", depth));
+        if usage.largest_dirs.is_empty() {
+            output.push_str("  (none)\n");
+        }
+        for dir in &usage.largest_dirs {
+            output.push_str(&format!(
+                "  {:>10}  {:>3.0}%  {}\n",
+                Self::format_bytes(dir.bytes),
+                share(dir.bytes),
+                dir.path.display()
+            ));
+        }
+
+        output.push_str("\nLargest files:\n");
+        if usage.largest_files.is_empty() {
+            output.push_str("  (none)\n");
+        }
+        for file in &usage.largest_files {
+            output.push_str(&format!("  {:>10}  {}\n", Self::format_bytes(file.bytes), file.path.display()));
+        }
+
+        if !usage.reclaimable.is_empty() {
+            output.push_str(&format!(
+                "\nReclaimable ({} total, {:.0}%):\n",
+                Self::format_bytes(reclaimable_bytes),
+                share(reclaimable_bytes)
+            ));
+            let shown = if detail == Detail::Full { usage.reclaimable.len() } else { top_n };
+            for entry in usage.reclaimable.iter().take(shown) {
+                output.push_str(&format!(
+                    "  {:>10}  {} - {}\n",
+                    Self::format_bytes(entry.bytes),
+                    entry.path.display(),
+                    entry.what
+                ));
+            }
+            if usage.reclaimable.len() > shown {
+                output.push_str(&format!("  ... and {} more\n", usage.reclaimable.len() - shown));
+            }
+        }
+
+        if !usage.other_filesystems.is_empty() {
+            let mounts: Vec<_> = usage.other_filesystems.iter().map(|p| p.display().to_string()).collect();
+            if detail == Detail::Full || mounts.len() <= 5 {
+                output.push_str(&format!("\nOther filesystems (not counted): {}\n", mounts.join(", ")));
+            } else {
+                output.push_str(&format!(
+                    "\nOther filesystems (not counted): {}, ... and {} more\n",
+                    mounts[..5].join(", "),
+                    mounts.len() - 5
+                ));
+            }
+        }
+        if usage.unreadable > 0 {
+            output.push_str(&format!("Unreadable directories (not counted): {}\n", usage.unreadable));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get top processes by CPU or memory usage")]
+    pub async fn get_top_processes(
+        &self,
+        Parameters(params): Parameters<TopProcessesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_all();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_all();
+
+        let count = params.count.unwrap_or(10);
+        let sort_by = params.sort_by.unwrap_or_else(|| "cpu".to_string());
+
+        let mut processes: Vec<_> = sys.processes().values().collect();
+
+        match sort_by.as_str() {
+            "memory" | "mem" => {
+                processes.sort_by_key(|p| std::cmp::Reverse(p.memory()));
+            }
+            _ => {
+                processes.sort_by(|a, b| {
+                    b.cpu_usage()
+                        .partial_cmp(&a.cpu_usage())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+            }
+        }
+
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let top: Vec<_> = processes
+                .iter()
+                .take(count.min(5))
+                .map(|p| match sort_by.as_str() {
+                    "memory" | "mem" => format!("{} {}", p.name().to_string_lossy(), Self::format_bytes(p.memory())),
+                    _ => format!("{} {:.1}%", p.name().to_string_lossy(), p.cpu_usage()),
+                })
+                .collect();
+            let output = format!("Top by {}: {}", sort_by, top.join(", "));
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let users = Users::new_with_refreshed_list();
+        let mut output = format!("Top {} processes by {}:\n\n", count, sort_by);
+        output.push_str(&format!(
+            "{:<8} {:<10} {:<10} {}\n",
+            "PID", "CPU%", "Memory", "Name"
+        ));
+        output.push_str(&format!("{:-<50}\n", ""));
+
+        for proc in processes.iter().take(count) {
+            output.push_str(&format!(
+                "{:<8} {:<10.1} {:<10} {}\n",
+                proc.pid(),
+                proc.cpu_usage(),
+                Self::format_bytes(proc.memory()),
+                proc.name().to_string_lossy()
+            ));
+            if detail == Detail::Full {
+                output.push_str(&Self::process_extras(proc, &users));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Find processes by name (case-insensitive, partial match)")]
+    pub async fn find_process(
+        &self,
+        Parameters(params): Parameters<FindProcessParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_all();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_all();
+
+        let search = params.name.to_lowercase();
+        let mut matches: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|p| p.name().to_string_lossy().to_lowercase().contains(&search))
+            .collect();
+
+        matches.sort_by(|a, b| {
             b.cpu_usage()
                 .partial_cmp(&a.cpu_usage())
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        let mut output = String::from("All Running Processes:\n\n");
-        output.push_str(&format!(
-            "{:<8} {:<10} {:<10} {}\n",
-            "PID", "CPU%", "Memory", "Name"
-        ));
-        output.push_str(&format!("{:-<60}\n", ""));
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let scope = format!("find_process:{}", search);
+        let snapshot: Snapshot = matches
+            .iter()
+            .map(|p| {
+                (
+                    format!("{}@{}", p.pid(), p.start_time()),
+                    format!("{} (PID {})", p.name().to_string_lossy(), p.pid()),
+                )
+            })
+            .collect();
+
+        if detail == Detail::Summary {
+            let pids: Vec<_> = matches.iter().take(10).map(|p| p.pid().to_string()).collect();
+            let output = format!(
+                "{} processes match '{}'{}{}",
+                matches.len(),
+                params.name,
+                if pids.is_empty() { "" } else { ": PIDs " },
+                pids.join(", ")
+            );
+            return self.report_changes(&scope, "Process", since, snapshot, output);
+        }
+
+        let users = Users::new_with_refreshed_list();
+        let limit = if detail == Detail::Full { usize::MAX } else { 20 };
+        let mut output = format!("Processes matching '{}':\n\n", params.name);
+
+        if matches.is_empty() {
+            output.push_str("No matching processes found.\n");
+        } else {
+            output.push_str(&format!(
+                "{:<8} {:<10} {:<10} {}\n",
+                "PID", "CPU%", "Memory", "Name"
+            ));
+            output.push_str(&format!("{:-<50}\n", ""));
+
+            for proc in matches.iter().take(limit) {
+                output.push_str(&format!(
+                    "{:<8} {:<10.1} {:<10} {}\n",
+                    proc.pid(),
+                    proc.cpu_usage(),
+                    Self::format_bytes(proc.memory()),
+                    proc.name().to_string_lossy()
+                ));
+                if detail == Detail::Full {
+                    output.push_str(&Self::process_extras(proc, &users));
+                }
+            }
+
+            if matches.len() > limit {
+                output.push_str(&format!("\n... and {} more matches\n", matches.len() - limit));
+            }
+
+            output.push_str(&format!("\nTotal matches: {}\n", matches.len()));
+        }
+
+        self.report_changes(&scope, "Process", since, snapshot, output)
+    }
+
+    #[rmcp::tool(description = "Get detailed information about a specific process by PID")]
+    pub async fn get_process_details(
+        &self,
+        Parameters(params): Parameters<ProcessIdParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_all();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_all();
+
+        let pid = sysinfo::Pid::from_u32(params.pid);
+
+        let proc = sys.process(pid).ok_or_else(|| {
+            McpError::internal_error(format!("Process {} not found", params.pid), None)
+        })?;
+
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let output = format!(
+                "{} (PID {}): {:?}, {:.1}% CPU, {}, up {}",
+                proc.name().to_string_lossy(),
+                params.pid,
+                proc.status(),
+                proc.cpu_usage(),
+                Self::format_bytes(proc.memory()),
+                Self::format_duration(proc.run_time())
+            );
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("Process Details (PID {}):\n\n", params.pid);
+
+        output.push_str(&format!("Name: {}\n", proc.name().to_string_lossy()));
+        output.push_str(&format!("Status: {:?}\n", proc.status()));
+        output.push_str(&format!("CPU Usage: {:.1}%\n", proc.cpu_usage()));
+        output.push_str(&format!("Memory: {}\n", Self::format_bytes(proc.memory())));
+        output.push_str(&format!("Virtual Memory: {}\n", Self::format_bytes(proc.virtual_memory())));
+
+        if let Some(parent) = proc.parent() {
+            output.push_str(&format!("Parent PID: {}\n", parent));
+        }
+
+        let run_time = proc.run_time();
+        output.push_str(&format!("Running for: {}\n", Self::format_duration(run_time)));
+
+        if let Some(exe) = proc.exe() {
+            output.push_str(&format!("Executable: {}\n", exe.display()));
+        }
+
+        if let Some(cwd) = proc.cwd() {
+            output.push_str(&format!("Working Dir: {}\n", cwd.display()));
+        }
+
+        let proc_root = std::path::Path::new(proc_files::PROC_ROOT);
+        if let Ok(fds) = proc_files::read_fds(proc_root, params.pid) {
+            match proc_files::read_nofile_limit(proc_root, params.pid).and_then(|l| l.soft) {
+                Some(soft) => output.push_str(&format!("Open Files: {} / {} (limit)\n", fds.len(), soft)),
+                None => output.push_str(&format!("Open Files: {}\n", fds.len())),
+            }
+        }
+
+        let cmd = proc.cmd();
+        if !cmd.is_empty() {
+            let cmd_str: Vec<_> = cmd.iter().map(|s| s.to_string_lossy()).collect();
+            let cmd_display = cmd_str.join(" ");
+            if cmd_display.len() > 200 && detail != Detail::Full {
+                output.push_str(&format!("Command: {}...\n", &cmd_display[..200]));
+            } else {
+                output.push_str(&format!("Command: {}\n", cmd_display));
+            }
+        }
+
+        if detail == Detail::Full {
+            let users = Users::new_with_refreshed_list();
+            if let Some(user) = proc.user_id().and_then(|uid| users.get_user_by_id(uid)) {
+                output.push_str(&format!("User: {}\n", user.name()));
+            }
+            if let Some(root) = proc.root() {
+                output.push_str(&format!("Root: {}\n", root.display()));
+            }
+            output.push_str(&format!(
+                "Started: {}\n",
+                history::format_timestamp(proc.start_time() as i64)
+            ));
+            let io = proc.disk_usage();
+            output.push_str(&format!(
+                "Disk I/O: {} read, {} written (total)\n",
+                Self::format_bytes(io.total_read_bytes),
+                Self::format_bytes(io.total_written_bytes)
+            ));
+            if let Some(tasks) = proc.tasks() {
+                output.push_str(&format!("Threads: {}\n", tasks.len()));
+            }
+            let env = proc.environ();
+            if !env.is_empty() {
+                output.push_str(&format!("Environment: {} variables\n", env.len()));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List a process's open file descriptors (files, sockets, pipes, devices), \
+        its fd count against the open-file limit, held file locks and memory-mapped libraries. \
+        Use for 'too many open files' and 'who has this file open or locked'")]
+    pub async fn get_process_files(
+        &self,
+        Parameters(params): Parameters<ProcessFilesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let proc_root = std::path::Path::new(proc_files::PROC_ROOT);
+        let fds = proc_files::read_fds(proc_root, params.pid).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                McpError::internal_error(format!("Process {} not found", params.pid), None)
+            }
+            std::io::ErrorKind::PermissionDenied => McpError::internal_error(
+                format!("Cannot read open files of PID {}: permission denied (other users' processes need root)", params.pid),
+                None,
+            ),
+            _ => McpError::internal_error(format!("Failed to read open files of PID {}: {}", params.pid, e), None),
+        })?;
+        let name = std::fs::read_to_string(proc_root.join(params.pid.to_string()).join("comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        let limit = proc_files::read_nofile_limit(proc_root, params.pid).unwrap_or_default();
+        let mapped = proc_files::read_mapped_files(proc_root, params.pid);
+        let locks = proc_files::read_locks(proc_root, params.pid);
+        let libraries: Vec<_> = mapped.iter().filter(|m| proc_files::is_library(&m.path)).collect();
+        let detail = params.detail.unwrap_or_default();
+
+        let count_of = |kind: FdKind| fds.iter().filter(|f| f.kind == kind).count();
+        let usage = limit.soft.filter(|soft| *soft > 0).map(|soft| fds.len() as f64 / soft as f64 * 100.0);
+        let limit_text = match (limit.soft, usage) {
+            (Some(soft), Some(usage)) => format!("{} / {} ({:.0}% of limit)", fds.len(), soft, usage),
+            _ => format!("{} (no limit)", fds.len()),
+        };
+
+        if detail == Detail::Summary {
+            let fd_text = match (limit.soft, usage) {
+                (Some(soft), Some(usage)) => format!("{} fds ({:.0}% of {} limit)", fds.len(), usage, soft),
+                _ => format!("{} fds", fds.len()),
+            };
+            let output = format!(
+                "{} (PID {}): {}, {} files, {} sockets, {} pipes, {} locks, {} libraries",
+                name,
+                params.pid,
+                fd_text,
+                count_of(FdKind::File),
+                count_of(FdKind::Socket),
+                count_of(FdKind::Pipe),
+                locks.len(),
+                libraries.len()
+            );
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("Open Files (PID {}, {}):\n\n", params.pid, name);
+        output.push_str(&format!("File Descriptors: {}\n", limit_text));
+        if let Some(hard) = limit.hard {
+            output.push_str(&format!("Hard Limit: {}\n", hard));
+        }
+        if usage.is_some_and(|u| u >= 80.0) {
+            output.push_str("WARNING: close to the open-file limit; new opens will fail with EMFILE\n");
+        }
+        let counts: Vec<_> = FdKind::ALL
+            .iter()
+            .map(|kind| (kind, count_of(*kind)))
+            .filter(|(_, n)| *n > 0)
+            .map(|(kind, n)| format!("{}: {}", kind.name(), n))
+            .collect();
+        if !counts.is_empty() {
+            output.push_str(&format!("  {}\n", counts.join(", ")));
+        }
+
+        let filter = params.path.as_deref();
+        let matches = |target: &str| filter.is_none_or(|f| target.contains(f));
+
+        // Sockets are described by their address rather than the bare inode.
+        let socket_names: std::collections::HashMap<u64, String> = if count_of(FdKind::Socket) > 0 {
+            sockets::read_sockets(proc_root, &Protocol::ALL)
+                .into_iter()
+                .filter(|s| s.pid == Some(params.pid))
+                .map(|s| {
+                    let mut text = format!("{} {}", s.protocol.name(), s.local);
+                    if let Some(remote) = &s.remote {
+                        text.push_str(&format!(" -> {}", remote));
+                    }
+                    text.push_str(&format!(" {}", s.state));
+                    (s.inode, text)
+                })
+                .collect()
+        } else {
+            std::collections::HashMap::new()
+        };
+        let describe = |f: &proc_files::OpenFile| {
+            let inode = f
+                .target
+                .strip_prefix("socket:[")
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            match inode.and_then(|i| socket_names.get(&i)) {
+                Some(socket) => format!("{} ({})", f.target, socket),
+                None => f.target.clone(),
+            }
+        };
+
+        let limit_rows = if detail == Detail::Full { usize::MAX } else { 50 };
+        let shown_kinds: &[FdKind] = if detail == Detail::Full {
+            &FdKind::ALL
+        } else {
+            &[FdKind::File, FdKind::Device, FdKind::Socket]
+        };
+        for kind in shown_kinds {
+            let rows: Vec<_> = fds
+                .iter()
+                .filter(|f| f.kind == *kind)
+                .map(|f| (f.fd, describe(f)))
+                .filter(|(_, text)| matches(text))
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("\n{} ({}):\n", kind.name(), rows.len()));
+            for (fd, text) in rows.iter().take(limit_rows) {
+                output.push_str(&format!("  {:>5}  {}\n", fd, text));
+            }
+            if rows.len() > limit_rows {
+                output.push_str(&format!("  ... and {} more\n", rows.len() - limit_rows));
+            }
+        }
+
+        if !locks.is_empty() {
+            output.push_str(&format!("\nHeld Locks ({}):\n", locks.len()));
+            for lock in &locks {
+                let file = fds
+                    .iter()
+                    .find(|f| f.inode == Some(lock.inode) && f.kind == FdKind::File)
+                    .map(|f| f.target.clone())
+                    .unwrap_or_else(|| format!("inode {}", lock.inode));
+                output.push_str(&format!("  {} {} {} (bytes {})\n", lock.class, lock.mode, file, lock.range));
+            }
+        }
+
+        let mapped_rows: Vec<_> = if detail == Detail::Full {
+            mapped.iter().filter(|m| matches(&m.path)).collect()
+        } else {
+            libraries.iter().copied().filter(|m| matches(&m.path)).collect()
+        };
+        if !mapped_rows.is_empty() {
+            let title = if detail == Detail::Full { "Mapped Files" } else { "Mapped Libraries" };
+            output.push_str(&format!("\n{} ({}):\n", title, mapped_rows.len()));
+            for m in mapped_rows.iter().take(limit_rows) {
+                output.push_str(&format!("  {:>10}  {}\n", Self::format_bytes(m.size), m.path));
+            }
+            if mapped_rows.len() > limit_rows {
+                output.push_str(&format!("  ... and {} more\n", mapped_rows.len() - limit_rows));
+            }
+        }
+
+        if filter.is_some() && !fds.iter().any(|f| matches(&describe(f))) && !mapped.iter().any(|m| matches(&m.path)) {
+            output.push_str(&format!("\nNothing open matches '{}'\n", filter.unwrap_or_default()));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Query processes by user, name/command-line regex, executable path, cwd prefix, \
+        status (e.g. zombie), minimum CPU/memory and age, with any sort key and chosen columns")]
+    pub async fn query_processes(
+        &self,
+        Parameters(params): Parameters<QueryProcessesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let regex = |pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(regex::Regex::new)
+                .transpose()
+                .map_err(|e| McpError::invalid_params(format!("Invalid regex: {}", e), None))
+        };
+        let statuses = match params.status.as_deref() {
+            Some(status) => Some(process_query::parse_status(status).ok_or_else(|| {
+                McpError::invalid_params(format!("Unknown status '{}'", status), None)
+            })?),
+            None => None,
+        };
+        let query = ProcessQuery {
+            user: params.user.clone(),
+            name: regex(&params.name)?,
+            cmdline: regex(&params.cmdline)?,
+            exe: params.exe.clone(),
+            cwd_prefix: params
+                .cwd
+                .as_deref()
+                .map(|cwd| std::fs::canonicalize(cwd).unwrap_or_else(|_| PathBuf::from(cwd))),
+            statuses,
+            min_cpu: params.min_cpu,
+            min_memory_bytes: params.min_memory_mb.map(|mb| mb * 1024 * 1024),
+            min_age_secs: params.min_age_seconds,
+            max_age_secs: params.max_age_seconds,
+        };
+
+        let sort_key = match params.sort_by.as_deref() {
+            Some(key) => SortKey::parse(key)
+                .ok_or_else(|| McpError::invalid_params(format!("Unknown sort key '{}'", key), None))?,
+            None => SortKey::Cpu,
+        };
+
+        let detail = params.detail.unwrap_or_default();
+        let fields: Vec<&str> = match &params.fields {
+            Some(fields) => {
+                if let Some(unknown) = fields.iter().find(|f| !process_query::FIELDS.contains(&f.as_str())) {
+                    return Err(McpError::invalid_params(
+                        format!("Unknown field '{}'. Known fields: {}", unknown, process_query::FIELDS.join(", ")),
+                        None,
+                    ));
+                }
+                fields.iter().map(String::as_str).collect()
+            }
+            None if detail == Detail::Full => process_query::FIELDS.to_vec(),
+            None => process_query::DEFAULT_FIELDS.to_vec(),
+        };
+        let limit = match (params.limit, detail) {
+            (Some(limit), _) => limit,
+            (None, Detail::Full) => usize::MAX,
+            (None, _) => 50,
+        };
+
+        let mut sys = System::new_all();
+        tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+        sys.refresh_all();
+        let users = Users::new_with_refreshed_list();
+
+        let mut matches: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none() && query.matches(p, &users))
+            .collect();
+        process_query::sort(&mut matches, sort_key, !params.ascending.unwrap_or(false), &users);
+
+        if detail == Detail::Summary {
+            let pids: Vec<_> = matches.iter().take(10).map(|p| p.pid().to_string()).collect();
+            let output = format!(
+                "{} processes match{}{}",
+                matches.len(),
+                if pids.is_empty() { "" } else { ": PIDs " },
+                pids.join(", ")
+            );
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("Matching processes: {}\n\n", matches.len());
+        if matches.is_empty() {
+            output.push_str("No processes match the query.\n");
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let shown = &matches[..matches.len().min(limit)];
+        output.push_str(&process_query::render_table(
+            shown,
+            &fields,
+            &users,
+            Self::format_bytes,
+            Self::format_duration,
+        ));
+        if matches.len() > shown.len() {
+            output.push_str(&format!("\n... and {} more (raise limit)\n", matches.len() - shown.len()));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Show the process tree rooted at a PID (or the whole system) with CPU and \
+        memory aggregated per subtree, e.g. total RAM of a browser or what a build spawned. Kernel threads \
+        are counted but not shown")]
+    pub async fn get_process_tree(
+        &self,
+        Parameters(params): Parameters<ProcessTreeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_all();
+        tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+        sys.refresh_all();
+
+        let tree = ProcessTree::build(&sys);
+        let detail = params.detail.unwrap_or_default();
+        let max_lines = if detail == Detail::Full { usize::MAX } else { 200 };
+
+        let roots: Vec<sysinfo::Pid> = match params.pid {
+            Some(pid) => {
+                let pid = sysinfo::Pid::from_u32(pid);
+                if tree.get(pid).is_none() {
+                    return Err(McpError::invalid_params(format!("Process {} not found", pid), None));
+                }
+                vec![pid]
+            }
+            None => tree.roots().to_vec(),
+        };
+
+        if detail == Detail::Summary {
+            let output = match params.pid.and_then(|_| tree.get(roots[0])) {
+                Some(node) => format!(
+                    "{} (PID {}): {} processes, {:.1}% CPU, {} in total",
+                    node.name,
+                    node.pid,
+                    node.subtree_count,
+                    node.subtree_cpu,
+                    Self::format_bytes(node.subtree_memory)
+                ),
+                None => {
+                    let largest = roots
+                        .iter()
+                        .flat_map(|r| tree.get(*r).map(|n| n.children.clone()).unwrap_or_default())
+                        .filter_map(|pid| tree.get(pid))
+                        .max_by_key(|n| n.subtree_memory)
+                        .map(|n| {
+                            format!(
+                                "; largest subtree {} (PID {}) with {}",
+                                n.name,
+                                n.pid,
+                                Self::format_bytes(n.subtree_memory)
+                            )
+                        })
+                        .unwrap_or_default();
+                    format!("{} processes under {} root(s){}", tree.process_count(), roots.len(), largest)
+                }
+            };
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = match params.pid {
+            Some(pid) => format!("Process Tree (PID {}):\n\n", pid),
+            None => format!(
+                "Process Tree ({} processes, {} kernel threads not shown):\n\n",
+                tree.process_count(),
+                tree.kernel_threads()
+            ),
+        };
+
+        if params.pid.is_some() {
+            let ancestors = tree.ancestors(&sys, roots[0]);
+            if !ancestors.is_empty() {
+                let chain: Vec<_> = ancestors.iter().map(|n| format!("{}({})", n.name, n.pid)).collect();
+                output.push_str(&format!("Ancestors: {}\n\n", chain.join(" > ")));
+            }
+        }
+
+        output.push_str(&tree.render(&roots, params.max_depth, max_lines, Self::format_bytes));
+        output.push('\n');
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List all running processes (sorted by CPU usage)")]
+    pub async fn list_processes(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut sys = System::new_all();
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_all();
+
+        let mut processes: Vec<_> = sys.processes().values().collect();
+        processes.sort_by(|a, b| {
+            b.cpu_usage()
+                .partial_cmp(&a.cpu_usage())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let detail = params.detail.unwrap_or_default();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = processes
+            .iter()
+            .map(|p| {
+                (
+                    format!("{}@{}", p.pid(), p.start_time()),
+                    format!("{} (PID {})", p.name().to_string_lossy(), p.pid()),
+                )
+            })
+            .collect();
+
+        if detail == Detail::Summary {
+            let busiest = processes
+                .first()
+                .map(|p| format!("; busiest {} at {:.1}% CPU", p.name().to_string_lossy(), p.cpu_usage()))
+                .unwrap_or_default();
+            let output = format!("{} processes{}", processes.len(), busiest);
+            return self.report_changes("list_processes", "Process", since, snapshot, output);
+        }
+
+        let users = Users::new_with_refreshed_list();
+        let limit = if detail == Detail::Full { usize::MAX } else { 50 };
+        let mut output = String::from("All Running Processes:\n\n");
+        output.push_str(&format!(
+            "{:<8} {:<10} {:<10} {}\n",
+            "PID", "CPU%", "Memory", "Name"
+        ));
+        output.push_str(&format!("{:-<60}\n", ""));
+
+        for proc in processes.iter().take(limit) {
+            output.push_str(&format!(
+                "{:<8} {:<10.1} {:<10} {}\n",
+                proc.pid(),
+                proc.cpu_usage(),
+                Self::format_bytes(proc.memory()),
+                proc.name().to_string_lossy()
+            ));
+            if detail == Detail::Full {
+                output.push_str(&Self::process_extras(proc, &users));
+            }
+        }
+
+        if processes.len() > limit {
+            output.push_str(&format!("\n... and {} more processes\n", processes.len() - limit));
+        }
+
+        output.push_str(&format!("\nTotal processes: {}\n", processes.len()));
+
+        self.report_changes("list_processes", "Process", since, snapshot, output)
+    }
+
+    #[rmcp::tool(description = "Get network interface throughput (bytes/s, packets/s, errors/s over a short \
+        sampling window) with lifetime counters, plus an upper-bound estimate of which processes are doing network \
+        I/O")]
+    pub async fn get_network_stats(
+        &self,
+        Parameters(params): Parameters<NetworkStatsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let detail = params.detail.unwrap_or_default();
+        let interval = Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000));
+        let proc_root = std::path::Path::new(net_io::PROC_ROOT);
+
+        let sample = |proc_root| match detail {
+            Detail::Summary => Default::default(),
+            _ => (net_io::sample(proc_root), net_io::sample_namespaces(proc_root)),
+        };
+
+        // One window for interfaces and processes, timed from the interface
+        // baseline; each /proc walk takes about as long as the other.
+        let mut networks = Networks::new_with_refreshed_list();
+        let started = std::time::Instant::now();
+        let (before, namespaces_before) = sample(proc_root);
+        tokio::time::sleep(interval).await;
+        networks.refresh(true);
+        let seconds = started.elapsed().as_secs_f64();
+        let (after, namespaces_after) = sample(proc_root);
+        let rate = |count: u64| count as f64 / seconds;
+
+        if detail == Detail::Summary {
+            let (rx, tx) = networks
+                .iter()
+                .fold((0, 0), |(rx, tx), (_, d)| (rx + d.received(), tx + d.transmitted()));
+            let busiest = networks
+                .iter()
+                .max_by_key(|(_, d)| d.received() + d.transmitted())
+                .filter(|(_, d)| d.received() + d.transmitted() > 0)
+                .map(|(name, _)| format!(" (busiest: {})", name))
+                .unwrap_or_default();
+            let output = format!(
+                "{} interfaces, {}/s down, {}/s up{}",
+                networks.iter().count(),
+                Self::format_bytes(rate(rx) as u64),
+                Self::format_bytes(rate(tx) as u64),
+                busiest
+            );
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("Network Interface Statistics (rates over {:.1}s):\n\n", seconds);
+
+        if networks.iter().count() == 0 {
+            output.push_str("No network interfaces found.\n");
+        } else {
+            let mut interfaces: Vec<_> = networks.iter().collect();
+            interfaces.sort_by_key(|(name, _)| name.as_str());
+            for (name, data) in interfaces {
+                output.push_str(&format!("{}:\n", name));
+                output.push_str(&format!(
+                    "  Receive: {}/s, {:.0} packets/s, {:.0} errors/s\n",
+                    Self::format_bytes(rate(data.received()) as u64),
+                    rate(data.packets_received()),
+                    rate(data.errors_on_received())
+                ));
+                output.push_str(&format!(
+                    "  Transmit: {}/s, {:.0} packets/s, {:.0} errors/s\n",
+                    Self::format_bytes(rate(data.transmitted()) as u64),
+                    rate(data.packets_transmitted()),
+                    rate(data.errors_on_transmitted())
+                ));
+                output.push_str(&format!("  Received: {}\n", Self::format_bytes(data.total_received())));
+                output.push_str(&format!("  Transmitted: {}\n", Self::format_bytes(data.total_transmitted())));
+                output.push_str(&format!("  Packets In: {}\n", data.total_packets_received()));
+                output.push_str(&format!("  Packets Out: {}\n", data.total_packets_transmitted()));
+                output.push_str(&format!("  Errors In: {}\n", data.total_errors_on_received()));
+                output.push_str(&format!("  Errors Out: {}\n", data.total_errors_on_transmitted()));
+                if detail == Detail::Full {
+                    output.push_str(&format!("  MAC: {}\n", data.mac_address()));
+                    output.push_str(&format!("  MTU: {}\n", data.mtu()));
+                    for net in data.ip_networks() {
+                        output.push_str(&format!("  Address: {}/{}\n", net.addr, net.prefix));
+                    }
+                }
+                output.push('\n');
+            }
+        }
+
+        let top = params.top.unwrap_or(if detail == Detail::Full { 20 } else { 5 });
+        let mut namespaces: Vec<_> = namespaces_after
+            .iter()
+            .filter_map(|(id, now)| {
+                let then = namespaces_before.get(id)?;
+                Some((
+                    now,
+                    rate(now.received.saturating_sub(then.received)),
+                    rate(now.transmitted.saturating_sub(then.transmitted)),
+                ))
+            })
+            .collect();
+        namespaces.sort_by(|a, b| (b.1 + b.2).partial_cmp(&(a.1 + a.2)).unwrap_or(std::cmp::Ordering::Equal));
+        if !namespaces.is_empty() {
+            output.push_str("Isolated network namespaces (exact, e.g. containers):\n");
+            for (namespace, rx, tx) in namespaces.iter().take(top) {
+                let mut members: Vec<_> = namespace
+                    .processes
+                    .iter()
+                    .map(|(pid, name)| format!("{} ({})", name, pid))
+                    .collect();
+                if members.len() > 3 && detail != Detail::Full {
+                    let more = members.len() - 3;
+                    members.truncate(3);
+                    members.push(format!("+{} more", more));
+                }
+                output.push_str(&format!(
+                    "  in {}/s, out {}/s: {}\n",
+                    Self::format_bytes(*rx as u64),
+                    Self::format_bytes(*tx as u64),
+                    members.join(", ")
+                ));
+            }
+            output.push('\n');
+        }
+
+        let processes = net_io::rates(&before, &after, seconds);
+        output.push_str(
+            "Socket-holding processes by read/write I/O (upper bound: includes cached file I/O and pipes, \
+             misses send/recv):\n",
+        );
+        if processes.is_empty() {
+            output.push_str("  (no activity seen; other users' processes need root)\n");
+        }
+        for p in processes.iter().take(top) {
+            output.push_str(&format!(
+                "  {:>7}  {:<20} in {}/s, out {}/s\n",
+                p.pid,
+                p.name,
+                Self::format_bytes(p.read_per_sec as u64),
+                Self::format_bytes(p.written_per_sec as u64)
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Read all hardware monitor sensors (fan RPMs, voltages, currents, power draw, \
+        temperatures) from /sys/class/hwmon, and CPU package power in watts from RAPL over a short window")]
+    pub async fn get_hwmon(
+        &self,
+        Parameters(params): Parameters<HwmonParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let chips = hwmon::read_chips(std::path::Path::new(hwmon::SYSFS_HWMON_ROOT));
+        let powercap = std::path::Path::new(hwmon::SYSFS_POWERCAP_ROOT);
+        let rapl_before = hwmon::read_rapl(powercap);
+        let rapl_readable = rapl_before.iter().any(|z| z.energy_uj.is_some());
+        let detail = params.detail.unwrap_or_default();
+
+        let mut watts = Vec::new();
+        let mut window = 0.0;
+        if rapl_readable {
+            let interval = Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000));
+            let started = std::time::Instant::now();
+            tokio::time::sleep(interval).await;
+            window = started.elapsed().as_secs_f64();
+            watts = hwmon::rapl_watts(&rapl_before, &hwmon::read_rapl(powercap), window);
+        }
+
+        if detail == Detail::Summary {
+            let readings = || chips.iter().flat_map(|c| c.readings.iter());
+            let mut parts = vec![format!("{} hwmon chip(s)", chips.len())];
+            let fans: Vec<String> = readings()
+                .filter(|r| r.kind == hwmon::SensorKind::Fan)
+                .map(|r| format!("{:.0}", r.value))
+                .collect();
+            if !fans.is_empty() {
+                parts.push(format!("fans {} RPM", fans.join("/")));
+            }
+            let alarms = readings().filter(|r| r.alarm).count();
+            if alarms > 0 {
+                parts.push(format!("{} alarm(s)", alarms));
+            }
+            if let Some((zone, w)) = watts.iter().find(|(zone, _)| !zone.contains('/')) {
+                parts.push(format!("{} {:.1} W", zone, w));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(parts.join(", "))]));
+        }
+
+        let full = detail == Detail::Full;
+        let mut output = String::from("Hardware Monitors:\n\n");
+        if chips.is_empty() {
+            output.push_str("No hwmon sensors found.\n");
+        }
+        for chip in &chips {
+            output.push_str(&format!("{} ({}):\n", chip.name, chip.id));
+            for reading in &chip.readings {
+                output.push_str(&format!("  {}\n", Self::format_hwmon_reading(reading, full)));
+            }
+        }
+
+        if rapl_readable {
+            output.push_str(&format!("\nCPU Power (RAPL, over {:.1}s):\n", window));
+            for (zone, watts) in &watts {
+                output.push_str(&format!("  {}: {:.2} W\n", zone, watts));
+            }
+        } else if !rapl_before.is_empty() {
+            output.push_str("\nCPU Power (RAPL): energy counters are not readable (root only on this kernel)\n");
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get component temperatures (CPU, GPU, etc.)")]
+    pub async fn get_component_temps(
+        &self,
+        Parameters(params): Parameters<DetailParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let components = Components::new_with_refreshed_list();
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let hottest = components
+                .iter()
+                .filter_map(|c| c.temperature().map(|t| (c.label(), t)))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            let output = match hottest {
+                Some((label, temp)) => format!(
+                    "{} sensors; hottest {} at {:.1}°C",
+                    components.iter().count(),
+                    label,
+                    temp
+                ),
+                None => "No temperature sensors found".to_string(),
+            };
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = String::from("Component Temperatures:\n\n");
+
+        if components.iter().count() == 0 {
+            output.push_str("No temperature sensors found.\n");
+        } else {
+            for component in components.iter() {
+                if let Some(temp) = component.temperature() {
+                    output.push_str(&format!("{}: {:.1}°C", component.label(), temp));
+                    if let Some(max) = component.max() {
+                        output.push_str(&format!(" (max: {:.1}°C)", max));
+                    }
+                    if let Some(critical) = component.critical() {
+                        output.push_str(&format!(" (critical: {:.1}°C)", critical));
+                    }
+                    output.push('\n');
+                } else if detail == Detail::Full {
+                    output.push_str(&format!("{}: unreadable\n", component.label()));
+                }
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Diagnose thermals: whether the CPU is throttling right now (throttle counters, \
+        clock vs hardware max under load, thermal zone trip points), which sensor is closest to critical and, \
+        with history enabled, how long the machine has been hot")]
+    pub async fn diagnose_thermals(
+        &self,
+        Parameters(params): Parameters<ThermalParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let cpu_root = std::path::Path::new(cpu::SYSFS_CPU_ROOT);
+        let mut sys = System::new_with_specifics(
+            RefreshKind::nothing().with_cpu(CpuRefreshKind::everything()),
+        );
+        let throttle_before = thermal::read_throttle(cpu_root);
+        let interval = Duration::from_millis(params.interval_ms.unwrap_or(1000).clamp(100, 10_000))
+            .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        let started = std::time::Instant::now();
+        tokio::time::sleep(interval).await;
+        sys.refresh_cpu_all();
+        let window = started.elapsed().as_secs_f64();
+        let throttle_after = thermal::read_throttle(cpu_root);
+        let new_events = throttle_before
+            .zip(throttle_after)
+            .map(|(before, after)| after.events_since(&before));
+
+        let components = Components::new_with_refreshed_list();
+        let sensors: Vec<(String, f64, Option<f64>)> = components
+            .iter()
+            .filter_map(|c| Some((c.label().to_string(), c.temperature()? as f64, c.critical().map(f64::from))))
+            .collect();
+        let hot: Vec<_> = sensors
+            .iter()
+            .filter(|(_, temp, critical)| *temp >= thermal::hot_threshold(*critical))
+            .collect();
+        let closest = sensors
+            .iter()
+            .filter_map(|(label, temp, critical)| critical.map(|c| (label, *temp, c)))
+            .min_by(|a, b| (a.2 - a.1).total_cmp(&(b.2 - b.1)));
+
+        let zones = thermal::read_zones(std::path::Path::new(thermal::SYSFS_THERMAL_ROOT));
+        let tripped: Vec<_> = zones
+            .iter()
+            .filter_map(|z| z.crossed_trips().into_iter().max_by(|a, b| a.temp_c.total_cmp(&b.temp_c)).map(|t| (z, t)))
+            .collect();
+
+        let cpus = sys.cpus();
+        let load = cpus.iter().map(|c| c.cpu_usage() as f64).sum::<f64>() / cpus.len().max(1) as f64;
+        let scaling = cpu::read_scaling(cpu_root);
+        let capped = scaling.values().filter(|s| s.is_capped()).count();
+        let hardware_max_khz = scaling.values().filter_map(|s| s.hardware_max_khz).max();
+        let clock_mhz = cpus.iter().map(|c| c.frequency()).sum::<u64>() / cpus.len().max(1) as u64;
+        let clock_ratio = hardware_max_khz
+            .filter(|hw| *hw > 0 && clock_mhz > 0)
+            .map(|hw| clock_mhz as f64 * 1000.0 / hw as f64);
+        let slow_under_load = load >= thermal::BUSY_LOAD_PERCENT
+            && clock_ratio.is_some_and(|r| r < thermal::SLOW_CLOCK_RATIO);
+
+        let verdict = match (new_events.filter(|n| *n > 0), tripped.first()) {
+            (Some(events), _) => format!("throttling now ({} throttle event(s) in {:.1}s)", events, window),
+            (None, Some((zone, trip))) => format!(
+                "throttling now ({} is past its {} trip point at {:.1}°C)",
+                zone.kind, trip.kind, trip.temp_c
+            ),
+            (None, None) if slow_under_load => format!(
+                "probably throttling (clock at {:.0}% of hardware max under {:.0}% load)",
+                clock_ratio.unwrap_or_default() * 100.0,
+                load
+            ),
+            (None, None) if !hot.is_empty() => format!("hot but not throttling ({} sensor(s) hot)", hot.len()),
+            (None, None) => "OK, not throttling".to_string(),
+        };
+
+        // The oldest start among the sensors that are hot right now.
+        let hot_for = match &self.history {
+            Some(store) if !hot.is_empty() => {
+                let since = hot
+                    .iter()
+                    .filter_map(|(label, _, critical)| {
+                        store
+                            .above_since("temperature_celsius", label, thermal::hot_threshold(*critical))
+                            .ok()
+                            .flatten()
+                    })
+                    .min();
+                Some(match since {
+                    Some(ts) => format!(
+                        "{} (since {})",
+                        Self::format_duration((chrono::Local::now().timestamp() - ts).max(0) as u64),
+                        history::format_timestamp(ts)
+                    ),
+                    None => "just now (no hot samples recorded yet)".to_string(),
+                })
+            }
+            None if !hot.is_empty() => Some("unknown (set RMCP_SENSORS_HISTORY_DB to record temperatures)".to_string()),
+            _ => None,
+        };
+
+        let detail = params.detail.unwrap_or_default();
+        if detail == Detail::Summary {
+            let mut output = format!("thermals: {}", verdict);
+            if let Some((label, temp, critical)) = closest {
+                output.push_str(&format!(
+                    "; closest to critical {} at {:.1}°C ({:.1}°C headroom)",
+                    label,
+                    temp,
+                    critical - temp
+                ));
+            }
+            if let Some(hot_for) = &hot_for {
+                output.push_str(&format!("; hot for {}", hot_for));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = String::from("Thermal Diagnosis:\n\n");
+        output.push_str(&format!("Verdict: {}\n", verdict));
+        match closest {
+            Some((label, temp, critical)) => output.push_str(&format!(
+                "Closest to critical: {} at {:.1}°C (critical {:.1}°C, {:.1}°C headroom)\n",
+                label,
+                temp,
+                critical,
+                critical - temp
+            )),
+            None => output.push_str("Closest to critical: no sensor reports a critical temperature\n"),
+        }
+        if let Some(hot_for) = &hot_for {
+            output.push_str(&format!("Hot for: {}\n", hot_for));
+        }
+
+        output.push_str("\nTemperatures:\n");
+        if sensors.is_empty() {
+            output.push_str("  No temperature sensors found.\n");
+        }
+        for (label, temp, critical) in &sensors {
+            let is_hot = *temp >= thermal::hot_threshold(*critical);
+            if detail != Detail::Full && !is_hot && Some(label) != closest.map(|c| c.0) {
+                continue;
+            }
+            output.push_str(&format!("  {}: {:.1}°C", label, temp));
+            if let Some(critical) = critical {
+                output.push_str(&format!(" (critical: {:.1}°C)", critical));
+            }
+            if is_hot {
+                output.push_str(" [hot]");
+            }
+            output.push('\n');
+        }
+        if detail != Detail::Full && sensors.len() > hot.len() + 1 {
+            output.push_str("  (other sensors are below their hot threshold; use detail 'full' to list them)\n");
+        }
+
+        output.push_str("\nCPU Clock:\n");
+        output.push_str(&format!("  Load: {:.0}% average over {:.1}s\n", load, window));
+        match (hardware_max_khz, clock_ratio) {
+            (Some(hw), Some(ratio)) => output.push_str(&format!(
+                "  Frequency: {} MHz average of {} MHz hardware max ({:.0}%)\n",
+                clock_mhz,
+                hw / 1000,
+                ratio * 100.0
+            )),
+            _ => output.push_str(&format!("  Frequency: {} MHz average (hardware max not exposed)\n", clock_mhz)),
+        }
+        if capped > 0 {
+            output.push_str(&format!("  {} CPU(s) capped below hardware max by the scaling policy\n", capped));
+        }
+
+        output.push_str("\nThrottle Counters:\n");
+        match throttle_after {
+            Some(counters) => {
+                output.push_str(&format!(
+                    "  During sample: {} new event(s)\n",
+                    new_events.unwrap_or_default()
+                ));
+                output.push_str(&format!(
+                    "  Since boot: {} core event(s) ({} throttled), {} package event(s) ({} throttled)\n",
+                    counters.core_events,
+                    Self::format_duration(counters.core_time_ms / 1000),
+                    counters.package_events,
+                    Self::format_duration(counters.package_time_ms / 1000)
+                ));
+            }
+            None => output.push_str("  Not exposed on this system (Intel CPUs only)\n"),
+        }
+
+        output.push_str("\nThermal Zones:\n");
+        if zones.is_empty() {
+            output.push_str("  No thermal zones found.\n");
+        }
+        for zone in &zones {
+            output.push_str(&format!("  {} ({}): ", zone.kind, zone.id));
+            match zone.temp_c {
+                Some(temp) => output.push_str(&format!("{:.1}°C", temp)),
+                None => output.push_str("unreadable"),
+            }
+            let trips: Vec<_> = zone
+                .trips
+                .iter()
+                .filter(|t| detail == Detail::Full || t.kind != "active")
+                .map(|t| format!("{} at {:.1}°C", t.kind, t.temp_c))
+                .collect();
+            if !trips.is_empty() {
+                output.push_str(&format!(", trips: {}", trips.join(", ")));
+            }
+            if !zone.crossed_trips().is_empty() {
+                output.push_str(" [tripped]");
+            } else if let (Some(temp), Some(next)) = (zone.temp_c, zone.next_trip()) {
+                output.push_str(&format!(" ({:.1}°C below {})", next.temp_c - temp, next.kind));
+            }
+            output.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List the user accounts on this machine, system accounts included. \
+        For who is actually logged in, use get_sessions")]
+    pub async fn get_users(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let users = Users::new_with_refreshed_list();
+        let since = params.since.as_deref();
+        let snapshot: Snapshot = users.iter().map(|u| (u.name().to_string(), String::new())).collect();
+
+        if params.detail == Some(Detail::Summary) {
+            let names: Vec<_> = users.iter().map(|u| u.name()).collect();
+            let output = format!("{} users: {}", names.len(), names.join(", "));
+            return self.report_changes("get_users", "User", since, snapshot, output);
+        }
+
+        let mut output = String::from("System Users:\n\n");
+
+        if users.iter().count() == 0 {
+            output.push_str("No users found.\n");
+        } else {
+            for user in users.iter() {
+                output.push_str(&format!("{}\n", user.name()));
+                output.push_str(&format!("  UID: {:?}\n", user.id()));
+                output.push_str(&format!("  GID: {:?}\n", user.group_id()));
+                let groups = user.groups();
+                if !groups.is_empty() {
+                    let group_names: Vec<_> = groups.iter().map(|g| g.name().to_string()).collect();
+                    output.push_str(&format!("  Groups: {}\n", group_names.join(", ")));
+                }
+                output.push('\n');
+            }
+            output.push_str(&format!("Total users: {}\n", users.iter().count()));
+        }
+
+        self.report_changes("get_users", "User", since, snapshot, output)
+    }
+
+    #[rmcp::tool(description = "Get the actual login sessions (logind, or utmp without it): user, TTY, seat, \
+        session type (x11/wayland/tty), remote host for SSH and start time, plus whether this server runs \
+        over SSH or inside tmux/screen, i.e. whether the user is remote")]
+    pub async fn get_sessions(
+        &self,
+        Parameters(params): Parameters<ChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (source, sessions) = Self::systemd_query(sessions::list_sessions).await?;
+        let context = sessions::server_context();
+        let since = params.since.as_deref();
+        let detail = params.detail.unwrap_or_default();
+        let snapshot: Snapshot = sessions
+            .iter()
+            .map(|s| {
+                let key = match (&s.id, &s.tty) {
+                    (Some(id), _) => format!("{} (session {})", s.user, id),
+                    (None, Some(tty)) => format!("{} on {}", s.user, tty),
+                    (None, None) => s.user.clone(),
+                };
+                let value = format!(
+                    "{} {}",
+                    s.state.as_deref().unwrap_or(""),
+                    s.remote_host.as_deref().unwrap_or("")
+                );
+                (key, value.trim().to_string())
+            })
+            .collect();
+
+        let mut server = Vec::new();
+        if context.ssh {
+            server.push(match &context.ssh_client {
+                Some(client) => format!("over SSH from {}", client),
+                None => "over SSH".to_string(),
+            });
+        }
+        if context.mosh {
+            server.push("over mosh".to_string());
+        }
+        if let Some(multiplexer) = context.multiplexer {
+            server.push(format!("inside {}", multiplexer));
+        }
+        let server = if server.is_empty() { "local, not inside SSH, tmux or screen".to_string() } else { server.join(", ") };
+
+        // Greeters, lingering user managers and cron jobs are sessions too.
+        let interactive = |s: &&sessions::Session| s.class.as_deref().is_none_or(|c| c == "user");
+        let shown: Vec<&sessions::Session> = if detail == Detail::Full {
+            sessions.iter().collect()
+        } else {
+            sessions.iter().filter(interactive).collect()
+        };
+
+        if detail == Detail::Summary {
+            let parts: Vec<String> = shown
+                .iter()
+                .map(|s| {
+                    let mut part = s.user.clone();
+                    if let Some(place) = s.tty.as_ref().or(s.seat.as_ref()) {
+                        part.push_str(&format!(" {}", place));
+                    }
+                    if let Some(host) = &s.remote_host {
+                        part.push_str(&format!(" from {}", host));
+                    } else if s.kind != "unspecified" && s.kind != "tty" {
+                        part.push_str(&format!(" {}", s.kind));
+                    }
+                    part
+                })
+                .collect();
+            let output = format!("{} session(s): {}; this server: {}", parts.len(), parts.join(", "), server);
+            return self.report_changes("get_sessions", "Session", since, snapshot, output);
+        }
+
+        let mut output = format!("Login Sessions ({}):\n\n", source.name());
+        if shown.is_empty() {
+            output.push_str("No login sessions.\n\n");
+        }
+        for session in &shown {
+            output.push_str(&session.user);
+            if let Some(id) = &session.id {
+                output.push_str(&format!(" (session {})", id));
+            }
+            if session.current {
+                output.push_str(" [this server]");
+            }
+            output.push('\n');
+            let mut kind = session.kind.clone();
+            if session.remote {
+                kind.push_str(match &session.remote_host {
+                    Some(host) => format!(", remote from {}", host),
+                    None => ", remote".to_string(),
+                }
+                .as_str());
+            }
+            output.push_str(&format!("  Type: {}\n", kind));
+            if let Some(tty) = &session.tty {
+                output.push_str(&format!("  TTY: {}\n", tty));
+            }
+            if let Some(seat) = &session.seat {
+                output.push_str(&format!("  Seat: {}\n", seat));
+            }
+            if let Some(display) = &session.display {
+                output.push_str(&format!("  Display: {}\n", display));
+            }
+            if let Some(state) = &session.state {
+                output.push_str(&format!("  State: {}\n", state));
+            }
+            if let Some(started) = session.started_usec {
+                output.push_str(&format!("  Started: {}\n", Self::relative_time(started, true)));
+            }
+            if detail == Detail::Full {
+                if let Some(uid) = session.uid {
+                    output.push_str(&format!("  UID: {}\n", uid));
+                }
+                if let Some(class) = &session.class {
+                    output.push_str(&format!("  Class: {}\n", class));
+                }
+                if let Some(service) = &session.service {
+                    output.push_str(&format!("  Service: {}\n", service));
+                }
+                if let Some(leader) = session.leader {
+                    output.push_str(&format!("  Leader PID: {}\n", leader));
+                }
+                if let Some(idle) = session.idle_since_usec {
+                    output.push_str(&format!("  Idle since: {}\n", Self::relative_time(idle, true)));
+                }
+            }
+            output.push('\n');
+        }
+
+        let remote = shown.iter().filter(|s| s.remote).count();
+        output.push_str(&format!("Total: {} session(s), {} remote\n", shown.len(), remote));
+        if shown.len() < sessions.len() {
+            output.push_str(&format!(
+                "{} greeter/background session(s) hidden (use detail 'full')\n",
+                sessions.len() - shown.len()
+            ));
+        }
+        output.push_str(&format!("This server: {}\n", server));
+        if detail == Detail::Full {
+            if let Some(id) = &context.session_id {
+                output.push_str(&format!("XDG_SESSION_ID: {}\n", id));
+            }
+        }
+
+        self.report_changes("get_sessions", "Session", since, snapshot, output)
+    }
+
+    // ========================================================================
+    // CONTAINERS
+    // ========================================================================
+
+    #[rmcp::tool(description = "List Docker/Podman containers with image, state, health, published ports and uptime")]
+    pub async fn list_containers(
+        &self,
+        Parameters(params): Parameters<ListContainersParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let engine = Self::container_engine()?;
+        let all = params.all.unwrap_or(false);
+        let containers: Vec<ContainerSummary> = engine
+            .get_json(&format!("/containers/json?all={}", all as u8))
+            .await
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let detail = params.detail.unwrap_or_default();
+        let unhealthy = containers.iter().filter(|c| c.health() == Some("unhealthy")).count();
+
+        let snapshot: Snapshot = containers
+            .iter()
+            .map(|c| {
+                let mut description = format!("{} ({}): {}", c.name(), c.image, c.state);
+                if let Some(health) = c.health() {
+                    description.push_str(&format!(", {}", health));
+                }
+                (c.id.clone(), description)
+            })
+            .collect();
+        let scope = format!("list_containers:{}", all);
+
+        if detail == Detail::Summary {
+            let running = containers.iter().filter(|c| c.state == "running").count();
+            let mut output = format!("{} container(s), {} running", containers.len(), running);
+            if unhealthy > 0 {
+                output.push_str(&format!(", {} unhealthy", unhealthy));
+            }
+            return self.report_changes(&scope, "Container", params.since.as_deref(), snapshot, output);
+        }
+
+
+        let mut output = format!("Containers ({}):\n\n", engine.socket().display());
+
+        if containers.is_empty() {
+            output.push_str(if all { "No containers.\n" } else { "No running containers.\n" });
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        for c in &containers {
+            output.push_str(&format!("{} ({})\n", c.name(), c.image));
+            if detail == Detail::Full {
+                output.push_str(&format!("  ID: {}\n", c.id));
+            } else {
+                output.push_str(&format!("  ID: {}\n", c.short_id()));
+            }
+            output.push_str(&format!("  State: {} - {}\n", c.state, c.status));
+            if c.created > 0 {
+                output.push_str(&format!(
+                    "  Created: {} ago\n",
+                    Self::format_duration(now.saturating_sub(c.created) as u64)
+                ));
+            }
+            if let Some(health) = c.health() {
+                output.push_str(&format!("  Health: {}\n", health));
+            }
+            let ports = c.port_list();
+            if !ports.is_empty() {
+                output.push_str(&format!("  Ports: {}\n", ports.join(", ")));
+            }
+            output.push('\n');
+        }
+
+        output.push_str(&format!("Total: {} container(s)", containers.len()));
+        if unhealthy > 0 {
+            output.push_str(&format!(", {} unhealthy", unhealthy));
+        }
+        output.push('\n');
+
+        self.report_changes(&scope, "Container", params.since.as_deref(), snapshot, output)
+    }
+
+    #[rmcp::tool(description = "Get resource usage of running containers (CPU, memory against limit, network, \
+        block I/O, pids), like 'docker stats'")]
+    pub async fn get_container_stats(
+        &self,
+        Parameters(params): Parameters<ContainerStatsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let engine = Self::container_engine()?;
+        let targets: Vec<String> = match &params.container {
+            Some(container) => {
+                Self::check_container_reference(container)?;
+                vec![container.clone()]
+            }
+            None => engine
+                .get_json::<Vec<ContainerSummary>>("/containers/json")
+                .await
+                .map_err(|e| McpError::internal_error(e, None))?
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect(),
+        };
+
+        // The engine takes a second or two per container to compute CPU
+        // deltas, so ask for all of them at once.
+        let mut requests = tokio::task::JoinSet::new();
+        for (i, name) in targets.iter().enumerate() {
+            let engine = engine.clone();
+            let path = format!("/containers/{}/stats?stream=false", name);
+            requests.spawn(async move { (i, engine.get_json::<Stats>(&path).await) });
+        }
+        let mut results = Vec::new();
+        while let Some(result) = requests.join_next().await {
+            if let Ok(result) = result {
+                results.push(result);
+            }
+        }
+        results.sort_by_key(|(i, _)| *i);
+
+        if params.container.is_some() {
+            if let Some((_, Err(e))) = results.first() {
+                return Err(McpError::internal_error(e.clone(), None));
+            }
+        }
+
+        let detail = params.detail.unwrap_or_default();
+        if detail == Detail::Summary {
+            let busiest = results
+                .iter()
+                .filter_map(|(i, r)| Some((&targets[*i], r.as_ref().ok()?.cpu_percent()?)))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            let mut output = format!("{} running container(s)", targets.len());
+            if let Some((name, cpu)) = busiest {
+                output.push_str(&format!(", busiest: {} at {:.1}% CPU", name, cpu));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = String::from("Container Resource Usage:\n\n");
+        if targets.is_empty() {
+            output.push_str("No running containers.\n");
+        }
+        for (i, result) in results {
+            match result {
+                Ok(stats) => {
+                    output.push_str(&format!("{}: {}\n", targets[i], Self::format_container_stats(&stats)));
+                    if detail == Detail::Full {
+                        for (interface, net) in stats.networks.iter().flatten() {
+                            output.push_str(&format!(
+                                "  {}: {} in / {} out\n",
+                                interface,
+                                Self::format_bytes(net.rx_bytes),
+                                Self::format_bytes(net.tx_bytes)
+                            ));
+                        }
+                        if let Some(usage) = stats.memory_stats.usage {
+                            output.push_str(&format!("  Memory incl. cache: {}\n", Self::format_bytes(usage)));
+                        }
+                    }
+                }
+                Err(e) => output.push_str(&format!("{}: {}\n", targets[i], e)),
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "Get the most recent log lines (stdout and stderr) of a container")]
+    pub async fn get_container_logs(
+        &self,
+        Parameters(params): Parameters<ContainerLogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        Self::check_container_reference(&params.container)?;
+        let engine = Self::container_engine()?;
+        let detail = params.detail.unwrap_or_default();
+        let tail = params.tail.unwrap_or(100).clamp(1, 2000);
+        let body = engine
+            .get(&format!(
+                "/containers/{}/logs?stdout=1&stderr=1&tail={}&timestamps={}",
+                params.container,
+                tail,
+                (detail == Detail::Full) as u8
+            ))
+            .await
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let logs = engine::demux_logs(&body);
+
+        if detail == Detail::Summary {
+            let lines: Vec<_> = logs.lines().filter(|l| !l.trim().is_empty()).collect();
+            let output = match lines.last() {
+                Some(last) => format!("{}: {} recent line(s), last: {}", params.container, lines.len(), last),
+                None => format!("{}: no log output", params.container),
+            };
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("Logs of {} (last {} lines):\n\n", params.container, tail);
+        if logs.trim().is_empty() {
+            output.push_str("(no output)\n");
+        } else {
+            output.push_str(&logs);
+            if !logs.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    // ========================================================================
+    // SYSTEMD
+    // ========================================================================
+
+    #[rmcp::tool(description = "List systemd units in the failed state, like 'systemctl --failed'")]
+    pub async fn list_failed_units(
+        &self,
+        Parameters(params): Parameters<FailedUnitsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let bus = Self::systemd_bus(params.user);
+        let units = Self::systemd_query(move || systemd::list_units(bus)).await?;
+        let failed: Vec<_> = units.iter().filter(|u| u.active_state == "failed").collect();
+        let detail = params.detail.unwrap_or_default();
+
+        let snapshot: Snapshot = failed
+            .iter()
+            .map(|u| (u.name.clone(), format!("{} ({})", u.description, u.sub_state)))
+            .collect();
+        let scope = format!("list_failed_units:{}", bus.name());
+
+        if detail == Detail::Summary {
+            let output = match failed.len() {
+                0 => format!("No failed units on the {} manager", bus.name()),
+                n => format!(
+                    "{} failed unit(s) on the {} manager: {}",
+                    n,
+                    bus.name(),
+                    failed.iter().map(|u| u.name.as_str()).collect::<Vec<_>>().join(", ")
+                ),
+            };
+            return self.report_changes(&scope, "Failed unit", params.since.as_deref(), snapshot, output);
+        }
+
+        let mut output = format!("Failed Units ({} manager):\n\n", bus.name());
+        if failed.is_empty() {
+            output.push_str(&format!("No failed units ({} loaded).\n", units.len()));
+        }
+        for unit in &failed {
+            output.push_str(&format!("{} - {} ({})", unit.name, unit.description, unit.sub_state));
+            if unit.load_state != "loaded" {
+                output.push_str(&format!(" [{}]", unit.load_state));
+            }
+            output.push('\n');
+            if detail == Detail::Full && unit.load_state == "loaded" {
+                let name = unit.name.clone();
+                if let Ok(status) = Self::systemd_query(move || systemd::service_status(bus, &name)).await {
+                    if let Some(since) = status.since_usec {
+                        output.push_str(&format!("  Failed: {}\n", Self::relative_time(since, true)));
+                    }
+                    if let Some(result) = &status.result {
+                        output.push_str(&format!("  Result: {}", result));
+                        if let Some(code) = status.exit_status.filter(|code| *code != 0) {
+                            output.push_str(&format!(" (exit status {})", code));
+                        }
+                        output.push('\n');
+                    }
+                }
+            }
+        }
+        if !failed.is_empty() {
+            output.push_str(&format!("\nTotal: {} failed of {} loaded\n", failed.len(), units.len()));
+        }
+
+        self.report_changes(&scope, "Failed unit", params.since.as_deref(), snapshot, output)
+    }
+
+    #[rmcp::tool(description = "Get the status of a systemd service: active state and sub-state since when, main PID, \
+        memory, tasks, restart count and last result, like 'systemctl status'")]
+    pub async fn get_service_status(
+        &self,
+        Parameters(params): Parameters<ServiceStatusParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let bus = Self::systemd_bus(params.user);
+        let name = systemd::unit_name(params.name.trim());
+        let status = Self::systemd_query(move || systemd::service_status(bus, &name)).await?;
+        let detail = params.detail.unwrap_or_default();
+
+        if status.load_state == "not-found" {
+            return Err(McpError::invalid_params(
+                format!("Unit {} not found on the {} manager", status.name, bus.name()),
+                None,
+            ));
+        }
+
+        if detail == Detail::Summary {
+            let mut output = format!("{}: {} ({})", status.name, status.active_state, status.sub_state);
+            if let Some(since) = status.since_usec {
+                output.push_str(&format!(" since {}", Self::relative_time(since, false)));
+            }
+            if let Some(pid) = status.main_pid {
+                output.push_str(&format!(", PID {}", pid));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let full = detail == Detail::Full;
+        let mut output = format!("{} - {}\n", status.name, status.description);
+        output.push_str(&format!("  Loaded: {}", status.load_state));
+        if let Some(path) = &status.fragment_path {
+            output.push_str(&format!(" ({}", path));
+            if let Some(state) = &status.unit_file_state {
+                output.push_str(&format!("; {}", state));
+            }
+            output.push(')');
+        }
+        output.push('\n');
+        output.push_str(&format!("  Active: {} ({})", status.active_state, status.sub_state));
+        if let Some(since) = status.since_usec {
+            output.push_str(&format!(" since {}", Self::relative_time(since, full)));
+        }
+        output.push('\n');
+        if let Some(pid) = status.main_pid {
+            output.push_str(&format!("  Main PID: {}\n", pid));
+        }
+        if let Some(tasks) = status.tasks {
+            output.push_str(&format!("  Tasks: {}\n", tasks));
+        }
+        if let Some(memory) = status.memory {
+            output.push_str(&format!("  Memory: {}\n", Self::format_bytes(memory)));
+        }
+        if let Some(cpu) = status.cpu_nsec {
+            output.push_str(&format!("  CPU: {:.1}s\n", cpu as f64 / 1e9));
+        }
+        if let Some(restarts) = status.restarts {
+            output.push_str(&format!("  Restarts: {}\n", restarts));
+        }
+        if let Some(result) = &status.result {
+            output.push_str(&format!("  Last Result: {}", result));
+            if let Some(code) = status.exit_status.filter(|code| *code != 0) {
+                output.push_str(&format!(" (exit status {})", code));
+            }
+            output.push('\n');
+        }
+        if full {
+            output.push_str(&format!("  Manager: {}\n", bus.name()));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        for proc in processes.iter().take(50) {
-            output.push_str(&format!(
-                "{:<8} {:<10.1} {:<10} {}\n",
-                proc.pid(),
-                proc.cpu_usage(),
-                Self::format_bytes(proc.memory()),
-                proc.name().to_string_lossy()
-            ));
+    #[rmcp::tool(description = "List active systemd timers with when they fire next, when they last fired and the unit \
+        they start, like 'systemctl list-timers'")]
+    pub async fn list_timers(
+        &self,
+        Parameters(params): Parameters<TimersParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let bus = Self::systemd_bus(params.user);
+        let timers = Self::systemd_query(move || systemd::list_timers(bus)).await?;
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let mut output = format!("{} active timer(s)", timers.len());
+            if let Some(timer) = timers.first() {
+                if let Some(next) = timer.next_usec {
+                    output.push_str(&format!(
+                        ", next: {} {}",
+                        timer.name,
+                        Self::relative_time(next, false)
+                    ));
+                }
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
         }
 
-        if processes.len() > 50 {
-            output.push_str(&format!("\n... and {} more processes\n", processes.len() - 50));
+        let full = detail == Detail::Full;
+        let mut output = format!("Timers ({} manager):\n\n", bus.name());
+        if timers.is_empty() {
+            output.push_str("No active timers.\n");
+        }
+        for timer in &timers {
+            output.push_str(&format!("{} -> {}\n", timer.name, timer.unit));
+            match timer.next_usec {
+                Some(next) => output.push_str(&format!("  Next: {}\n", Self::relative_time(next, full))),
+                None => output.push_str("  Next: not scheduled\n"),
+            }
+            if let Some(last) = timer.last_usec {
+                output.push_str(&format!("  Last: {}\n", Self::relative_time(last, full)));
+            }
         }
 
-        output.push_str(&format!("\nTotal processes: {}\n", processes.len()));
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    // ========================================================================
+    // LOGS
+    // ========================================================================
+
+    #[rmcp::tool(description = "Show the most recent log lines from the systemd journal and configured log files, \
+        optionally filtered by unit, priority and time window. Credentials are masked.")]
+    pub async fn tail_logs(
+        &self,
+        Parameters(params): Parameters<TailLogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let lines = params.lines.unwrap_or(50).clamp(1, 1000);
+        let minutes = params.minutes.map(|m| m.min(logs::MAX_WINDOW_MINUTES));
+        let (sources, filter) = Self::log_query(
+            params.source.as_deref(),
+            params.unit.as_deref(),
+            params.priority.as_deref(),
+            minutes,
+            None,
+            lines,
+        )?;
+        let mut title = format!("Last {} log lines", lines);
+        if let Some(minutes) = minutes {
+            title.push_str(&format!(" from the last {}", Self::format_duration(minutes * 60)));
+        }
+        let output = Self::run_log_query(title, sources, filter, params.detail.unwrap_or_default()).await?;
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get network interface I/O statistics (bytes sent/received)")]
-    pub async fn get_network_stats(&self) -> Result<CallToolResult, McpError> {
-        let networks = Networks::new_with_refreshed_list();
+    #[rmcp::tool(description = "Search the systemd journal and configured log files with a regex, newest matches \
+        first kept, optionally filtered by unit, priority and time window. Credentials are masked.")]
+    pub async fn search_logs(
+        &self,
+        Parameters(params): Parameters<SearchLogsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let limit = params.limit.unwrap_or(50).clamp(1, 500);
+        let minutes = params.minutes.unwrap_or(1440).min(logs::MAX_WINDOW_MINUTES);
+        let (sources, filter) = Self::log_query(
+            params.source.as_deref(),
+            params.unit.as_deref(),
+            params.priority.as_deref(),
+            Some(minutes),
+            Some(&params.pattern),
+            limit,
+        )?;
+        let title = format!(
+            "Log lines matching '{}' in the last {}",
+            params.pattern,
+            Self::format_duration(minutes * 60)
+        );
+        let output = Self::run_log_query(title, sources, filter, params.detail.unwrap_or_default()).await?;
 
-        let mut output = String::from("Network Interface Statistics:\n\n");
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        if networks.iter().count() == 0 {
-            output.push_str("No network interfaces found.\n");
+    // ========================================================================
+    // FILES
+    // ========================================================================
+
+    fn format_event_time(time: chrono::DateTime<chrono::Local>) -> String {
+        if time.date_naive() == chrono::Local::now().date_naive() {
+            time.format("%H:%M:%S").to_string()
         } else {
-            for (name, data) in networks.iter() {
-                output.push_str(&format!("{}:\n", name));
-                output.push_str(&format!("  Received: {}\n", Self::format_bytes(data.total_received())));
-                output.push_str(&format!("  Transmitted: {}\n", Self::format_bytes(data.total_transmitted())));
-                output.push_str(&format!("  Packets In: {}\n", data.total_packets_received()));
-                output.push_str(&format!("  Packets Out: {}\n", data.total_packets_transmitted()));
-                output.push_str(&format!("  Errors In: {}\n", data.total_errors_on_received()));
-                output.push_str(&format!("  Errors Out: {}\n", data.total_errors_on_transmitted()));
-                output.push('\n');
+            time.format("%Y-%m-%d %H:%M:%S").to_string()
+        }
+    }
+
+    #[rmcp::tool(description = "List files created, modified or deleted recently in the watched project \
+        directories (RMCP_SENSORS_WATCH_DIRS), newest first, skipping git-ignored paths. Shows what the user \
+        touched lately rather than what differs from HEAD")]
+    pub async fn get_recent_file_activity(
+        &self,
+        Parameters(params): Parameters<FileActivityParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let watcher = self.file_watcher.as_ref().ok_or_else(|| {
+            McpError::internal_error(
+                "File activity watching is disabled. Set RMCP_SENSORS_WATCH_DIRS to the directories to watch.",
+                None,
+            )
+        })?;
+        let watched: Vec<_> = watcher.dirs().iter().map(|d| d.display().to_string()).collect();
+
+        let under = match params.path.as_deref() {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let path = path.canonicalize().unwrap_or(path);
+                if watcher.root_of(&path).is_none() {
+                    return Err(McpError::invalid_params(
+                        format!(
+                            "'{}' is not inside a watched directory (watching: {})",
+                            path.display(),
+                            if watched.is_empty() { "none".to_string() } else { watched.join(", ") }
+                        ),
+                        None,
+                    ));
+                }
+                Some(path)
+            }
+            None => None,
+        };
+
+        let now = chrono::Local::now();
+        let within = file_activity::parse_window(params.within.as_deref().unwrap_or("10m"))
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let since = now - within;
+        let limit = params.limit.unwrap_or(50).clamp(1, 500);
+        let activity = watcher.activity(under.as_deref(), since);
+        let count = |change: Change| activity.iter().filter(|a| a.change == change).count();
+        let window = Self::format_duration(within.num_seconds() as u64);
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let mut output = format!(
+                "{} file(s) touched in the last {}: {} created, {} modified, {} deleted",
+                activity.len(),
+                window,
+                count(Change::Created),
+                count(Change::Modified),
+                count(Change::Deleted)
+            );
+            if let Some(latest) = activity.first() {
+                output.push_str(&format!(
+                    "; latest {} at {}",
+                    latest.path.display(),
+                    Self::format_event_time(latest.last)
+                ));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!(
+            "File activity in the last {} ({}):\n",
+            window,
+            under.as_ref().map(|u| u.display().to_string()).unwrap_or_else(|| watched.join(", "))
+        );
+        if since < watcher.started() {
+            output.push_str(&format!(
+                "Recording started at {}; earlier changes are unknown.\n",
+                Self::format_event_time(watcher.started())
+            ));
+        }
+        output.push('\n');
+
+        if activity.is_empty() {
+            output.push_str("No changes.\n");
+        }
+        let mut current_root = None;
+        for entry in activity.iter().take(limit) {
+            // Paths are shown relative to their watched directory.
+            let root = watcher.root_of(&entry.path);
+            if watched.len() > 1 && root != current_root {
+                if let Some(root) = root {
+                    output.push_str(&format!("{}:\n", root.display()));
+                }
+                current_root = root;
+            }
+            let shown = match (detail, root) {
+                (Detail::Full, _) | (_, None) => entry.path.display().to_string(),
+                (_, Some(root)) => entry.path.strip_prefix(root).unwrap_or(&entry.path).display().to_string(),
+            };
+            output.push_str(&format!(
+                "  {}  {:<8}  {}",
+                Self::format_event_time(entry.last),
+                entry.change.name(),
+                shown
+            ));
+            if detail == Detail::Full {
+                output.push_str(&format!(
+                    " ({} event(s), first at {})",
+                    entry.events,
+                    Self::format_event_time(entry.first)
+                ));
+            } else if entry.change == Change::Modified && entry.events > 1 {
+                output.push_str(&format!(
+                    " ({} writes since {})",
+                    entry.events,
+                    Self::format_event_time(entry.first)
+                ));
             }
+            output.push('\n');
+        }
+        if activity.len() > limit {
+            output.push_str(&format!("  ... and {} more\n", activity.len() - limit));
+        }
+
+        output.push_str(&format!(
+            "\n{} file(s): {} created, {} modified, {} deleted\n",
+            activity.len(),
+            count(Change::Created),
+            count(Change::Modified),
+            count(Change::Deleted)
+        ));
+        if !watcher.failed().is_empty() {
+            let failed: Vec<_> = watcher
+                .failed()
+                .iter()
+                .map(|(dir, reason)| format!("{} ({})", dir.display(), reason))
+                .collect();
+            output.push_str(&format!("Not watched: {}\n", failed.join("; ")));
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get component temperatures (CPU, GPU, etc.)")]
-    pub async fn get_component_temps(&self) -> Result<CallToolResult, McpError> {
-        let components = Components::new_with_refreshed_list();
+    // ========================================================================
+    // HISTORY
+    // ========================================================================
 
-        let mut output = String::from("Component Temperatures:\n\n");
+    #[rmcp::tool(description = "Query recorded sensor history over a time range with aggregation \
+        (e.g. average CPU yesterday afternoon, battery health trend over 90 days). \
+        Requires RMCP_SENSORS_HISTORY_DB to be set.")]
+    pub async fn query_history(
+        &self,
+        Parameters(params): Parameters<HistoryQueryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let store = self.history.as_ref().ok_or_else(|| {
+            McpError::internal_error(
+                "History is disabled. Set RMCP_SENSORS_HISTORY_DB to a database path to enable sampling.",
+                None,
+            )
+        })?;
 
-        if components.iter().count() == 0 {
-            output.push_str("No temperature sensors found.\n");
-        } else {
-            for component in components.iter() {
-                if let Some(temp) = component.temperature() {
-                    output.push_str(&format!("{}: {:.1}°C", component.label(), temp));
-                    if let Some(max) = component.max() {
-                        output.push_str(&format!(" (max: {:.1}°C)", max));
-                    }
-                    if let Some(critical) = component.critical() {
-                        output.push_str(&format!(" (critical: {:.1}°C)", critical));
-                    }
-                    output.push('\n');
+        let unit = Unit::of(&params.metric);
+        if unit.is_none() {
+            let known: Vec<_> = history::METRICS.iter().map(|(name, _, _)| *name).collect();
+            return Err(McpError::invalid_params(
+                format!("Unknown metric '{}'. Known metrics: {}", params.metric, known.join(", ")),
+                None,
+            ));
+        }
+
+        let now = chrono::Local::now().timestamp();
+        let start = history::parse_time(params.start.as_deref().unwrap_or("-24h"), now)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let end = history::parse_time(params.end.as_deref().unwrap_or("now"), now)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        if end < start {
+            return Err(McpError::invalid_params("Range end is before range start", None));
+        }
+
+        let aggregation = match params.aggregation.as_deref() {
+            Some(a) => Aggregation::parse(a).ok_or_else(|| {
+                McpError::invalid_params(format!("Unknown aggregation '{}'", a), None)
+            })?,
+            None => Aggregation::Avg,
+        };
+
+        let bucket_secs = match params.bucket.as_deref() {
+            Some(b) => Some(
+                history::parse_duration(b)
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| McpError::invalid_params(format!("Invalid bucket '{}'", b), None))?,
+            ),
+            None => None,
+        };
+
+        let query = HistoryQuery {
+            metric: params.metric.clone(),
+            key: params.key.clone(),
+            start,
+            end,
+            aggregation,
+            bucket_secs,
+        };
+
+        let result = store
+            .query(&query)
+            .map_err(|e| McpError::internal_error(format!("History query failed: {}", e), None))?;
+        let detail = params.detail.unwrap_or_default();
+        let value_unit = if aggregation == Aggregation::Count { None } else { unit };
+
+        if detail == Detail::Summary {
+            let samples: i64 = result.rows.iter().map(|r| r.samples).sum();
+            let output = match result.rows.as_slice() {
+                [] => format!("{}: no samples in range", params.metric),
+                [row] => format!(
+                    "{} {}: {} ({} samples)",
+                    params.metric,
+                    aggregation.name(),
+                    Self::format_metric(row.value, value_unit),
+                    samples
+                ),
+                rows => {
+                    let values = rows.iter().map(|r| r.value);
+                    let low = values.clone().fold(f64::INFINITY, f64::min);
+                    let high = values.fold(f64::NEG_INFINITY, f64::max);
+                    format!(
+                        "{} {}: {} values from {} to {} ({} samples)",
+                        params.metric,
+                        aggregation.name(),
+                        rows.len(),
+                        Self::format_metric(low, value_unit),
+                        Self::format_metric(high, value_unit),
+                        samples
+                    )
+                }
+            };
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!("History: {} ({})\n", params.metric, aggregation.name());
+        output.push_str(&format!(
+            "Range: {} to {}{}\n\n",
+            history::format_timestamp(start),
+            history::format_timestamp(end),
+            if result.from_rollups { " (hourly rollups)" } else { "" }
+        ));
+
+        if result.rows.is_empty() {
+            output.push_str("No samples recorded in this range.\n");
+            if let Ok((Some(first), Some(last))) = store.coverage() {
+                output.push_str(&format!(
+                    "History covers {} to {}.\n",
+                    history::format_timestamp(first),
+                    history::format_timestamp(last)
+                ));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut current_key: Option<&str> = None;
+
+        for row in &result.rows {
+            if current_key != Some(row.key.as_str()) {
+                if !row.key.is_empty() {
+                    output.push_str(&format!("{}:\n", row.key));
                 }
+                current_key = Some(row.key.as_str());
+            }
+
+            let indent = if row.key.is_empty() { "" } else { "  " };
+            let value = if aggregation == Aggregation::Count {
+                format!("{}", row.value as i64)
+            } else {
+                Self::format_metric(row.value, value_unit)
+            };
+
+            match row.bucket {
+                Some(bucket) => output.push_str(&format!(
+                    "{}{}  {} ({} samples)\n",
+                    indent,
+                    history::format_timestamp(bucket),
+                    value,
+                    row.samples
+                )),
+                None => output.push_str(&format!("{}{} ({} samples)\n", indent, value, row.samples)),
+            }
+        }
+
+        if detail == Detail::Full {
+            if let Ok((Some(first), Some(last))) = store.coverage() {
+                output.push_str(&format!(
+                    "\nHistory covers {} to {}, sampled every {}s.\n",
+                    history::format_timestamp(first),
+                    history::format_timestamp(last),
+                    store.config().interval.as_secs()
+                ));
             }
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[rmcp::tool(description = "Get logged in users")]
-    pub async fn get_users(&self) -> Result<CallToolResult, McpError> {
-        let users = Users::new_with_refreshed_list();
+    // ========================================================================
+    // SERVER
+    // ========================================================================
 
-        let mut output = String::from("System Users:\n\n");
+    #[rmcp::tool(description = "Get sensors server self-telemetry: uptime, per-tool call counts, errors, \
+        latency percentiles, in-flight calls, cache hit rates, own memory/CPU, and which sensors are available")]
+    pub async fn get_server_stats(
+        &self,
+        Parameters(params): Parameters<DetailParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let summaries = self.telemetry.tool_summaries();
+            let output = format!(
+                "Up {}, {} tool calls, {} errors, {} other calls in flight",
+                Self::format_duration(self.telemetry.uptime().as_secs()),
+                summaries.iter().map(|s| s.calls).sum::<u64>(),
+                summaries.iter().map(|s| s.errors).sum::<u64>(),
+                self.telemetry.in_flight().len().saturating_sub(1)
+            );
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
 
-        if users.iter().count() == 0 {
-            output.push_str("No users found.\n");
+        let pid = sysinfo::Pid::from_u32(std::process::id());
+        let mut sys = System::new();
+        let refresh = ProcessRefreshKind::nothing().with_memory().with_cpu();
+        sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh);
+        tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+        sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh);
+
+        let mut output = String::from("Server Statistics:\n\n");
+        output.push_str(&format!("Version: {}\n", env!("CARGO_PKG_VERSION")));
+        output.push_str(&format!(
+            "Uptime: {}\n",
+            Self::format_duration(self.telemetry.uptime().as_secs())
+        ));
+        if let Some(proc) = sys.process(pid) {
+            output.push_str(&format!(
+                "Process: PID {}, RSS {}, CPU {:.1}%\n",
+                pid,
+                Self::format_bytes(proc.memory()),
+                proc.cpu_usage()
+            ));
+        }
+        match &self.history {
+            Some(store) => output.push_str(&format!(
+                "History: recording every {}s to {}\n",
+                store.config().interval.as_secs(),
+                store.config().path.display()
+            )),
+            None => output.push_str("History: disabled\n"),
+        }
+
+        let in_flight = self.telemetry.in_flight();
+        // The stats call itself is always in flight; leave it out.
+        let in_flight: Vec<_> = in_flight.iter().filter(|c| c.name != "get_server_stats").collect();
+        output.push_str("\nIn-flight calls:\n");
+        if in_flight.is_empty() {
+            output.push_str("  (none)\n");
         } else {
-            for user in users.iter() {
-                output.push_str(&format!("{}\n", user.name()));
-                output.push_str(&format!("  UID: {:?}\n", user.id()));
-                output.push_str(&format!("  GID: {:?}\n", user.group_id()));
-                let groups = user.groups();
-                if !groups.is_empty() {
-                    let group_names: Vec<_> = groups.iter().map(|g| g.name().to_string()).collect();
-                    output.push_str(&format!("  Groups: {}\n", group_names.join(", ")));
+            for call in &in_flight {
+                output.push_str(&format!(
+                    "  #{} {} running for {}\n",
+                    call.id,
+                    call.name,
+                    telemetry::format_latency(call.elapsed)
+                ));
+            }
+        }
+
+        let summaries = self.telemetry.tool_summaries();
+        let total_calls: u64 = summaries.iter().map(|s| s.calls).sum();
+        let total_errors: u64 = summaries.iter().map(|s| s.errors).sum();
+        output.push_str(&format!(
+            "\nTool calls: {} total, {} errors\n",
+            total_calls, total_errors
+        ));
+
+        if !summaries.is_empty() {
+            output.push_str(&format!(
+                "{:<24} {:>6} {:>6} {:>6} {:>8} {:>8} {:>8} {:>8}\n",
+                "Tool", "Calls", "Errors", "Cancel", "p50", "p95", "p99", "max"
+            ));
+            output.push_str(&format!("{:-<82}\n", ""));
+            for s in &summaries {
+                output.push_str(&format!(
+                    "{:<24} {:>6} {:>6} {:>6} {:>8} {:>8} {:>8} {:>8}\n",
+                    s.name,
+                    s.calls,
+                    s.errors,
+                    s.cancelled,
+                    telemetry::format_latency(s.p50),
+                    telemetry::format_latency(s.p95),
+                    telemetry::format_latency(s.p99),
+                    telemetry::format_latency(s.max)
+                ));
+            }
+        }
+
+        output.push_str("\nCaches:\n");
+        let caches = self.telemetry.cache_summaries();
+        if caches.is_empty() {
+            output.push_str("  (no lookups yet)\n");
+        }
+        for cache in &caches {
+            output.push_str(&format!(
+                "  {}: {} lookup(s), {:.0}% hit ({} hits, {} misses)\n",
+                cache.name,
+                cache.lookups(),
+                cache.hit_rate().unwrap_or_default(),
+                cache.hits,
+                cache.misses
+            ));
+        }
+
+        output.push_str("\nSensors:\n");
+        for capability in self.capabilities() {
+            output.push_str(&format!("  {}: {}", capability.sensor, capability.status.as_str()));
+            if detail == Detail::Full {
+                output.push_str(&format!(" ({})", capability.reason));
+            }
+            output.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[rmcp::tool(description = "List sensor capabilities on this host: which backends are available, \
+        degraded or unavailable, why, and which tools depend on them")]
+    pub async fn list_capabilities(
+        &self,
+        peer: Peer<RoleServer>,
+        Parameters(params): Parameters<CapabilitiesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let refresh = params.refresh.unwrap_or(false);
+        self.telemetry.record_cache("capability probes", !refresh);
+        if refresh {
+            let fresh = capabilities::probe(self.history.is_some(), self.file_watcher.as_deref()).await;
+            self.set_capabilities(fresh);
+            if self.update_hidden_tools() {
+                if let Err(e) = peer.notify_tool_list_changed().await {
+                    tracing::warn!("Could not notify the client of the new tool list: {}", e);
                 }
-                output.push('\n');
             }
-            output.push_str(&format!("Total users: {}\n", users.iter().count()));
         }
 
+        let capabilities = self.capabilities();
+
+        if params.detail == Some(Detail::Summary) {
+            let sensors = |status: Status| {
+                let names: Vec<_> = capabilities.iter().filter(|c| c.status == status).map(|c| c.sensor).collect();
+                format!("{} {} ({})", names.len(), status.as_str(), names.join(", "))
+            };
+            let output = format!(
+                "{}; {}; {}",
+                sensors(Status::Available),
+                sensors(Status::Degraded),
+                sensors(Status::Unavailable)
+            );
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = String::from("Sensor Capabilities:\n\n");
+
+        for capability in &capabilities {
+            output.push_str(&format!(
+                "{}: {}\n  {}\n",
+                capability.sensor,
+                capability.status.as_str().to_uppercase(),
+                capability.reason
+            ));
+
+            let hidden = capability.tools.iter().any(|t| self.is_hidden(t));
+            output.push_str(&format!(
+                "  Tools: {}{}\n\n",
+                capability.tools.join(", "),
+                if hidden { " (hidden)" } else { "" }
+            ));
+        }
+
+        let count = |status: Status| capabilities.iter().filter(|c| c.status == status).count();
+        output.push_str(&format!(
+            "Summary: {} available, {} degraded, {} unavailable\n",
+            count(Status::Available),
+            count(Status::Degraded),
+            count(Status::Unavailable)
+        ));
+
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
@@ -1471,7 +5992,14 @@ impl SensorsServer {
             .map(|d| d.value.as_str())
             .unwrap_or("Unknown");
 
-        let output = format!(
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let output = format!("{}: {}, {}°F / {}°C", area, desc, current.temp_F, current.temp_C);
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let mut output = format!(
             "Weather for {}:\n\
              Conditions: {}\n\
              Temperature: {}°F / {}°C\n\
@@ -1496,6 +6024,13 @@ impl SensorsServer {
             current.uvIndex
         );
 
+        if detail == Detail::Full {
+            output.push_str(&format!("\nPrecipitation: {} mm", current.precipMM));
+            if let Some(country) = data.nearest_area.first().and_then(|a| a.country.first()) {
+                output.push_str(&format!("\nCountry: {}", country.value));
+            }
+        }
+
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
@@ -1519,6 +6054,20 @@ impl SensorsServer {
             })
             .unwrap_or_else(|| params.location.clone());
 
+        let detail = params.detail.unwrap_or_default();
+
+        if detail == Detail::Summary {
+            let daily: Vec<_> = data
+                .weather
+                .iter()
+                .take(days)
+                .map(|d| format!("{} {}-{}°F", d.date, d.mintempF, d.maxtempF))
+                .collect();
+            let output = format!("{}: {}", area, daily.join(", "));
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+
+        let step = if detail == Detail::Full { 1 } else { 3 };
         let mut output = format!("Forecast for {} ({} days):\n\n", area, days);
 
         for day in data.weather.iter().take(days) {
@@ -1527,7 +6076,7 @@ impl SensorsServer {
                 day.date, day.maxtempF, day.maxtempC, day.mintempF, day.mintempC
             ));
 
-            for hour in day.hourly.iter().step_by(3) {
+            for hour in day.hourly.iter().step_by(step) {
                 let time_hr = hour.time.parse::<u32>().unwrap_or(0) / 100;
                 let desc = hour
                     .weatherDesc
@@ -1546,16 +6095,50 @@ impl SensorsServer {
     }
 }
 
-#[rmcp::tool_handler]
 impl ServerHandler for SensorsServer {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let call = self.telemetry.begin(&request.name);
+        let result = if self.is_hidden(&request.name) {
+            Err(McpError::invalid_params("tool not found", None))
+        } else {
+            self.tool_router.call(ToolCallContext::new(self, request, context)).await
+        };
+        call.finish(matches!(&result, Ok(r) if r.is_error != Some(true)));
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self
+                .tool_router
+                .list_all()
+                .into_iter()
+                .filter(|tool| !self.is_hidden(&tool.name))
+                .collect(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(
                 "Claude Sensors - Cross-platform environmental awareness for AI assistants. \
-                 Provides display, idle, network, USB, battery, bluetooth, git, system, and weather information."
+                 Provides display, idle, network, USB, battery, bluetooth, git, system, container, systemd, log, and weather information."
                     .into(),
             ),
         }
@@ -1572,7 +6155,44 @@ async fn main() -> anyhow::Result<()> {
         .with_writer(std::io::stderr)
         .init();
 
-    let server = SensorsServer::new();
+    let mut server = SensorsServer::new();
+
+    if let Some(config) = HistoryConfig::from_env() {
+        let path = config.path.clone();
+        match HistoryStore::open(config) {
+            Ok(store) => {
+                let store = Arc::new(store);
+                history::spawn_sampler(store.clone());
+                server = server.with_history(store);
+                tracing::info!("Recording metrics history to {}", path.display());
+            }
+            Err(e) => tracing::warn!("Could not open history database {}: {}", path.display(), e),
+        }
+    }
+
+    if let Some(watcher) = FileWatcher::from_env() {
+        for (dir, reason) in watcher.failed() {
+            tracing::warn!("Cannot watch {}: {}", dir.display(), reason);
+        }
+        tracing::info!("Watching {} directories for file activity", watcher.dirs().len());
+        server = server.with_file_watcher(Arc::new(watcher));
+    }
+
+    let capabilities = capabilities::probe(server.history.is_some(), server.file_watcher.as_deref()).await;
+    for capability in &capabilities {
+        tracing::info!(
+            "Sensor {}: {} ({})",
+            capability.sensor,
+            capability.status.as_str(),
+            capability.reason
+        );
+    }
+    server = server.with_capabilities(capabilities);
+
+    if std::env::var("RMCP_SENSORS_HIDE_UNAVAILABLE").is_ok_and(|v| v == "1" || v == "true") {
+        server = server.hide_unavailable_tools();
+    }
+
     let transport = stdio();
 
     tracing::info!("claude-sensors starting...");
@@ -0,0 +1,257 @@
+//! Self-telemetry for the sensors server: per-tool call counts, errors,
+//! latency percentiles, calls that are still in flight and how often the
+//! server's caches answer lookups.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of recent latencies kept per tool for percentile calculation.
+const LATENCY_WINDOW: usize = 1024;
+
+#[derive(Debug, Default)]
+struct ToolStats {
+    calls: u64,
+    errors: u64,
+    cancelled: u64,
+    latencies: VecDeque<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolSummary {
+    pub name: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub cancelled: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheSummary {
+    pub name: &'static str,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheSummary {
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// Share of lookups answered from the cache, in percent.
+    pub fn hit_rate(&self) -> Option<f64> {
+        (self.lookups() > 0).then(|| self.hits as f64 / self.lookups() as f64 * 100.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InFlightCall {
+    pub id: u64,
+    pub name: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub struct Telemetry {
+    started: Instant,
+    next_id: AtomicU64,
+    tools: Mutex<BTreeMap<String, ToolStats>>,
+    in_flight: Mutex<HashMap<u64, (String, Instant)>>,
+    /// Hits and misses per cache.
+    caches: Mutex<BTreeMap<&'static str, (u64, u64)>>,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            next_id: AtomicU64::new(1),
+            tools: Mutex::new(BTreeMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            caches: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Registers the start of a tool call. The returned guard must be
+    /// finished; if it is dropped first the call is counted as cancelled.
+    pub fn begin(&self, name: &str) -> CallGuard<'_> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let start = Instant::now();
+        lock(&self.in_flight).insert(id, (name.to_string(), start));
+        CallGuard {
+            telemetry: self,
+            id,
+            name: name.to_string(),
+            start,
+            finished: false,
+        }
+    }
+
+    fn record(&self, id: u64, name: &str, elapsed: Duration, outcome: Outcome) {
+        lock(&self.in_flight).remove(&id);
+
+        let mut tools = lock(&self.tools);
+        let stats = tools.entry(name.to_string()).or_default();
+        stats.calls += 1;
+        match outcome {
+            Outcome::Ok => {}
+            Outcome::Error => stats.errors += 1,
+            Outcome::Cancelled => stats.cancelled += 1,
+        }
+        if stats.latencies.len() == LATENCY_WINDOW {
+            stats.latencies.pop_front();
+        }
+        stats.latencies.push_back(elapsed);
+    }
+
+    pub fn tool_summaries(&self) -> Vec<ToolSummary> {
+        lock(&self.tools)
+            .iter()
+            .map(|(name, stats)| {
+                let mut sorted: Vec<_> = stats.latencies.iter().copied().collect();
+                sorted.sort();
+                ToolSummary {
+                    name: name.clone(),
+                    calls: stats.calls,
+                    errors: stats.errors,
+                    cancelled: stats.cancelled,
+                    p50: percentile(&sorted, 50.0),
+                    p95: percentile(&sorted, 95.0),
+                    p99: percentile(&sorted, 99.0),
+                    max: sorted.last().copied().unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
+    /// Counts a lookup in one of the server's caches.
+    pub fn record_cache(&self, cache: &'static str, hit: bool) {
+        let mut caches = lock(&self.caches);
+        let (hits, misses) = caches.entry(cache).or_default();
+        if hit {
+            *hits += 1;
+        } else {
+            *misses += 1;
+        }
+    }
+
+    pub fn cache_summaries(&self) -> Vec<CacheSummary> {
+        lock(&self.caches)
+            .iter()
+            .map(|(name, (hits, misses))| CacheSummary {
+                name,
+                hits: *hits,
+                misses: *misses,
+            })
+            .collect()
+    }
+
+    /// Calls that have started but not yet returned, longest-running first.
+    pub fn in_flight(&self) -> Vec<InFlightCall> {
+        let mut calls: Vec<_> = lock(&self.in_flight)
+            .iter()
+            .map(|(id, (name, start))| InFlightCall {
+                id: *id,
+                name: name.clone(),
+                elapsed: start.elapsed(),
+            })
+            .collect();
+        calls.sort_by_key(|c| std::cmp::Reverse(c.elapsed));
+        calls
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Outcome {
+    Ok,
+    Error,
+    Cancelled,
+}
+
+pub struct CallGuard<'a> {
+    telemetry: &'a Telemetry,
+    id: u64,
+    name: String,
+    start: Instant,
+    finished: bool,
+}
+
+impl CallGuard<'_> {
+    pub fn finish(mut self, success: bool) {
+        self.finished = true;
+        let elapsed = self.start.elapsed();
+        tracing::debug!("{} finished in {:?} (success: {})", self.name, elapsed, success);
+        let outcome = if success { Outcome::Ok } else { Outcome::Error };
+        self.telemetry.record(self.id, &self.name, elapsed, outcome);
+    }
+}
+
+impl Drop for CallGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            tracing::debug!("{} cancelled after {:?}", self.name, self.start.elapsed());
+            self.telemetry
+                .record(self.id, &self.name, self.start.elapsed(), Outcome::Cancelled);
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Nearest-rank percentile over an already sorted slice.
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+pub fn format_latency(d: Duration) -> String {
+    let ms = d.as_secs_f64() * 1000.0;
+    if ms < 1.0 {
+        "<1ms".to_string()
+    } else if ms < 1000.0 {
+        format!("{:.0}ms", ms)
+    } else {
+        format!("{:.1}s", ms / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_cache_hits_per_cache() {
+        let telemetry = Telemetry::new();
+        assert!(telemetry.cache_summaries().is_empty());
+        for hit in [true, true, false, true] {
+            telemetry.record_cache("change tokens", hit);
+        }
+        telemetry.record_cache("capability probes", false);
+
+        let caches = telemetry.cache_summaries();
+        let summary: Vec<_> = caches.iter().map(|c| (c.name, c.hits, c.misses, c.hit_rate())).collect();
+        assert_eq!(
+            summary,
+            [("capability probes", 0, 1, Some(0.0)), ("change tokens", 3, 1, Some(75.0))]
+        );
+    }
+}